@@ -21,6 +21,16 @@ trait Scheme {
     }
 
     fn sec_param_name(&self) -> &'static str;
+
+    /// Additional preprocessor symbols defined for this variant
+    fn defines(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Sources of the reference directory that don't belong to this variant
+    fn excluded_sources(&self) -> &'static [&'static str] {
+        &[]
+    }
 }
 
 struct Kyber;
@@ -73,6 +83,36 @@ impl Scheme for Dilithium {
     }
 }
 
+/// Dilithium-AES: the reference code built with `DILITHIUM_USE_AES`, as `PQCgenKAT_sign{2,3,5}aes`
+struct DilithiumAes;
+
+impl Scheme for DilithiumAes {
+    fn alg_name(&self) -> &'static str {
+        "dilithium"
+    }
+
+    fn variant_name(&self, level: u8) -> &'static str {
+        match level {
+            2 => "dilithium2aes",
+            3 => "dilithium3aes",
+            5 => "dilithium5aes",
+            _ => unreachable!(),
+        }
+    }
+
+    fn sec_param_name(&self) -> &'static str {
+        "DILITHIUM_MODE"
+    }
+
+    fn defines(&self) -> &'static [&'static str] {
+        &["DILITHIUM_USE_AES"]
+    }
+
+    fn excluded_sources(&self) -> &'static [&'static str] {
+        &["symmetric-shake.c"]
+    }
+}
+
 fn compile_lib(alg: &dyn Scheme, level: u8) {
     let ref_dir = alg.ref_path();
     rerun_if_changed!(ref_dir.to_str().unwrap());
@@ -101,6 +141,10 @@ fn compile_lib(alg: &dyn Scheme, level: u8) {
         bindings_builder = bindings_builder.clang_arg(format!("-D{symbol}={name}"))
     }
 
+    for symbol in alg.defines() {
+        bindings_builder = bindings_builder.clang_arg(format!("-D{symbol}"))
+    }
+
     for header in glob(format!("{}/**/*.h", ref_dir.to_string_lossy()).as_str())
         .unwrap()
         .map(|p| p.unwrap())
@@ -126,9 +170,10 @@ fn compile_lib(alg: &dyn Scheme, level: u8) {
 
     let mut cc_build = cc::Build::new();
 
-    fn exclude_path(path: &PathBuf) -> bool {
+    let exclude_path = |path: &PathBuf| {
         let file_name = path.file_name().unwrap().to_str().unwrap();
-        !file_name.contains("test")
+        !alg.excluded_sources().contains(&file_name)
+            && !file_name.contains("test")
             && !file_name.contains("genKAT")
             && ![
                 "rng.c",
@@ -138,11 +183,11 @@ fn compile_lib(alg: &dyn Scheme, level: u8) {
                 "randombytes.c",
             ]
             .contains(&file_name)
-    }
+    };
 
     let c_files = glob(format!("{}/**/*.c", ref_dir.to_string_lossy()).as_str())
         .unwrap()
-        .filter_map(|p| p.ok().filter(exclude_path));
+        .filter_map(|p| p.ok().filter(&exclude_path));
 
     cc_build
         .files(c_files)
@@ -166,6 +211,10 @@ fn compile_lib(alg: &dyn Scheme, level: u8) {
         cc_build.define(&sym, Some(repl));
     }
 
+    for symbol in alg.defines() {
+        cc_build.define(symbol, None);
+    }
+
     let lib_name = format!("{}", alg.variant_name(level));
 
     cc_build.compile(format!("lib{lib_name}.a").as_str());
@@ -186,5 +235,6 @@ fn main() {
 
     for level in [2, 3, 5] {
         compile_lib(&Dilithium, level);
+        compile_lib(&DilithiumAes, level);
     }
 }
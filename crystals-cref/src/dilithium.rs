@@ -19,8 +19,30 @@ mod bindings_5 {
     include!(concat!(env!("OUT_DIR"), "/dilithium5_bindings.rs"));
 }
 
+mod bindings_2aes {
+    #![allow(unsafe_code)]
+    #![allow(warnings)]
+    extern crate std;
+    include!(concat!(env!("OUT_DIR"), "/dilithium2aes_bindings.rs"));
+}
+
+mod bindings_3aes {
+    #![allow(unsafe_code)]
+    #![allow(warnings)]
+    extern crate std;
+    include!(concat!(env!("OUT_DIR"), "/dilithium3aes_bindings.rs"));
+}
+
+mod bindings_5aes {
+    #![allow(unsafe_code)]
+    #![allow(warnings)]
+    extern crate std;
+    include!(concat!(env!("OUT_DIR"), "/dilithium5aes_bindings.rs"));
+}
+
 const DILITHIUM_N: usize = bindings_2::N as usize;
 const SEEDBYTES: usize = bindings_2::SEEDBYTES as usize;
+const CRHBYTES: usize = bindings_2::CRHBYTES as usize;
 
 pub type CPoly = [i32; DILITHIUM_N];
 
@@ -97,3 +119,699 @@ pub fn polyveck_invntt_tomont<const K: usize>(pv: &mut [CPoly; K]) {
         _ => unreachable!(),
     }
 }
+
+#[inline(always)]
+pub fn polyt1_pack(r: &mut [u8; 320], a: &CPoly) {
+    #[allow(unsafe_code)]
+    unsafe {
+        bindings_2::pqcrystals_dilithium2_ref_polyt1_pack(r.as_mut_ptr(), a.as_ptr() as _);
+    }
+}
+
+#[inline(always)]
+pub fn polyt1_unpack(r: &mut CPoly, a: &[u8; 320]) {
+    #[allow(unsafe_code)]
+    unsafe {
+        bindings_2::pqcrystals_dilithium2_ref_polyt1_unpack(r.as_mut_ptr() as _, a.as_ptr());
+    }
+}
+
+#[inline(always)]
+pub fn polyt0_pack(r: &mut [u8; 416], a: &CPoly) {
+    #[allow(unsafe_code)]
+    unsafe {
+        bindings_2::pqcrystals_dilithium2_ref_polyt0_pack(r.as_mut_ptr(), a.as_ptr() as _);
+    }
+}
+
+#[inline(always)]
+pub fn polyt0_unpack(r: &mut CPoly, a: &[u8; 416]) {
+    #[allow(unsafe_code)]
+    unsafe {
+        bindings_2::pqcrystals_dilithium2_ref_polyt0_unpack(r.as_mut_ptr() as _, a.as_ptr());
+    }
+}
+
+/// `K` selects the parameter set: 4 => Dilithium2, 6 => Dilithium3, 8 => Dilithium5
+#[inline(always)]
+pub fn polyeta_pack<const K: usize>(r: &mut [u8], a: &CPoly) {
+    #![allow(unsafe_code)]
+    match K {
+        4 => unsafe {
+            assert_eq!(r.len(), bindings_2::POLYETA_PACKEDBYTES as usize);
+            bindings_2::pqcrystals_dilithium2_ref_polyeta_pack(r.as_mut_ptr(), a.as_ptr() as _);
+        },
+        6 => unsafe {
+            assert_eq!(r.len(), bindings_3::POLYETA_PACKEDBYTES as usize);
+            bindings_3::pqcrystals_dilithium3_ref_polyeta_pack(r.as_mut_ptr(), a.as_ptr() as _);
+        },
+        8 => unsafe {
+            assert_eq!(r.len(), bindings_5::POLYETA_PACKEDBYTES as usize);
+            bindings_5::pqcrystals_dilithium5_ref_polyeta_pack(r.as_mut_ptr(), a.as_ptr() as _);
+        },
+        _ => unreachable!(),
+    }
+}
+
+/// `K` selects the parameter set: 4 => Dilithium2, 6 => Dilithium3, 8 => Dilithium5
+#[inline(always)]
+pub fn polyeta_unpack<const K: usize>(r: &mut CPoly, a: &[u8]) {
+    #![allow(unsafe_code)]
+    match K {
+        4 => unsafe {
+            assert_eq!(a.len(), bindings_2::POLYETA_PACKEDBYTES as usize);
+            bindings_2::pqcrystals_dilithium2_ref_polyeta_unpack(r.as_mut_ptr() as _, a.as_ptr());
+        },
+        6 => unsafe {
+            assert_eq!(a.len(), bindings_3::POLYETA_PACKEDBYTES as usize);
+            bindings_3::pqcrystals_dilithium3_ref_polyeta_unpack(r.as_mut_ptr() as _, a.as_ptr());
+        },
+        8 => unsafe {
+            assert_eq!(a.len(), bindings_5::POLYETA_PACKEDBYTES as usize);
+            bindings_5::pqcrystals_dilithium5_ref_polyeta_unpack(r.as_mut_ptr() as _, a.as_ptr());
+        },
+        _ => unreachable!(),
+    }
+}
+
+/// `K` selects the parameter set: 4 => Dilithium2, 6 => Dilithium3, 8 => Dilithium5
+#[inline(always)]
+pub fn polyz_pack<const K: usize>(r: &mut [u8], a: &CPoly) {
+    #![allow(unsafe_code)]
+    match K {
+        4 => unsafe {
+            assert_eq!(r.len(), bindings_2::POLYZ_PACKEDBYTES as usize);
+            bindings_2::pqcrystals_dilithium2_ref_polyz_pack(r.as_mut_ptr(), a.as_ptr() as _);
+        },
+        6 => unsafe {
+            assert_eq!(r.len(), bindings_3::POLYZ_PACKEDBYTES as usize);
+            bindings_3::pqcrystals_dilithium3_ref_polyz_pack(r.as_mut_ptr(), a.as_ptr() as _);
+        },
+        8 => unsafe {
+            assert_eq!(r.len(), bindings_5::POLYZ_PACKEDBYTES as usize);
+            bindings_5::pqcrystals_dilithium5_ref_polyz_pack(r.as_mut_ptr(), a.as_ptr() as _);
+        },
+        _ => unreachable!(),
+    }
+}
+
+/// `K` selects the parameter set: 4 => Dilithium2, 6 => Dilithium3, 8 => Dilithium5
+#[inline(always)]
+pub fn polyz_unpack<const K: usize>(r: &mut CPoly, a: &[u8]) {
+    #![allow(unsafe_code)]
+    match K {
+        4 => unsafe {
+            assert_eq!(a.len(), bindings_2::POLYZ_PACKEDBYTES as usize);
+            bindings_2::pqcrystals_dilithium2_ref_polyz_unpack(r.as_mut_ptr() as _, a.as_ptr());
+        },
+        6 => unsafe {
+            assert_eq!(a.len(), bindings_3::POLYZ_PACKEDBYTES as usize);
+            bindings_3::pqcrystals_dilithium3_ref_polyz_unpack(r.as_mut_ptr() as _, a.as_ptr());
+        },
+        8 => unsafe {
+            assert_eq!(a.len(), bindings_5::POLYZ_PACKEDBYTES as usize);
+            bindings_5::pqcrystals_dilithium5_ref_polyz_unpack(r.as_mut_ptr() as _, a.as_ptr());
+        },
+        _ => unreachable!(),
+    }
+}
+
+/// `K` selects the parameter set: 4 => Dilithium2, 6 => Dilithium3, 8 => Dilithium5
+#[inline(always)]
+pub fn polyw1_pack<const K: usize>(r: &mut [u8], a: &CPoly) {
+    #![allow(unsafe_code)]
+    match K {
+        4 => unsafe {
+            assert_eq!(r.len(), bindings_2::POLYW1_PACKEDBYTES as usize);
+            bindings_2::pqcrystals_dilithium2_ref_polyw1_pack(r.as_mut_ptr(), a.as_ptr() as _);
+        },
+        6 => unsafe {
+            assert_eq!(r.len(), bindings_3::POLYW1_PACKEDBYTES as usize);
+            bindings_3::pqcrystals_dilithium3_ref_polyw1_pack(r.as_mut_ptr(), a.as_ptr() as _);
+        },
+        8 => unsafe {
+            assert_eq!(r.len(), bindings_5::POLYW1_PACKEDBYTES as usize);
+            bindings_5::pqcrystals_dilithium5_ref_polyw1_pack(r.as_mut_ptr(), a.as_ptr() as _);
+        },
+        _ => unreachable!(),
+    }
+}
+
+/// `K` selects the parameter set: 4 => Dilithium2, 6 => Dilithium3, 8 => Dilithium5
+#[inline(always)]
+pub fn poly_uniform_eta<const K: usize>(poly: &mut CPoly, seed: &[u8; CRHBYTES], nonce: u16) {
+    #![allow(unsafe_code)]
+    match K {
+        4 => unsafe {
+            bindings_2::pqcrystals_dilithium2_ref_poly_uniform_eta(
+                poly.as_mut_ptr() as _,
+                seed.as_ptr(),
+                nonce,
+            );
+        },
+        6 => unsafe {
+            bindings_3::pqcrystals_dilithium3_ref_poly_uniform_eta(
+                poly.as_mut_ptr() as _,
+                seed.as_ptr(),
+                nonce,
+            );
+        },
+        8 => unsafe {
+            bindings_5::pqcrystals_dilithium5_ref_poly_uniform_eta(
+                poly.as_mut_ptr() as _,
+                seed.as_ptr(),
+                nonce,
+            );
+        },
+        _ => unreachable!(),
+    }
+}
+
+/// `K` selects the parameter set: 4 => Dilithium2, 6 => Dilithium3, 8 => Dilithium5
+#[inline(always)]
+pub fn poly_uniform_gamma1<const K: usize>(poly: &mut CPoly, seed: &[u8; CRHBYTES], nonce: u16) {
+    #![allow(unsafe_code)]
+    match K {
+        4 => unsafe {
+            bindings_2::pqcrystals_dilithium2_ref_poly_uniform_gamma1(
+                poly.as_mut_ptr() as _,
+                seed.as_ptr(),
+                nonce,
+            );
+        },
+        6 => unsafe {
+            bindings_3::pqcrystals_dilithium3_ref_poly_uniform_gamma1(
+                poly.as_mut_ptr() as _,
+                seed.as_ptr(),
+                nonce,
+            );
+        },
+        8 => unsafe {
+            bindings_5::pqcrystals_dilithium5_ref_poly_uniform_gamma1(
+                poly.as_mut_ptr() as _,
+                seed.as_ptr(),
+                nonce,
+            );
+        },
+        _ => unreachable!(),
+    }
+}
+
+/// `K` selects the parameter set: 4 => Dilithium2, 6 => Dilithium3, 8 => Dilithium5
+#[inline(always)]
+pub fn poly_challenge<const K: usize>(poly: &mut CPoly, seed: &[u8; SEEDBYTES]) {
+    #![allow(unsafe_code)]
+    match K {
+        4 => unsafe {
+            bindings_2::pqcrystals_dilithium2_ref_poly_challenge(
+                poly.as_mut_ptr() as _,
+                seed.as_ptr(),
+            );
+        },
+        6 => unsafe {
+            bindings_3::pqcrystals_dilithium3_ref_poly_challenge(
+                poly.as_mut_ptr() as _,
+                seed.as_ptr(),
+            );
+        },
+        8 => unsafe {
+            bindings_5::pqcrystals_dilithium5_ref_poly_challenge(
+                poly.as_mut_ptr() as _,
+                seed.as_ptr(),
+            );
+        },
+        _ => unreachable!(),
+    }
+}
+
+/// returns `(a1, a0)`
+#[inline(always)]
+pub fn power2round(a: i32) -> (i32, i32) {
+    let mut a0 = 0;
+    #[allow(unsafe_code)]
+    let a1 = unsafe { bindings_2::pqcrystals_dilithium2_ref_power2round(&mut a0, a) };
+    (a1, a0)
+}
+
+/// `K` selects the parameter set: 4 => Dilithium2, 6 => Dilithium3, 8 => Dilithium5
+/// returns `(a1, a0)`
+#[inline(always)]
+pub fn decompose<const K: usize>(a: i32) -> (i32, i32) {
+    #![allow(unsafe_code)]
+    let mut a0 = 0;
+    let a1 = match K {
+        4 => unsafe { bindings_2::pqcrystals_dilithium2_ref_decompose(&mut a0, a) },
+        6 => unsafe { bindings_3::pqcrystals_dilithium3_ref_decompose(&mut a0, a) },
+        8 => unsafe { bindings_5::pqcrystals_dilithium5_ref_decompose(&mut a0, a) },
+        _ => unreachable!(),
+    };
+    (a1, a0)
+}
+
+/// `K` selects the parameter set: 4 => Dilithium2, 6 => Dilithium3, 8 => Dilithium5
+#[inline(always)]
+pub fn make_hint<const K: usize>(a0: i32, a1: i32) -> u32 {
+    #![allow(unsafe_code)]
+    match K {
+        4 => unsafe { bindings_2::pqcrystals_dilithium2_ref_make_hint(a0, a1) },
+        6 => unsafe { bindings_3::pqcrystals_dilithium3_ref_make_hint(a0, a1) },
+        8 => unsafe { bindings_5::pqcrystals_dilithium5_ref_make_hint(a0, a1) },
+        _ => unreachable!(),
+    }
+}
+
+/// `K` selects the parameter set: 4 => Dilithium2, 6 => Dilithium3, 8 => Dilithium5
+#[inline(always)]
+pub fn use_hint<const K: usize>(a: i32, hint: u32) -> i32 {
+    #![allow(unsafe_code)]
+    match K {
+        4 => unsafe { bindings_2::pqcrystals_dilithium2_ref_use_hint(a, hint) },
+        6 => unsafe { bindings_3::pqcrystals_dilithium3_ref_use_hint(a, hint) },
+        8 => unsafe { bindings_5::pqcrystals_dilithium5_ref_use_hint(a, hint) },
+        _ => unreachable!(),
+    }
+}
+
+/// `K` selects the parameter set: 4 => Dilithium2, 6 => Dilithium3, 8 => Dilithium5
+#[inline(always)]
+pub fn polyvec_matrix_expand<const K: usize, const L: usize>(
+    mat: &mut [[CPoly; L]; K],
+    rho: &[u8; SEEDBYTES],
+) {
+    #![allow(unsafe_code)]
+    match K {
+        4 => unsafe {
+            bindings_2::pqcrystals_dilithium2_ref_polyvec_matrix_expand(
+                mat.as_mut_ptr() as _,
+                rho.as_ptr(),
+            );
+        },
+        6 => unsafe {
+            bindings_3::pqcrystals_dilithium3_ref_polyvec_matrix_expand(
+                mat.as_mut_ptr() as _,
+                rho.as_ptr(),
+            );
+        },
+        8 => unsafe {
+            bindings_5::pqcrystals_dilithium5_ref_polyvec_matrix_expand(
+                mat.as_mut_ptr() as _,
+                rho.as_ptr(),
+            );
+        },
+        _ => unreachable!(),
+    }
+}
+
+/// `K` selects the parameter set: 4 => Dilithium2, 6 => Dilithium3, 8 => Dilithium5
+#[inline(always)]
+pub fn polyvec_matrix_pointwise_montgomery<const K: usize, const L: usize>(
+    t: &mut [CPoly; K],
+    mat: &[[CPoly; L]; K],
+    v: &[CPoly; L],
+) {
+    #![allow(unsafe_code)]
+    match K {
+        4 => unsafe {
+            bindings_2::pqcrystals_dilithium2_ref_polyvec_matrix_pointwise_montgomery(
+                t.as_mut_ptr() as _,
+                mat.as_ptr() as _,
+                v.as_ptr() as _,
+            );
+        },
+        6 => unsafe {
+            bindings_3::pqcrystals_dilithium3_ref_polyvec_matrix_pointwise_montgomery(
+                t.as_mut_ptr() as _,
+                mat.as_ptr() as _,
+                v.as_ptr() as _,
+            );
+        },
+        8 => unsafe {
+            bindings_5::pqcrystals_dilithium5_ref_polyvec_matrix_pointwise_montgomery(
+                t.as_mut_ptr() as _,
+                mat.as_ptr() as _,
+                v.as_ptr() as _,
+            );
+        },
+        _ => unreachable!(),
+    }
+}
+
+/// `K` selects the parameter set: 4 => Dilithium2, 6 => Dilithium3, 8 => Dilithium5, and `AES` the
+/// Dilithium-AES variant of it
+///
+/// The `SEEDBYTES` bytes of key generation randomness are drawn from [`crate::randombytes`]
+#[inline(always)]
+pub fn crypto_sign_keypair<const K: usize, const AES: bool>(pk: &mut [u8], sk: &mut [u8]) {
+    #![allow(unsafe_code)]
+    match (K, AES) {
+        (4, false) => unsafe {
+            assert_eq!(pk.len(), bindings_2::CRYPTO_PUBLICKEYBYTES as usize);
+            assert_eq!(sk.len(), bindings_2::CRYPTO_SECRETKEYBYTES as usize);
+            bindings_2::pqcrystals_dilithium2_ref_keypair(pk.as_mut_ptr(), sk.as_mut_ptr());
+        },
+        (4, true) => unsafe {
+            assert_eq!(pk.len(), bindings_2aes::CRYPTO_PUBLICKEYBYTES as usize);
+            assert_eq!(sk.len(), bindings_2aes::CRYPTO_SECRETKEYBYTES as usize);
+            bindings_2aes::pqcrystals_dilithium2aes_ref_keypair(pk.as_mut_ptr(), sk.as_mut_ptr());
+        },
+        (6, false) => unsafe {
+            assert_eq!(pk.len(), bindings_3::CRYPTO_PUBLICKEYBYTES as usize);
+            assert_eq!(sk.len(), bindings_3::CRYPTO_SECRETKEYBYTES as usize);
+            bindings_3::pqcrystals_dilithium3_ref_keypair(pk.as_mut_ptr(), sk.as_mut_ptr());
+        },
+        (6, true) => unsafe {
+            assert_eq!(pk.len(), bindings_3aes::CRYPTO_PUBLICKEYBYTES as usize);
+            assert_eq!(sk.len(), bindings_3aes::CRYPTO_SECRETKEYBYTES as usize);
+            bindings_3aes::pqcrystals_dilithium3aes_ref_keypair(pk.as_mut_ptr(), sk.as_mut_ptr());
+        },
+        (8, false) => unsafe {
+            assert_eq!(pk.len(), bindings_5::CRYPTO_PUBLICKEYBYTES as usize);
+            assert_eq!(sk.len(), bindings_5::CRYPTO_SECRETKEYBYTES as usize);
+            bindings_5::pqcrystals_dilithium5_ref_keypair(pk.as_mut_ptr(), sk.as_mut_ptr());
+        },
+        (8, true) => unsafe {
+            assert_eq!(pk.len(), bindings_5aes::CRYPTO_PUBLICKEYBYTES as usize);
+            assert_eq!(sk.len(), bindings_5aes::CRYPTO_SECRETKEYBYTES as usize);
+            bindings_5aes::pqcrystals_dilithium5aes_ref_keypair(pk.as_mut_ptr(), sk.as_mut_ptr());
+        },
+        _ => unreachable!(),
+    }
+}
+
+/// `K` selects the parameter set: 4 => Dilithium2, 6 => Dilithium3, 8 => Dilithium5, and `AES` the
+/// Dilithium-AES variant of it
+/// returns the signature length
+#[inline(always)]
+pub fn crypto_sign_signature<const K: usize, const AES: bool>(
+    sig: &mut [u8],
+    m: &[u8],
+    sk: &[u8],
+) -> usize {
+    #![allow(unsafe_code)]
+    let mut siglen = 0;
+    match (K, AES) {
+        (4, false) => unsafe {
+            assert_eq!(sig.len(), bindings_2::CRYPTO_BYTES as usize);
+            assert_eq!(sk.len(), bindings_2::CRYPTO_SECRETKEYBYTES as usize);
+            bindings_2::pqcrystals_dilithium2_ref_signature(
+                sig.as_mut_ptr(),
+                &mut siglen,
+                m.as_ptr(),
+                m.len(),
+                sk.as_ptr(),
+            );
+        },
+        (4, true) => unsafe {
+            assert_eq!(sig.len(), bindings_2aes::CRYPTO_BYTES as usize);
+            assert_eq!(sk.len(), bindings_2aes::CRYPTO_SECRETKEYBYTES as usize);
+            bindings_2aes::pqcrystals_dilithium2aes_ref_signature(
+                sig.as_mut_ptr(),
+                &mut siglen,
+                m.as_ptr(),
+                m.len(),
+                sk.as_ptr(),
+            );
+        },
+        (6, false) => unsafe {
+            assert_eq!(sig.len(), bindings_3::CRYPTO_BYTES as usize);
+            assert_eq!(sk.len(), bindings_3::CRYPTO_SECRETKEYBYTES as usize);
+            bindings_3::pqcrystals_dilithium3_ref_signature(
+                sig.as_mut_ptr(),
+                &mut siglen,
+                m.as_ptr(),
+                m.len(),
+                sk.as_ptr(),
+            );
+        },
+        (6, true) => unsafe {
+            assert_eq!(sig.len(), bindings_3aes::CRYPTO_BYTES as usize);
+            assert_eq!(sk.len(), bindings_3aes::CRYPTO_SECRETKEYBYTES as usize);
+            bindings_3aes::pqcrystals_dilithium3aes_ref_signature(
+                sig.as_mut_ptr(),
+                &mut siglen,
+                m.as_ptr(),
+                m.len(),
+                sk.as_ptr(),
+            );
+        },
+        (8, false) => unsafe {
+            assert_eq!(sig.len(), bindings_5::CRYPTO_BYTES as usize);
+            assert_eq!(sk.len(), bindings_5::CRYPTO_SECRETKEYBYTES as usize);
+            bindings_5::pqcrystals_dilithium5_ref_signature(
+                sig.as_mut_ptr(),
+                &mut siglen,
+                m.as_ptr(),
+                m.len(),
+                sk.as_ptr(),
+            );
+        },
+        (8, true) => unsafe {
+            assert_eq!(sig.len(), bindings_5aes::CRYPTO_BYTES as usize);
+            assert_eq!(sk.len(), bindings_5aes::CRYPTO_SECRETKEYBYTES as usize);
+            bindings_5aes::pqcrystals_dilithium5aes_ref_signature(
+                sig.as_mut_ptr(),
+                &mut siglen,
+                m.as_ptr(),
+                m.len(),
+                sk.as_ptr(),
+            );
+        },
+        _ => unreachable!(),
+    }
+    siglen
+}
+
+/// `K` selects the parameter set: 4 => Dilithium2, 6 => Dilithium3, 8 => Dilithium5, and `AES` the
+/// Dilithium-AES variant of it
+/// `sm` receives `signature || m`, returns its length
+#[inline(always)]
+pub fn crypto_sign<const K: usize, const AES: bool>(sm: &mut [u8], m: &[u8], sk: &[u8]) -> usize {
+    #![allow(unsafe_code)]
+    let mut smlen = 0;
+    match (K, AES) {
+        (4, false) => unsafe {
+            assert_eq!(sm.len(), bindings_2::CRYPTO_BYTES as usize + m.len());
+            assert_eq!(sk.len(), bindings_2::CRYPTO_SECRETKEYBYTES as usize);
+            bindings_2::pqcrystals_dilithium2_ref(
+                sm.as_mut_ptr(),
+                &mut smlen,
+                m.as_ptr(),
+                m.len(),
+                sk.as_ptr(),
+            );
+        },
+        (4, true) => unsafe {
+            assert_eq!(sm.len(), bindings_2aes::CRYPTO_BYTES as usize + m.len());
+            assert_eq!(sk.len(), bindings_2aes::CRYPTO_SECRETKEYBYTES as usize);
+            bindings_2aes::pqcrystals_dilithium2aes_ref(
+                sm.as_mut_ptr(),
+                &mut smlen,
+                m.as_ptr(),
+                m.len(),
+                sk.as_ptr(),
+            );
+        },
+        (6, false) => unsafe {
+            assert_eq!(sm.len(), bindings_3::CRYPTO_BYTES as usize + m.len());
+            assert_eq!(sk.len(), bindings_3::CRYPTO_SECRETKEYBYTES as usize);
+            bindings_3::pqcrystals_dilithium3_ref(
+                sm.as_mut_ptr(),
+                &mut smlen,
+                m.as_ptr(),
+                m.len(),
+                sk.as_ptr(),
+            );
+        },
+        (6, true) => unsafe {
+            assert_eq!(sm.len(), bindings_3aes::CRYPTO_BYTES as usize + m.len());
+            assert_eq!(sk.len(), bindings_3aes::CRYPTO_SECRETKEYBYTES as usize);
+            bindings_3aes::pqcrystals_dilithium3aes_ref(
+                sm.as_mut_ptr(),
+                &mut smlen,
+                m.as_ptr(),
+                m.len(),
+                sk.as_ptr(),
+            );
+        },
+        (8, false) => unsafe {
+            assert_eq!(sm.len(), bindings_5::CRYPTO_BYTES as usize + m.len());
+            assert_eq!(sk.len(), bindings_5::CRYPTO_SECRETKEYBYTES as usize);
+            bindings_5::pqcrystals_dilithium5_ref(
+                sm.as_mut_ptr(),
+                &mut smlen,
+                m.as_ptr(),
+                m.len(),
+                sk.as_ptr(),
+            );
+        },
+        (8, true) => unsafe {
+            assert_eq!(sm.len(), bindings_5aes::CRYPTO_BYTES as usize + m.len());
+            assert_eq!(sk.len(), bindings_5aes::CRYPTO_SECRETKEYBYTES as usize);
+            bindings_5aes::pqcrystals_dilithium5aes_ref(
+                sm.as_mut_ptr(),
+                &mut smlen,
+                m.as_ptr(),
+                m.len(),
+                sk.as_ptr(),
+            );
+        },
+        _ => unreachable!(),
+    }
+    smlen
+}
+
+/// `K` selects the parameter set: 4 => Dilithium2, 6 => Dilithium3, 8 => Dilithium5, and `AES` the
+/// Dilithium-AES variant of it
+/// returns `true` if `sig` is a valid signature of `m`
+#[inline(always)]
+pub fn crypto_sign_verify<const K: usize, const AES: bool>(
+    sig: &[u8],
+    m: &[u8],
+    pk: &[u8],
+) -> bool {
+    #![allow(unsafe_code)]
+    let ret = match (K, AES) {
+        (4, false) => unsafe {
+            assert_eq!(pk.len(), bindings_2::CRYPTO_PUBLICKEYBYTES as usize);
+            bindings_2::pqcrystals_dilithium2_ref_verify(
+                sig.as_ptr(),
+                sig.len(),
+                m.as_ptr(),
+                m.len(),
+                pk.as_ptr(),
+            )
+        },
+        (4, true) => unsafe {
+            assert_eq!(pk.len(), bindings_2aes::CRYPTO_PUBLICKEYBYTES as usize);
+            bindings_2aes::pqcrystals_dilithium2aes_ref_verify(
+                sig.as_ptr(),
+                sig.len(),
+                m.as_ptr(),
+                m.len(),
+                pk.as_ptr(),
+            )
+        },
+        (6, false) => unsafe {
+            assert_eq!(pk.len(), bindings_3::CRYPTO_PUBLICKEYBYTES as usize);
+            bindings_3::pqcrystals_dilithium3_ref_verify(
+                sig.as_ptr(),
+                sig.len(),
+                m.as_ptr(),
+                m.len(),
+                pk.as_ptr(),
+            )
+        },
+        (6, true) => unsafe {
+            assert_eq!(pk.len(), bindings_3aes::CRYPTO_PUBLICKEYBYTES as usize);
+            bindings_3aes::pqcrystals_dilithium3aes_ref_verify(
+                sig.as_ptr(),
+                sig.len(),
+                m.as_ptr(),
+                m.len(),
+                pk.as_ptr(),
+            )
+        },
+        (8, false) => unsafe {
+            assert_eq!(pk.len(), bindings_5::CRYPTO_PUBLICKEYBYTES as usize);
+            bindings_5::pqcrystals_dilithium5_ref_verify(
+                sig.as_ptr(),
+                sig.len(),
+                m.as_ptr(),
+                m.len(),
+                pk.as_ptr(),
+            )
+        },
+        (8, true) => unsafe {
+            assert_eq!(pk.len(), bindings_5aes::CRYPTO_PUBLICKEYBYTES as usize);
+            bindings_5aes::pqcrystals_dilithium5aes_ref_verify(
+                sig.as_ptr(),
+                sig.len(),
+                m.as_ptr(),
+                m.len(),
+                pk.as_ptr(),
+            )
+        },
+        _ => unreachable!(),
+    };
+    ret == 0
+}
+
+/// `K` selects the parameter set: 4 => Dilithium2, 6 => Dilithium3, 8 => Dilithium5, and `AES` the
+/// Dilithium-AES variant of it
+///
+/// `m` needs room for `sm.len()` bytes, as the reference implementation clears all of it on failure.
+/// returns the length of the recovered message, or `None` if the signature is invalid
+#[inline(always)]
+pub fn crypto_sign_open<const K: usize, const AES: bool>(
+    m: &mut [u8],
+    sm: &[u8],
+    pk: &[u8],
+) -> Option<usize> {
+    #![allow(unsafe_code)]
+    assert!(m.len() >= sm.len());
+    let mut mlen = 0;
+    let ret = match (K, AES) {
+        (4, false) => unsafe {
+            assert_eq!(pk.len(), bindings_2::CRYPTO_PUBLICKEYBYTES as usize);
+            bindings_2::pqcrystals_dilithium2_ref_open(
+                m.as_mut_ptr(),
+                &mut mlen,
+                sm.as_ptr(),
+                sm.len(),
+                pk.as_ptr(),
+            )
+        },
+        (4, true) => unsafe {
+            assert_eq!(pk.len(), bindings_2aes::CRYPTO_PUBLICKEYBYTES as usize);
+            bindings_2aes::pqcrystals_dilithium2aes_ref_open(
+                m.as_mut_ptr(),
+                &mut mlen,
+                sm.as_ptr(),
+                sm.len(),
+                pk.as_ptr(),
+            )
+        },
+        (6, false) => unsafe {
+            assert_eq!(pk.len(), bindings_3::CRYPTO_PUBLICKEYBYTES as usize);
+            bindings_3::pqcrystals_dilithium3_ref_open(
+                m.as_mut_ptr(),
+                &mut mlen,
+                sm.as_ptr(),
+                sm.len(),
+                pk.as_ptr(),
+            )
+        },
+        (6, true) => unsafe {
+            assert_eq!(pk.len(), bindings_3aes::CRYPTO_PUBLICKEYBYTES as usize);
+            bindings_3aes::pqcrystals_dilithium3aes_ref_open(
+                m.as_mut_ptr(),
+                &mut mlen,
+                sm.as_ptr(),
+                sm.len(),
+                pk.as_ptr(),
+            )
+        },
+        (8, false) => unsafe {
+            assert_eq!(pk.len(), bindings_5::CRYPTO_PUBLICKEYBYTES as usize);
+            bindings_5::pqcrystals_dilithium5_ref_open(
+                m.as_mut_ptr(),
+                &mut mlen,
+                sm.as_ptr(),
+                sm.len(),
+                pk.as_ptr(),
+            )
+        },
+        (8, true) => unsafe {
+            assert_eq!(pk.len(), bindings_5aes::CRYPTO_PUBLICKEYBYTES as usize);
+            bindings_5aes::pqcrystals_dilithium5aes_ref_open(
+                m.as_mut_ptr(),
+                &mut mlen,
+                sm.as_ptr(),
+                sm.len(),
+                pk.as_ptr(),
+            )
+        },
+        _ => unreachable!(),
+    };
+    (ret == 0).then_some(mlen)
+}
@@ -0,0 +1,444 @@
+//! Constant-time AES-256 and the AES-256-CTR stream used by the Dilithium-AES variants
+//!
+//! Up to 4 blocks are encrypted at once in bitsliced representation: the state is held as 8 bit planes of
+//! 64 bits each, and all round functions are evaluated with bitwise operations only, `SubBytes` with the
+//! circuit of Boyar and Peralta. There are no secret-dependent table lookups or branches.
+
+use crate::utils::flatten::{FlattenSlice, FlattenSliceMut};
+
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub const AES_BLOCK_BYTES: usize = 16;
+pub const AES256_KEY_BYTES: usize = 32;
+
+const NUM_ROUNDS: usize = 14;
+
+/// Maximum number of blocks encrypted at once, one per 16-bit lane of the bit planes
+pub const AES_PARALLEL_BLOCKS: usize = 4;
+pub const AES256CTR_BLOCK_BYTES: usize = AES_PARALLEL_BLOCKS * AES_BLOCK_BYTES;
+
+/// Bit planes of up to 64 bytes: bit `i` of `planes[b]` is bit `b` of byte `i`
+/// Within each block, the bytes are ordered by column, i.e. byte `4 * c + r` is in column `c` and row `r`.
+type Planes = [u64; 8];
+
+#[inline(always)]
+fn to_planes(bytes: &[u8]) -> Planes {
+    debug_assert!(bytes.len() <= 64);
+    let mut planes = [0u64; 8];
+    for (i, &byte) in bytes.iter().enumerate() {
+        for (b, plane) in planes.iter_mut().enumerate() {
+            *plane |= (((byte >> b) & 1) as u64) << i;
+        }
+    }
+    planes
+}
+
+#[inline(always)]
+fn from_planes(planes: &Planes, bytes: &mut [u8]) {
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = 0;
+        for (b, plane) in planes.iter().enumerate() {
+            *byte |= (((plane >> i) & 1) as u8) << b;
+        }
+    }
+}
+
+/// Replicate the 16-bit pattern `x` to all 4 lanes
+const fn lanes(x: u64) -> u64 {
+    x * 0x0001_0001_0001_0001
+}
+
+/// Bitsliced AES S-box, with the circuit of J. Boyar and R. Peralta, "A depth-16 circuit for the AES S-box"
+#[rustfmt::skip]
+fn sbox(q: &mut Planes) {
+    let (x0, x1, x2, x3) = (q[7], q[6], q[5], q[4]);
+    let (x4, x5, x6, x7) = (q[3], q[2], q[1], q[0]);
+
+    // top linear transformation
+    let y14 = x3 ^ x5;
+    let y13 = x0 ^ x6;
+    let y9 = x0 ^ x3;
+    let y8 = x0 ^ x5;
+    let t0 = x1 ^ x2;
+    let y1 = t0 ^ x7;
+    let y4 = y1 ^ x3;
+    let y12 = y13 ^ y14;
+    let y2 = y1 ^ x0;
+    let y5 = y1 ^ x6;
+    let y3 = y5 ^ y8;
+    let t1 = x4 ^ y12;
+    let y15 = t1 ^ x5;
+    let y20 = t1 ^ x1;
+    let y6 = y15 ^ x7;
+    let y10 = y15 ^ t0;
+    let y11 = y20 ^ y9;
+    let y7 = x7 ^ y11;
+    let y17 = y10 ^ y11;
+    let y19 = y10 ^ y8;
+    let y16 = t0 ^ y11;
+    let y21 = y13 ^ y16;
+    let y18 = x0 ^ y16;
+
+    // non-linear section
+    let t2 = y12 & y15;
+    let t3 = y3 & y6;
+    let t4 = t3 ^ t2;
+    let t5 = y4 & x7;
+    let t6 = t5 ^ t2;
+    let t7 = y13 & y16;
+    let t8 = y5 & y1;
+    let t9 = t8 ^ t7;
+    let t10 = y2 & y7;
+    let t11 = t10 ^ t7;
+    let t12 = y9 & y11;
+    let t13 = y14 & y17;
+    let t14 = t13 ^ t12;
+    let t15 = y8 & y10;
+    let t16 = t15 ^ t12;
+    let t17 = t4 ^ t14;
+    let t18 = t6 ^ t16;
+    let t19 = t9 ^ t14;
+    let t20 = t11 ^ t16;
+    let t21 = t17 ^ y20;
+    let t22 = t18 ^ y19;
+    let t23 = t19 ^ y21;
+    let t24 = t20 ^ y18;
+
+    let t25 = t21 ^ t22;
+    let t26 = t21 & t23;
+    let t27 = t24 ^ t26;
+    let t28 = t25 & t27;
+    let t29 = t28 ^ t22;
+    let t30 = t23 ^ t24;
+    let t31 = t22 ^ t26;
+    let t32 = t31 & t30;
+    let t33 = t32 ^ t24;
+    let t34 = t23 ^ t33;
+    let t35 = t27 ^ t33;
+    let t36 = t24 & t35;
+    let t37 = t36 ^ t34;
+    let t38 = t27 ^ t36;
+    let t39 = t29 & t38;
+    let t40 = t25 ^ t39;
+
+    let t41 = t40 ^ t37;
+    let t42 = t29 ^ t33;
+    let t43 = t29 ^ t40;
+    let t44 = t33 ^ t37;
+    let t45 = t42 ^ t41;
+    let z0 = t44 & y15;
+    let z1 = t37 & y6;
+    let z2 = t33 & x7;
+    let z3 = t43 & y16;
+    let z4 = t40 & y1;
+    let z5 = t29 & y7;
+    let z6 = t42 & y11;
+    let z7 = t45 & y17;
+    let z8 = t41 & y10;
+    let z9 = t44 & y12;
+    let z10 = t37 & y3;
+    let z11 = t33 & y4;
+    let z12 = t43 & y13;
+    let z13 = t40 & y5;
+    let z14 = t29 & y2;
+    let z15 = t42 & y9;
+    let z16 = t45 & y14;
+    let z17 = t41 & y8;
+
+    // bottom linear transformation
+    let t46 = z15 ^ z16;
+    let t47 = z10 ^ z11;
+    let t48 = z5 ^ z13;
+    let t49 = z9 ^ z10;
+    let t50 = z2 ^ z12;
+    let t51 = z2 ^ z5;
+    let t52 = z7 ^ z8;
+    let t53 = z0 ^ z3;
+    let t54 = z6 ^ z7;
+    let t55 = z16 ^ z17;
+    let t56 = z12 ^ t48;
+    let t57 = t50 ^ t53;
+    let t58 = z4 ^ t46;
+    let t59 = z3 ^ t54;
+    let t60 = t46 ^ t57;
+    let t61 = z14 ^ t57;
+    let t62 = t52 ^ t58;
+    let t63 = t49 ^ t58;
+    let t64 = z4 ^ t59;
+    let t65 = t61 ^ t62;
+    let t66 = z1 ^ t63;
+    let s0 = t59 ^ t63;
+    let s6 = t56 ^ !t62;
+    let s7 = t48 ^ !t60;
+    let t67 = t64 ^ t65;
+    let s3 = t53 ^ t66;
+    let s4 = t51 ^ t66;
+    let s5 = t47 ^ t65;
+    let s1 = t64 ^ !s3;
+    let s2 = t55 ^ !t67;
+
+    *q = [s7, s6, s5, s4, s3, s2, s1, s0];
+}
+
+/// Apply the AES S-box to each byte of `bytes` (at most 64 bytes)
+fn sub_bytes(bytes: &mut [u8]) {
+    let mut q = to_planes(bytes);
+    sbox(&mut q);
+    from_planes(&q, bytes);
+}
+
+/// `ShiftRows`: row `r` of each block is rotated by `r` columns, i.e. by `4 * r` bits within each lane
+#[inline(always)]
+fn shift_rows(q: &mut Planes) {
+    for p in q.iter_mut() {
+        let mut r = *p & lanes(0x1111);
+        for row in 1..4 {
+            let x = *p & lanes(0x1111 << row);
+            let s = 4 * row;
+            let lo = lanes((1 << (16 - s)) - 1);
+            r |= ((x >> s) & lo) | ((x << (16 - s)) & !lo);
+        }
+        *p = r;
+    }
+}
+
+/// Rotate the rows of each column by `n`, i.e. row `r` receives row `r + n`
+#[inline(always)]
+fn rotate_rows(p: u64, n: usize) -> u64 {
+    let lo = lanes(0x1111 * ((1 << (4 - n)) - 1));
+    ((p >> n) & lo) | ((p << (4 - n)) & !lo)
+}
+
+/// `MixColumns`: `a_r' = 2 * (a_r + a_(r+1)) + a_(r+1) + a_(r+2) + a_(r+3)`
+#[inline(always)]
+fn mix_columns(q: &mut Planes) {
+    let r1 = q.map(|p| rotate_rows(p, 1));
+    let t = |k: usize| q[k] ^ r1[k];
+    // multiplication by x modulo x^8 + x^4 + x^3 + x + 1
+    let xt = [
+        t(7),
+        t(0) ^ t(7),
+        t(1),
+        t(2) ^ t(7),
+        t(3) ^ t(7),
+        t(4),
+        t(5),
+        t(6),
+    ];
+    for ((p, xt), r1) in q.iter_mut().zip(xt.iter()).zip(r1.iter()) {
+        *p = xt ^ r1 ^ rotate_rows(*p, 2) ^ rotate_rows(*p, 3);
+    }
+}
+
+#[inline(always)]
+fn add_round_key(q: &mut Planes, rk: &Planes) {
+    for (p, k) in q.iter_mut().zip(rk.iter()) {
+        *p ^= k;
+    }
+}
+
+#[inline(always)]
+fn xtime(x: u8) -> u8 {
+    (x << 1) ^ ((x >> 7) * 0x1b)
+}
+
+/// AES-256 block cipher (encryption only)
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct Aes256 {
+    /// Round keys in bitsliced representation, replicated to all lanes
+    round_keys: [Planes; NUM_ROUNDS + 1],
+}
+
+impl Aes256 {
+    pub fn new(key: &[u8; AES256_KEY_BYTES]) -> Self {
+        let mut w = [[0u8; 4]; 4 * (NUM_ROUNDS + 1)];
+        for (w, k) in w.iter_mut().zip(key.chunks_exact(4)) {
+            w.copy_from_slice(k);
+        }
+        let mut rcon = 1u8;
+        for i in 8..w.len() {
+            let mut t = w[i - 1];
+            if i % 8 == 0 {
+                t.rotate_left(1);
+                sub_bytes(&mut t);
+                t[0] ^= rcon;
+                rcon = xtime(rcon);
+            } else if i % 8 == 4 {
+                sub_bytes(&mut t);
+            }
+            for (j, t) in t.iter().enumerate() {
+                w[i][j] = w[i - 8][j] ^ t;
+            }
+        }
+
+        let mut round_keys = [Planes::default(); NUM_ROUNDS + 1];
+        let mut rk = [[0u8; 4]; 4 * AES_PARALLEL_BLOCKS];
+        for (round_key, w) in round_keys.iter_mut().zip(w.chunks_exact(4)) {
+            for rk in rk.chunks_exact_mut(4) {
+                rk.copy_from_slice(w);
+            }
+            *round_key = to_planes(rk.flatten_slice());
+        }
+        w.zeroize();
+        rk.zeroize();
+        Self { round_keys }
+    }
+
+    /// Encrypt `N <= AES_PARALLEL_BLOCKS` blocks in place
+    pub fn encrypt_blocks<const N: usize>(&self, blocks: &mut [[u8; AES_BLOCK_BYTES]; N]) {
+        debug_assert!(N <= AES_PARALLEL_BLOCKS);
+
+        let mut q = to_planes(blocks.flatten_slice());
+        add_round_key(&mut q, &self.round_keys[0]);
+        for rk in self.round_keys[1..NUM_ROUNDS].iter() {
+            sbox(&mut q);
+            shift_rows(&mut q);
+            mix_columns(&mut q);
+            add_round_key(&mut q, rk);
+        }
+        sbox(&mut q);
+        shift_rows(&mut q);
+        add_round_key(&mut q, &self.round_keys[NUM_ROUNDS]);
+        from_planes(&q, blocks.flatten_slice_mut());
+        q.zeroize();
+    }
+
+    /// Encrypt a single block in place
+    pub fn encrypt_block(&self, block: &mut [u8; AES_BLOCK_BYTES]) {
+        self.encrypt_blocks(core::array::from_mut(block));
+    }
+}
+
+/// AES-256 in counter mode with a 96-bit IV consisting of the little-endian 64-bit `nonce` followed by 4 zero
+/// bytes, and a 32-bit big-endian block counter starting from 0, as used by the round-3 Dilithium-AES and
+/// Kyber-90s reference implementations
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct Aes256Ctr {
+    aes: Aes256,
+    iv: [u8; AES_BLOCK_BYTES],
+    ctr: u32,
+    buf: [[u8; AES_BLOCK_BYTES]; AES_PARALLEL_BLOCKS],
+    pos: usize,
+}
+
+impl Aes256Ctr {
+    pub fn new(key: &[u8; AES256_KEY_BYTES], nonce: u64) -> Self {
+        let mut iv = [0u8; AES_BLOCK_BYTES];
+        iv[..8].copy_from_slice(&nonce.to_le_bytes());
+        Self {
+            aes: Aes256::new(key),
+            iv,
+            ctr: 0,
+            buf: Default::default(),
+            pos: AES256CTR_BLOCK_BYTES,
+        }
+    }
+
+    /// Generate the next `AES256CTR_BLOCK_BYTES` of key stream into `self.buf`
+    #[inline]
+    fn next_blocks(&mut self) {
+        for block in self.buf.iter_mut() {
+            *block = self.iv;
+            block[12..].copy_from_slice(&self.ctr.to_be_bytes());
+            self.ctr = self.ctr.wrapping_add(1);
+        }
+        self.aes.encrypt_blocks(&mut self.buf);
+        self.pos = 0;
+    }
+
+    /// Fill `out` with the next `out.len()` bytes of the key stream
+    pub fn squeeze(&mut self, out: &mut [u8]) {
+        let mut out = out;
+        while !out.is_empty() {
+            if self.pos == AES256CTR_BLOCK_BYTES {
+                self.next_blocks();
+            }
+            let n = out.len().min(AES256CTR_BLOCK_BYTES - self.pos);
+            let (head, tail) = out.split_at_mut(n);
+            head.copy_from_slice(&self.buf.flatten_slice()[self.pos..self.pos + n]);
+            self.pos += n;
+            out = tail;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rand::Rng;
+
+    /// S-box computed from the definition, with the inverse found by exhaustive search
+    fn sbox_ref(x: u8) -> u8 {
+        let mul = |mut a: u8, mut b: u8| {
+            let mut r = 0u8;
+            while b != 0 {
+                if b & 1 == 1 {
+                    r ^= a;
+                }
+                a = xtime(a);
+                b >>= 1;
+            }
+            r
+        };
+        let inv = (1..=255u8).find(|&y| mul(x, y) == 1).unwrap_or(0);
+        0x63 ^ inv
+            ^ inv.rotate_left(1)
+            ^ inv.rotate_left(2)
+            ^ inv.rotate_left(3)
+            ^ inv.rotate_left(4)
+    }
+
+    #[test]
+    fn sbox() {
+        let mut bytes = [0u8; 256];
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        for chunk in bytes.chunks_exact_mut(64) {
+            sub_bytes(chunk);
+        }
+        for (i, &b) in bytes.iter().enumerate() {
+            assert_eq!(b, sbox_ref(i as u8), "S-box mismatch at {:#04x}", i);
+        }
+    }
+
+    #[test]
+    fn aes256_fips197() {
+        let aes = Aes256::new(&hex!(
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"
+        ));
+        let mut block = hex!("00112233445566778899aabbccddeeff");
+        aes.encrypt_block(&mut block);
+        assert_eq!(block, hex!("8ea2b7ca516745bfeafc49904b496089"));
+    }
+
+    #[test]
+    fn aes256ctr_squeeze() {
+        let mut key = [0u8; AES256_KEY_BYTES];
+        for (i, k) in key.iter_mut().enumerate() {
+            *k = i as u8;
+        }
+        let expected = hex!(
+            "d2b5021f9f0cc098bc87d98662967c1723aaae2ada18a3ada263d1ca5916b0f0"
+            "d27d587419b824e8a2618bf6edc11887484466ec78e210b0fdd3bc2501b834d6"
+            "f8884f9081734d55cef8f89383b9bd0b3ee8708e777e24381a7f1525435b8bc2"
+            "1e05dc22"
+        );
+
+        let mut out = [0u8; 100];
+        Aes256Ctr::new(&key, 0x0102).squeeze(&mut out);
+        assert_eq!(out, expected);
+
+        // the key stream does not depend on how it is split into squeezes
+        let mut rng = rand::thread_rng();
+        let mut ctr = Aes256Ctr::new(&key, 0x0102);
+        let mut rest = &mut out[..];
+        while !rest.is_empty() {
+            let (chunk, r) = rest.split_at_mut(rng.gen_range(0..=rest.len()));
+            ctr.squeeze(chunk);
+            rest = r;
+        }
+        assert_eq!(out, expected);
+    }
+}
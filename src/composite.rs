@@ -0,0 +1,293 @@
+//! Composite Dilithium3 + Ed25519 signatures, built like `id-MLDSA65-Ed25519-SHA512` of the IETF LAMPS
+//! composite signatures draft
+//!
+//! Both components sign the message representative
+//! `M' = Prefix || Label || len(ctx) || ctx || SHA-512(M)`, and a composite signature is valid only if both
+//! component signatures are. Keys and signatures are the concatenations of the component encodings, with
+//! the Dilithium part first.
+//!
+//! The draft's ML-DSA-65 is replaced by round 3.1 Dilithium3, which has no context string input and signs
+//! `M'` directly rather than with `ctx = Label`. This is a different algorithm, so it uses its own label
+//! instead of the registered one and is not interoperable with implementations of the draft.
+
+use crate::dilithium::{
+    Dilithium3, Dilithium3PublicKey, Dilithium3SecretKey, Dilithium3Signature, DilithiumError,
+    SEED_BYTES,
+};
+use crate::ed25519::{self, Ed25519, Ed25519Error};
+use crate::sha2::{Sha512, SHA512_DIGEST_BYTES};
+
+pub const PREFIX: &[u8; 32] = b"CompositeAlgorithmSignatures2025";
+/// Not a registered label of the draft
+pub const LABEL: &[u8; 34] = b"KRYSTALS-Dilithium3-Ed25519-SHA512";
+
+pub const MAX_CONTEXT_BYTES: usize = 255;
+pub const MAX_MESSAGE_REPRESENTATIVE_BYTES: usize =
+    PREFIX.len() + LABEL.len() + 1 + MAX_CONTEXT_BYTES + SHA512_DIGEST_BYTES;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompositeError {
+    /// Encoded key or signature has the wrong length
+    InvalidLength,
+    /// Context string is longer than [`MAX_CONTEXT_BYTES`]
+    InvalidContext,
+    /// Signature verification failed
+    InvalidSignature,
+}
+
+impl From<DilithiumError> for CompositeError {
+    fn from(err: DilithiumError) -> Self {
+        match err {
+            DilithiumError::InvalidLength => Self::InvalidLength,
+            _ => Self::InvalidSignature,
+        }
+    }
+}
+
+impl From<Ed25519Error> for CompositeError {
+    fn from(err: Ed25519Error) -> Self {
+        match err {
+            Ed25519Error::InvalidLength => Self::InvalidLength,
+            Ed25519Error::InvalidSignature => Self::InvalidSignature,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompositePublicKey {
+    pub dilithium: Dilithium3PublicKey,
+    pub ed25519: ed25519::PublicKey,
+}
+
+impl CompositePublicKey {
+    pub const BYTES: usize = Dilithium3PublicKey::BYTES + ed25519::PublicKey::BYTES;
+
+    /// Panics if `bytes.len() != Self::BYTES`
+    pub fn write_bytes(&self, bytes: &mut [u8]) {
+        assert_eq!(bytes.len(), Self::BYTES);
+        let (d, e) = bytes.split_at_mut(Dilithium3PublicKey::BYTES);
+        self.dilithium.write_bytes(d);
+        self.ed25519.write_bytes(e);
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CompositeError> {
+        if bytes.len() != Self::BYTES {
+            return Err(CompositeError::InvalidLength);
+        }
+        let (d, e) = bytes.split_at(Dilithium3PublicKey::BYTES);
+        Ok(Self {
+            dilithium: Dilithium3PublicKey::from_bytes(d)?,
+            ed25519: ed25519::PublicKey::from_bytes(e)?,
+        })
+    }
+}
+
+/// Both component secret keys, the Ed25519 one is encoded as its seed
+#[derive(Clone, Default)]
+pub struct CompositeSecretKey {
+    pub dilithium: Dilithium3SecretKey,
+    pub ed25519: ed25519::SecretKey,
+}
+
+impl CompositeSecretKey {
+    pub const BYTES: usize = Dilithium3SecretKey::BYTES + ed25519::SecretKey::BYTES;
+
+    /// Panics if `bytes.len() != Self::BYTES`
+    pub fn write_bytes(&self, bytes: &mut [u8]) {
+        assert_eq!(bytes.len(), Self::BYTES);
+        let (d, e) = bytes.split_at_mut(Dilithium3SecretKey::BYTES);
+        self.dilithium.write_bytes(d);
+        self.ed25519.write_bytes(e);
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CompositeError> {
+        if bytes.len() != Self::BYTES {
+            return Err(CompositeError::InvalidLength);
+        }
+        let (d, e) = bytes.split_at(Dilithium3SecretKey::BYTES);
+        Ok(Self {
+            dilithium: Dilithium3SecretKey::from_bytes(d)?,
+            ed25519: ed25519::SecretKey::from_bytes(e)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompositeSignature {
+    pub dilithium: Dilithium3Signature,
+    pub ed25519: ed25519::Signature,
+}
+
+impl CompositeSignature {
+    pub const BYTES: usize = Dilithium3Signature::BYTES + ed25519::Signature::BYTES;
+
+    /// Panics if `bytes.len() != Self::BYTES`
+    pub fn write_bytes(&self, bytes: &mut [u8]) {
+        assert_eq!(bytes.len(), Self::BYTES);
+        let (d, e) = bytes.split_at_mut(Dilithium3Signature::BYTES);
+        self.dilithium.write_bytes(d);
+        self.ed25519.write_bytes(e);
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CompositeError> {
+        if bytes.len() != Self::BYTES {
+            return Err(CompositeError::InvalidLength);
+        }
+        let (d, e) = bytes.split_at(Dilithium3Signature::BYTES);
+        Ok(Self {
+            dilithium: Dilithium3Signature::from_bytes(d)?,
+            ed25519: ed25519::Signature::from_bytes(e)?,
+        })
+    }
+}
+
+/// Dilithium3 and Ed25519 composite
+pub struct Dilithium3Ed25519;
+
+impl Dilithium3Ed25519 {
+    pub const SEED_BYTES: usize = SEED_BYTES + ed25519::SEED_BYTES;
+    pub const PUBLIC_KEY_BYTES: usize = CompositePublicKey::BYTES;
+    pub const SECRET_KEY_BYTES: usize = CompositeSecretKey::BYTES;
+    pub const SIGNATURE_BYTES: usize = CompositeSignature::BYTES;
+
+    /// Generate key pair deterministically from `seed`, the Dilithium seed followed by the Ed25519 seed
+    pub fn keypair(
+        seed: &[u8; SEED_BYTES + ed25519::SEED_BYTES],
+        sk: &mut CompositeSecretKey,
+        pk: &mut CompositePublicKey,
+    ) {
+        let (d, e) = seed.split_at(SEED_BYTES);
+        Dilithium3::keypair(d.try_into().unwrap(), &mut sk.dilithium, &mut pk.dilithium);
+        Ed25519::keypair(e.try_into().unwrap(), &mut sk.ed25519, &mut pk.ed25519);
+    }
+
+    /// Write the message representative `M' = Prefix || Label || len(ctx) || ctx || SHA-512(msg)` into
+    /// `buf` and return it
+    pub fn message_representative<'a>(
+        msg: &[u8],
+        ctx: &[u8],
+        buf: &'a mut [u8; MAX_MESSAGE_REPRESENTATIVE_BYTES],
+    ) -> Result<&'a [u8], CompositeError> {
+        if ctx.len() > MAX_CONTEXT_BYTES {
+            return Err(CompositeError::InvalidContext);
+        }
+        let mut ph = [0u8; SHA512_DIGEST_BYTES];
+        Sha512::digest_parts(&[msg], &mut ph);
+
+        let mut len = 0;
+        for part in [&PREFIX[..], LABEL, &[ctx.len() as u8], ctx, &ph] {
+            buf[len..len + part.len()].copy_from_slice(part);
+            len += part.len();
+        }
+        Ok(&buf[..len])
+    }
+
+    /// Deterministic signature of `msg` with context string `ctx`
+    pub fn sign(
+        msg: &[u8],
+        ctx: &[u8],
+        sk: &CompositeSecretKey,
+        sig: &mut CompositeSignature,
+    ) -> Result<(), CompositeError> {
+        let mut buf = [0u8; MAX_MESSAGE_REPRESENTATIVE_BYTES];
+        let m = Self::message_representative(msg, ctx, &mut buf)?;
+        Dilithium3::sign(m, &sk.dilithium, &mut sig.dilithium);
+        Ed25519::sign(m, &sk.ed25519, &mut sig.ed25519);
+        Ok(())
+    }
+
+    /// Verify signature `sig` of message `msg` with context string `ctx`; both component signatures have
+    /// to be valid
+    pub fn verify(
+        msg: &[u8],
+        ctx: &[u8],
+        sig: &CompositeSignature,
+        pk: &CompositePublicKey,
+    ) -> Result<(), CompositeError> {
+        let mut buf = [0u8; MAX_MESSAGE_REPRESENTATIVE_BYTES];
+        let m = Self::message_representative(msg, ctx, &mut buf)?;
+        let dilithium = Dilithium3::verify(m, &sig.dilithium, &pk.dilithium);
+        let ed25519 = Ed25519::verify(m, &sig.ed25519, &pk.ed25519);
+        dilithium?;
+        ed25519?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, RngCore};
+
+    #[test]
+    fn composite_sign_verify() {
+        let mut rng = rand::thread_rng();
+        let mut sk = CompositeSecretKey::default();
+        let mut pk = CompositePublicKey::default();
+        let mut sig = CompositeSignature::default();
+
+        for _ in 0..5 {
+            let mut seed = [0u8; Dilithium3Ed25519::SEED_BYTES];
+            rng.fill_bytes(&mut seed);
+            let mut msg = [0u8; 59];
+            rng.fill_bytes(&mut msg);
+            let ctx = b"krystals";
+
+            Dilithium3Ed25519::keypair(&seed, &mut sk, &mut pk);
+            Dilithium3Ed25519::sign(&msg, ctx, &sk, &mut sig).unwrap();
+            assert_eq!(Dilithium3Ed25519::verify(&msg, ctx, &sig, &pk), Ok(()));
+
+            // encodings
+            let mut bytes = vec![0u8; Dilithium3Ed25519::PUBLIC_KEY_BYTES];
+            pk.write_bytes(&mut bytes);
+            assert_eq!(CompositePublicKey::from_bytes(&bytes), Ok(pk));
+            let mut bytes = vec![0u8; Dilithium3Ed25519::SECRET_KEY_BYTES];
+            sk.write_bytes(&mut bytes);
+            let sk2 = CompositeSecretKey::from_bytes(&bytes).unwrap();
+            let mut sig2 = CompositeSignature::default();
+            Dilithium3Ed25519::sign(&msg, ctx, &sk2, &mut sig2).unwrap();
+            assert_eq!(sig, sig2);
+            assert_eq!(
+                CompositeSignature::from_bytes(&bytes[1..]),
+                Err(CompositeError::InvalidLength)
+            );
+
+            // wrong context
+            assert_eq!(
+                Dilithium3Ed25519::verify(&msg, b"", &sig, &pk),
+                Err(CompositeError::InvalidSignature)
+            );
+
+            // either component failing invalidates the signature
+            let mut bad = sig;
+            bad.dilithium.c[rng.gen_range(0..SEED_BYTES)] ^= 1;
+            assert_eq!(
+                Dilithium3Ed25519::verify(&msg, ctx, &bad, &pk),
+                Err(CompositeError::InvalidSignature)
+            );
+            let mut bad = sig;
+            bad.ed25519.r[rng.gen_range(0..32)] ^= 1;
+            assert_eq!(
+                Dilithium3Ed25519::verify(&msg, ctx, &bad, &pk),
+                Err(CompositeError::InvalidSignature)
+            );
+
+            // component signatures are over M', not msg
+            let mut buf = [0u8; MAX_MESSAGE_REPRESENTATIVE_BYTES];
+            let m = Dilithium3Ed25519::message_representative(&msg, ctx, &mut buf).unwrap();
+            assert_eq!(m.len(), 32 + 34 + 1 + ctx.len() + 64);
+            assert_eq!(Dilithium3::verify(m, &sig.dilithium, &pk.dilithium), Ok(()));
+            assert_eq!(Ed25519::verify(m, &sig.ed25519, &pk.ed25519), Ok(()));
+        }
+    }
+
+    #[test]
+    fn composite_context_too_long() {
+        let sk = CompositeSecretKey::default();
+        let mut sig = CompositeSignature::default();
+        assert_eq!(
+            Dilithium3Ed25519::sign(b"msg", &[0; MAX_CONTEXT_BYTES + 1], &sk, &mut sig),
+            Err(CompositeError::InvalidContext)
+        );
+    }
+}
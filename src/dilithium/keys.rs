@@ -0,0 +1,194 @@
+use super::{DilithiumError, Prf, SEED_BYTES};
+use crate::keccak::fips202::SpongeOps;
+use crate::poly::dilithium::{T0_BITS, T1_BITS};
+use crate::utils::flatten::{FlattenTwice, FlattenTwiceMut};
+
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Copy consecutive parts of `bytes` into `parts`; `bytes` must have the exact total length of `parts`
+fn split_into(bytes: &[u8], parts: &mut [&mut [u8]]) -> Result<(), DilithiumError> {
+    if bytes.len() != parts.iter().map(|p| p.len()).sum::<usize>() {
+        return Err(DilithiumError::InvalidLength);
+    }
+    let mut bytes = bytes;
+    for part in parts.iter_mut() {
+        let (b, rest) = bytes.split_at(part.len());
+        part.copy_from_slice(b);
+        bytes = rest;
+    }
+    Ok(())
+}
+
+/// Copy concatenation of `parts` into `bytes`
+/// Panics if the length of `bytes` is not the total length of `parts`
+fn concat_into(parts: &[&[u8]], bytes: &mut [u8]) {
+    assert_eq!(bytes.len(), parts.iter().map(|p| p.len()).sum::<usize>());
+    let mut bytes = bytes;
+    for part in parts {
+        let (b, rest) = bytes.split_at_mut(part.len());
+        b.copy_from_slice(part);
+        bytes = rest;
+    }
+}
+
+/// Packed public key `(rho, t1)`
+///
+/// `AES` is set for keys of the AES variants, so they can't be passed to the functions of the SHAKE variants
+/// and vice versa. The same holds for [`SecretKey`] and [`Signature`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublicKey<const K: usize, const AES: bool = false> {
+    pub rho: [u8; SEED_BYTES],
+    pub t1: [[[u8; T1_BITS]; 32]; K],
+}
+
+impl<const K: usize, const AES: bool> Default for PublicKey<K, AES> {
+    fn default() -> Self {
+        Self {
+            rho: [0; SEED_BYTES],
+            t1: [[[0; T1_BITS]; 32]; K],
+        }
+    }
+}
+
+impl<const K: usize, const AES: bool> PublicKey<K, AES> {
+    pub const BYTES: usize = SEED_BYTES + K * 32 * T1_BITS;
+
+    /// Panics if `bytes.len() != Self::BYTES`
+    pub fn write_bytes(&self, bytes: &mut [u8]) {
+        concat_into(&[&self.rho, self.t1.flatten_twice()], bytes);
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DilithiumError> {
+        let mut pk = Self::default();
+        split_into(bytes, &mut [&mut pk.rho, pk.t1.flatten_twice_mut()])?;
+        Ok(pk)
+    }
+
+    /// `tr = H(pk)`
+    #[inline]
+    pub(crate) fn hash(&self, prf: &mut Prf, tr: &mut [u8; SEED_BYTES]) {
+        prf.absorb_parts(&[&self.rho, self.t1.flatten_twice()]);
+        prf.squeeze(tr);
+    }
+}
+
+/// Packed secret key `(rho, key, tr, s1, s2, t0)`
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct SecretKey<const K: usize, const L: usize, const ETA_BITS: usize, const AES: bool = false>
+{
+    pub rho: [u8; SEED_BYTES],
+    pub key: [u8; SEED_BYTES],
+    pub tr: [u8; SEED_BYTES],
+    pub s1: [[[u8; ETA_BITS]; 32]; L],
+    pub s2: [[[u8; ETA_BITS]; 32]; K],
+    pub t0: [[[u8; T0_BITS]; 32]; K],
+}
+
+impl<const K: usize, const L: usize, const ETA_BITS: usize, const AES: bool> Default
+    for SecretKey<K, L, ETA_BITS, AES>
+{
+    fn default() -> Self {
+        Self {
+            rho: [0; SEED_BYTES],
+            key: [0; SEED_BYTES],
+            tr: [0; SEED_BYTES],
+            s1: [[[0; ETA_BITS]; 32]; L],
+            s2: [[[0; ETA_BITS]; 32]; K],
+            t0: [[[0; T0_BITS]; 32]; K],
+        }
+    }
+}
+
+impl<const K: usize, const L: usize, const ETA_BITS: usize, const AES: bool>
+    SecretKey<K, L, ETA_BITS, AES>
+{
+    pub const BYTES: usize = 3 * SEED_BYTES + (K + L) * 32 * ETA_BITS + K * 32 * T0_BITS;
+
+    /// Panics if `bytes.len() != Self::BYTES`
+    pub fn write_bytes(&self, bytes: &mut [u8]) {
+        concat_into(
+            &[
+                &self.rho,
+                &self.key,
+                &self.tr,
+                self.s1.flatten_twice(),
+                self.s2.flatten_twice(),
+                self.t0.flatten_twice(),
+            ],
+            bytes,
+        );
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DilithiumError> {
+        let mut sk = Self::default();
+        split_into(
+            bytes,
+            &mut [
+                &mut sk.rho,
+                &mut sk.key,
+                &mut sk.tr,
+                sk.s1.flatten_twice_mut(),
+                sk.s2.flatten_twice_mut(),
+                sk.t0.flatten_twice_mut(),
+            ],
+        )?;
+        Ok(sk)
+    }
+}
+
+/// Packed signature `(c, z, h)`, where the hint `h` is encoded as coefficient indices `h_idx` and
+/// cumulative counts `h_cnt`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signature<
+    const K: usize,
+    const L: usize,
+    const Z_BITS: usize,
+    const OMEGA: usize,
+    const AES: bool = false,
+> {
+    pub c: [u8; SEED_BYTES],
+    pub z: [[[u8; Z_BITS]; 32]; L],
+    pub h_idx: [u8; OMEGA],
+    pub h_cnt: [u8; K],
+}
+
+impl<const K: usize, const L: usize, const Z_BITS: usize, const OMEGA: usize, const AES: bool>
+    Default for Signature<K, L, Z_BITS, OMEGA, AES>
+{
+    fn default() -> Self {
+        Self {
+            c: [0; SEED_BYTES],
+            z: [[[0; Z_BITS]; 32]; L],
+            h_idx: [0; OMEGA],
+            h_cnt: [0; K],
+        }
+    }
+}
+
+impl<const K: usize, const L: usize, const Z_BITS: usize, const OMEGA: usize, const AES: bool>
+    Signature<K, L, Z_BITS, OMEGA, AES>
+{
+    pub const BYTES: usize = SEED_BYTES + L * 32 * Z_BITS + OMEGA + K;
+
+    /// Panics if `bytes.len() != Self::BYTES`
+    pub fn write_bytes(&self, bytes: &mut [u8]) {
+        concat_into(
+            &[&self.c, self.z.flatten_twice(), &self.h_idx, &self.h_cnt],
+            bytes,
+        );
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DilithiumError> {
+        let mut sig = Self::default();
+        split_into(
+            bytes,
+            &mut [
+                &mut sig.c,
+                sig.z.flatten_twice_mut(),
+                &mut sig.h_idx,
+                &mut sig.h_cnt,
+            ],
+        )?;
+        Ok(sig)
+    }
+}
@@ -0,0 +1,455 @@
+//! Signing and verification with a small memory footprint, for constrained targets without a heap
+//!
+//! The matrix `A` is never held in memory as a whole but expanded one row at a time, the mask `y` and
+//! `w = A * y` are recomputed for each row instead of being stored, and the signature is packed as soon as
+//! each of its polynomials is available. This trades running time for memory: a signing attempt expands
+//! `A` twice and samples `y` `2 * K` times.
+//! The produced signatures are identical to the ones of [`Dilithium::sign`] and [`Dilithium::sign_randomized`].
+
+use super::{
+    stats::Rejection, stream::Hasher, Dilithium, DilithiumError, Prf, PublicKey, SecretKey,
+    Signature, CRH_BYTES, SEED_BYTES,
+};
+use crate::field::dilithium::{GAMMA2_32, GAMMA2_88};
+use crate::poly::dilithium::DilithiumPoly;
+use crate::poly::SizedPolynomial;
+use crate::polyvec::DilithiumPolyVec;
+use crate::utils::flatten::FlattenSlice;
+
+use rand::{CryptoRng, RngCore};
+
+impl<
+        const K: usize,
+        const L: usize,
+        const ETA_BITS: usize,
+        const Z_BITS: usize,
+        const W1_BITS: usize,
+        const OMEGA: usize,
+        const AES: bool,
+    > Dilithium<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>
+{
+    #[inline(always)]
+    fn decompose_poly(a1: &mut DilithiumPoly, a0: &mut DilithiumPoly) {
+        match Self::GAMMA2 {
+            GAMMA2_88 => a1.decompose::<GAMMA2_88>(a0),
+            GAMMA2_32 => a1.decompose::<GAMMA2_32>(a0),
+            _ => unreachable!(),
+        }
+    }
+
+    #[inline(always)]
+    fn make_hint_poly(h: &mut DilithiumPoly, a0: &DilithiumPoly, a1: &DilithiumPoly) -> usize {
+        match Self::GAMMA2 {
+            GAMMA2_88 => h.make_hint::<GAMMA2_88>(a0, a1),
+            GAMMA2_32 => h.make_hint::<GAMMA2_32>(a0, a1),
+            _ => unreachable!(),
+        }
+    }
+
+    #[inline(always)]
+    fn use_hint_poly(a: &mut DilithiumPoly, h: &DilithiumPoly) {
+        match Self::GAMMA2 {
+            GAMMA2_88 => a.use_hint::<GAMMA2_88>(h),
+            GAMMA2_32 => a.use_hint::<GAMMA2_32>(h),
+            _ => unreachable!(),
+        }
+    }
+
+    #[inline(always)]
+    fn uniform_gamma1_poly(
+        y: &mut DilithiumPoly,
+        prf: &mut Prf,
+        seed: &[u8; CRH_BYTES],
+        nonce: u16,
+    ) {
+        if AES {
+            y.uniform_gamma1_aes::<Z_BITS>(seed, nonce);
+        } else {
+            y.uniform_gamma1::<Z_BITS>(prf, seed, nonce);
+        }
+    }
+
+    /// `r = c * a` for `c` in NTT domain, with `r` reduced by `reduce32`
+    #[inline]
+    fn mult_challenge(cp: &DilithiumPoly, a: &mut DilithiumPoly, r: &mut DilithiumPoly) {
+        a.ntt();
+        cp.pointwise(a, r);
+        r.inv_ntt();
+        r.reduce32();
+    }
+
+    /// Row `i` of `A` as [`Self::expand_row`], but sampled one polynomial at a time to keep the four-way
+    /// Keccak state and its output buffers off the stack
+    fn expand_row_low_ram(row: &mut DilithiumPolyVec<L>, rho: &[u8; SEED_BYTES], i: usize) {
+        if AES {
+            Self::expand_row(row, rho, i);
+        } else {
+            for (j, a) in row.as_mut().iter_mut().enumerate() {
+                a.uniform(rho, j as u8, i as u8);
+            }
+        }
+    }
+
+    /// Row `i` of `w = A * y` with coefficients in standard representation, with `y` re-sampled from
+    /// `rhoprime` and `nonce`
+    fn row_times_mask(
+        prf: &mut Prf,
+        rho: &[u8; SEED_BYTES],
+        rhoprime: &[u8; CRH_BYTES],
+        nonce: u16,
+        i: usize,
+        w: &mut DilithiumPoly,
+    ) {
+        let mut row = DilithiumPolyVec::<L>::default();
+        Self::expand_row_low_ram(&mut row, rho, i);
+
+        *w = DilithiumPoly::default();
+        let mut y = DilithiumPoly::default();
+        for (j, a) in row.as_ref().iter().enumerate() {
+            Self::uniform_gamma1_poly(
+                &mut y,
+                prf,
+                rhoprime,
+                (L as u16).wrapping_mul(nonce).wrapping_add(j as u16),
+            );
+            y.ntt();
+            a.pointwise_acc(&y, w);
+        }
+        w.reduce32();
+        w.inv_ntt();
+        w.caddq();
+    }
+
+    /// Low-memory version of `sign_internal`
+    pub(crate) fn sign_low_ram_internal<F: FnMut(Rejection)>(
+        mu: &[u8; CRH_BYTES],
+        rhoprime: &[u8; CRH_BYTES],
+        sk: &SecretKey<K, L, ETA_BITS, AES>,
+        sig: &mut Signature<K, L, Z_BITS, OMEGA, AES>,
+        mut observe: F,
+    ) {
+        let mut prf = Prf::default();
+
+        let mut w1 = DilithiumPoly::default();
+        let mut w0 = DilithiumPoly::default();
+        let mut cp = DilithiumPoly::default();
+        let mut a = DilithiumPoly::default();
+        let mut r = DilithiumPoly::default();
+        let mut w1_packed = [[0u8; W1_BITS]; 32];
+
+        let mut nonce = 0u16;
+        'rejection: loop {
+            // Call the random oracle on the high bits of w = Ay, one row at a time
+            let mut hasher = Hasher::new(mu);
+            for i in 0..K {
+                Self::row_times_mask(&mut prf, &sk.rho, rhoprime, nonce, i, &mut w1);
+                Self::decompose_poly(&mut w1, &mut w0);
+                w1.pack_w1(&mut w1_packed);
+                hasher.update(w1_packed.flatten_slice());
+            }
+            hasher.finalize(&mut sig.c);
+            Self::challenge(&mut cp, &mut prf, &sig.c);
+            cp.ntt();
+
+            // Compute z = y + cs1, reject if it reveals secret
+            for (j, (s1, z)) in sk.s1.iter().zip(sig.z.iter_mut()).enumerate() {
+                a.unpack_eta(s1);
+                Self::mult_challenge(&cp, &mut a, &mut r);
+                let nonce_j = (L as u16).wrapping_mul(nonce).wrapping_add(j as u16);
+                Self::uniform_gamma1_poly(&mut a, &mut prf, rhoprime, nonce_j);
+                r += &a;
+                r.reduce32();
+                if r.chknorm(Self::GAMMA1 - Self::BETA) {
+                    observe(Rejection::ZNorm);
+                    nonce = nonce.wrapping_add(1);
+                    continue 'rejection;
+                }
+                r.pack_z(z);
+            }
+
+            // Recompute w row by row for the checks on its low bits and the hints
+            let mut k = 0;
+            for (i, ((s2, t0), cnt)) in sk
+                .s2
+                .iter()
+                .zip(sk.t0.iter())
+                .zip(sig.h_cnt.iter_mut())
+                .enumerate()
+            {
+                Self::row_times_mask(&mut prf, &sk.rho, rhoprime, nonce, i, &mut w1);
+                Self::decompose_poly(&mut w1, &mut w0);
+
+                a.unpack_eta(s2);
+                Self::mult_challenge(&cp, &mut a, &mut r);
+                w0 -= &r;
+                w0.reduce32();
+                if w0.chknorm(Self::GAMMA2 - Self::BETA) {
+                    observe(Rejection::R0Norm);
+                    nonce = nonce.wrapping_add(1);
+                    continue 'rejection;
+                }
+
+                a.unpack_t0(t0);
+                Self::mult_challenge(&cp, &mut a, &mut r);
+                if r.chknorm(Self::GAMMA2) {
+                    observe(Rejection::Ct0Norm);
+                    nonce = nonce.wrapping_add(1);
+                    continue 'rejection;
+                }
+
+                w0 += &r;
+                if k + Self::make_hint_poly(&mut r, &w0, &w1) > OMEGA {
+                    observe(Rejection::HintCount);
+                    nonce = nonce.wrapping_add(1);
+                    continue 'rejection;
+                }
+                k += r.pack_hint(&mut sig.h_idx[k..]);
+                *cnt = k as u8;
+            }
+            sig.h_idx[k..].fill(0);
+            return;
+        }
+    }
+
+    /// Deterministic signature of `msg`, using a small amount of memory
+    pub fn sign_low_ram(
+        msg: &[u8],
+        sk: &SecretKey<K, L, ETA_BITS, AES>,
+        sig: &mut Signature<K, L, Z_BITS, OMEGA, AES>,
+    ) {
+        let mut mu = [0u8; CRH_BYTES];
+        Self::compute_mu(&mut Prf::default(), &sk.tr, msg, &mut mu);
+        let mut rhoprime = [0u8; CRH_BYTES];
+        Self::deterministic_rhoprime(&sk.key, &mu, &mut rhoprime);
+        Self::sign_low_ram_internal(&mu, &rhoprime, sk, sig, |_| {});
+    }
+
+    /// Randomized signature of `msg`, using a small amount of memory
+    pub fn sign_low_ram_randomized<R: RngCore + CryptoRng>(
+        msg: &[u8],
+        sk: &SecretKey<K, L, ETA_BITS, AES>,
+        rng: &mut R,
+        sig: &mut Signature<K, L, Z_BITS, OMEGA, AES>,
+    ) -> Result<(), DilithiumError> {
+        let mut mu = [0u8; CRH_BYTES];
+        Self::compute_mu(&mut Prf::default(), &sk.tr, msg, &mut mu);
+        let mut rhoprime = [0u8; CRH_BYTES];
+        rng.try_fill_bytes(&mut rhoprime)
+            .or(Err(DilithiumError::RngFailure))?;
+        Self::sign_low_ram_internal(&mu, &rhoprime, sk, sig, |_| {});
+        Ok(())
+    }
+
+    /// Verify signature `sig` of message `msg`, using a small amount of memory
+    pub fn verify_low_ram(
+        msg: &[u8],
+        sig: &Signature<K, L, Z_BITS, OMEGA, AES>,
+        pk: &PublicKey<K, AES>,
+    ) -> Result<(), DilithiumError> {
+        let mut prf = Prf::default();
+
+        // Check the encoding of the hint; the positions within each polynomial are checked when decoding it
+        let mut k = 0;
+        for &cnt in sig.h_cnt.iter() {
+            let cnt = cnt as usize;
+            if cnt < k || cnt > OMEGA {
+                return Err(DilithiumError::InvalidSignature);
+            }
+            k = cnt;
+        }
+        if sig.h_idx[k..].iter().any(|&b| b != 0) {
+            return Err(DilithiumError::InvalidSignature);
+        }
+
+        let mut z = DilithiumPoly::default();
+        for z_packed in sig.z.iter() {
+            z.unpack_z(z_packed);
+            if z.chknorm(Self::GAMMA1 - Self::BETA) {
+                return Err(DilithiumError::InvalidSignature);
+            }
+        }
+
+        let mut tr = [0u8; SEED_BYTES];
+        pk.hash(&mut prf, &mut tr);
+        let mut mu = [0u8; CRH_BYTES];
+        Self::compute_mu(&mut prf, &tr, msg, &mut mu);
+
+        let mut cp = DilithiumPoly::default();
+        Self::challenge(&mut cp, &mut prf, &sig.c);
+        cp.ntt();
+
+        // Compute w1 = UseHint(h, Az - ct1 * 2^D) one row at a time and call the random oracle on it
+        let mut hasher = Hasher::new(&mu);
+        let mut row = DilithiumPolyVec::<L>::default();
+        let mut t1 = DilithiumPoly::default();
+        let mut h = DilithiumPoly::default();
+        let mut w1_packed = [[0u8; W1_BITS]; 32];
+        let mut k = 0;
+        for (i, (t1_packed, &cnt)) in pk.t1.iter().zip(sig.h_cnt.iter()).enumerate() {
+            Self::expand_row_low_ram(&mut row, &pk.rho, i);
+            let mut w1 = DilithiumPoly::default();
+            for (a, z_packed) in row.as_ref().iter().zip(sig.z.iter()) {
+                z.unpack_z(z_packed);
+                z.ntt();
+                a.pointwise_acc(&z, &mut w1);
+            }
+
+            t1.unpack_t1(t1_packed);
+            t1.shiftl();
+            t1.ntt();
+            cp.pointwise(&t1, &mut h);
+            w1 -= &h;
+            w1.reduce32();
+            w1.inv_ntt();
+            w1.caddq();
+
+            if !h.unpack_hint(&sig.h_idx[k..cnt as usize]) {
+                return Err(DilithiumError::InvalidSignature);
+            }
+            k = cnt as usize;
+            Self::use_hint_poly(&mut w1, &h);
+
+            w1.pack_w1(&mut w1_packed);
+            hasher.update(w1_packed.flatten_slice());
+        }
+
+        let mut c2 = [0u8; SEED_BYTES];
+        hasher.finalize(&mut c2);
+        if c2 != sig.c {
+            return Err(DilithiumError::InvalidSignature);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    use std::boxed::Box;
+
+    const NUM_TESTS: usize = if cfg!(miri) { 1 } else { 10 };
+
+    /// Run `f` on a thread with a stack of `stack_kib` KiB; the test process aborts if `f` needs more
+    fn with_stack<F: FnOnce() + Send + 'static>(stack_kib: usize, f: F) {
+        std::thread::Builder::new()
+            .stack_size(stack_kib * 1024)
+            .spawn(f)
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    fn low_ram_vs_default<
+        const K: usize,
+        const L: usize,
+        const ETA_BITS: usize,
+        const Z_BITS: usize,
+        const W1_BITS: usize,
+        const OMEGA: usize,
+        const AES: bool,
+    >(
+        stack_kib: usize,
+    ) {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..NUM_TESTS {
+            let mut sk = Box::new(SecretKey::<K, L, ETA_BITS, AES>::default());
+            let mut pk = Box::new(PublicKey::<K, AES>::default());
+            let mut sig = Box::new(Signature::<K, L, Z_BITS, OMEGA, AES>::default());
+            let seed: [u8; SEED_BYTES] = rng.gen();
+            let mut msg = [0u8; 33];
+            rng.fill(&mut msg[..]);
+
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::keypair(
+                &seed, &mut sk, &mut pk,
+            );
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::sign(&msg, &sk, &mut sig);
+
+            // verification of low-memory signatures with the default implementation
+            let mut sig_low_ram = Signature::<K, L, Z_BITS, OMEGA, AES>::default();
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::sign_low_ram_randomized(
+                &msg,
+                &sk,
+                &mut rng,
+                &mut sig_low_ram,
+            )
+            .unwrap();
+            assert_eq!(
+                Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::verify(
+                    &msg,
+                    &sig_low_ram,
+                    &pk
+                ),
+                Ok(())
+            );
+
+            // the keys and the reference signature are kept on the heap, so that the stack usage of the thread
+            // is the one of the low-memory functions plus the signature they produce
+            with_stack(stack_kib, move || {
+                let mut sig_low_ram = Signature::<K, L, Z_BITS, OMEGA, AES>::default();
+                Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::sign_low_ram(
+                    &msg,
+                    &sk,
+                    &mut sig_low_ram,
+                );
+                assert!(sig_low_ram == *sig);
+                assert!(
+                    Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::verify_low_ram(
+                        &msg, &sig, &pk
+                    )
+                    .is_ok()
+                );
+
+                Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::sign_low_ram_randomized(
+                    &msg,
+                    &sk,
+                    &mut rand::thread_rng(),
+                    &mut sig_low_ram,
+                )
+                .unwrap();
+                assert!(
+                    Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::verify_low_ram(
+                        &msg,
+                        &sig_low_ram,
+                        &pk
+                    )
+                    .is_ok()
+                );
+
+                let mut msg = msg;
+                msg[0] ^= 1;
+                assert!(
+                    Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::verify_low_ram(
+                        &msg, &sig, &pk
+                    )
+                    .is_err()
+                );
+            });
+        }
+    }
+
+    // Peak stack usage of the low-memory functions, including the produced signature, measured on x86_64 Linux
+    // with rustc 1.95 by bisecting the stack size of the thread (the minimum thread stack is 16 KiB):
+    //
+    //               test profile   release
+    // Dilithium2    45 KiB         <= 16 KiB
+    // Dilithium3    46 KiB         <= 16 KiB
+    // Dilithium5    47 KiB         26 KiB
+    //
+    // For comparison, `sign` needs about 124 KiB (Dilithium2) to 361 KiB (Dilithium5) in the test profile.
+    // The limits below leave some headroom for other compilers and targets.
+    const DILITHIUM2_STACK_KIB: usize = 56;
+    const DILITHIUM3_STACK_KIB: usize = 58;
+    const DILITHIUM5_STACK_KIB: usize = 60;
+
+    #[test]
+    fn low_ram_sign_verify() {
+        low_ram_vs_default::<4, 4, 3, 18, 6, 80, false>(DILITHIUM2_STACK_KIB);
+        low_ram_vs_default::<6, 5, 4, 20, 4, 55, false>(DILITHIUM3_STACK_KIB);
+        low_ram_vs_default::<8, 7, 3, 20, 4, 75, false>(DILITHIUM5_STACK_KIB);
+        low_ram_vs_default::<4, 4, 3, 18, 6, 80, true>(DILITHIUM2_STACK_KIB);
+        low_ram_vs_default::<6, 5, 4, 20, 4, 55, true>(DILITHIUM3_STACK_KIB);
+        low_ram_vs_default::<8, 7, 3, 20, 4, 75, true>(DILITHIUM5_STACK_KIB);
+    }
+}
@@ -1 +1,107 @@
+pub mod keys;
+pub mod low_ram;
+pub mod prepared;
+pub mod seed;
+pub mod sign;
+#[cfg(feature = "instrumentation")]
+pub mod stats;
+#[cfg(not(feature = "instrumentation"))]
+mod stats;
+pub mod stream;
 
+#[cfg(feature = "signature")]
+mod traits;
+
+pub use keys::*;
+pub use prepared::*;
+pub use seed::*;
+pub use sign::*;
+#[cfg(feature = "instrumentation")]
+pub use stats::*;
+pub use stream::*;
+#[cfg(feature = "signature")]
+pub use traits::*;
+
+use crate::keccak::fips202::{HasParams, Shake256};
+use crate::keccak::KeccakParams;
+
+pub const SEED_BYTES: usize = 32;
+
+pub const CRH_BYTES: usize = 64;
+
+pub type Prf = Shake256;
+pub const PRF_BLOCK_BYTES: usize = <Prf as HasParams<_>>::Params::RATE_BYTES;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DilithiumError {
+    /// Encoded key or signature has the wrong length
+    InvalidLength,
+    /// Signature verification failed
+    InvalidSignature,
+    /// Secret key is inconsistent with the public key recomputed from it
+    InvalidKey,
+    /// Random number generator failed to produce randomness
+    RngFailure,
+}
+
+/// NIST security level 2
+pub type Dilithium2 = Dilithium<4, 4, 3, 18, 6, 80>;
+/// NIST security level 3
+pub type Dilithium3 = Dilithium<6, 5, 4, 20, 4, 55>;
+/// NIST security level 5
+pub type Dilithium5 = Dilithium<8, 7, 3, 20, 4, 75>;
+
+pub type Dilithium2PublicKey = PublicKey<4>;
+pub type Dilithium2SecretKey = SecretKey<4, 4, 3>;
+pub type Dilithium2Signature = Signature<4, 4, 18, 80>;
+pub type Dilithium2SigningContext<'a> = SigningContext<'a, 4, 4, 3, 18, 6, 80>;
+pub type Dilithium2VerifyingContext<'a> = VerifyingContext<'a, 4, 4, 3, 18, 6, 80>;
+pub type Dilithium2PreparedSigningKey = PreparedSigningKey<4, 4>;
+pub type Dilithium2PreparedVerifyingKey = PreparedVerifyingKey<4, 4>;
+
+pub type Dilithium3PublicKey = PublicKey<6>;
+pub type Dilithium3SecretKey = SecretKey<6, 5, 4>;
+pub type Dilithium3Signature = Signature<6, 5, 20, 55>;
+pub type Dilithium3SigningContext<'a> = SigningContext<'a, 6, 5, 4, 20, 4, 55>;
+pub type Dilithium3VerifyingContext<'a> = VerifyingContext<'a, 6, 5, 4, 20, 4, 55>;
+pub type Dilithium3PreparedSigningKey = PreparedSigningKey<6, 5>;
+pub type Dilithium3PreparedVerifyingKey = PreparedVerifyingKey<6, 5>;
+
+pub type Dilithium5PublicKey = PublicKey<8>;
+pub type Dilithium5SecretKey = SecretKey<8, 7, 3>;
+pub type Dilithium5Signature = Signature<8, 7, 20, 75>;
+pub type Dilithium5SigningContext<'a> = SigningContext<'a, 8, 7, 3, 20, 4, 75>;
+pub type Dilithium5VerifyingContext<'a> = VerifyingContext<'a, 8, 7, 3, 20, 4, 75>;
+pub type Dilithium5PreparedSigningKey = PreparedSigningKey<8, 7>;
+pub type Dilithium5PreparedVerifyingKey = PreparedVerifyingKey<8, 7>;
+
+/// NIST security level 2, with AES-256-CTR expansion of the matrix and vectors
+pub type Dilithium2Aes = Dilithium<4, 4, 3, 18, 6, 80, true>;
+/// NIST security level 3, with AES-256-CTR expansion of the matrix and vectors
+pub type Dilithium3Aes = Dilithium<6, 5, 4, 20, 4, 55, true>;
+/// NIST security level 5, with AES-256-CTR expansion of the matrix and vectors
+pub type Dilithium5Aes = Dilithium<8, 7, 3, 20, 4, 75, true>;
+
+pub type Dilithium2AesPublicKey = PublicKey<4, true>;
+pub type Dilithium2AesSecretKey = SecretKey<4, 4, 3, true>;
+pub type Dilithium2AesSignature = Signature<4, 4, 18, 80, true>;
+pub type Dilithium2AesSigningContext<'a> = SigningContext<'a, 4, 4, 3, 18, 6, 80, true>;
+pub type Dilithium2AesVerifyingContext<'a> = VerifyingContext<'a, 4, 4, 3, 18, 6, 80, true>;
+pub type Dilithium2AesPreparedSigningKey = PreparedSigningKey<4, 4, true>;
+pub type Dilithium2AesPreparedVerifyingKey = PreparedVerifyingKey<4, 4, true>;
+
+pub type Dilithium3AesPublicKey = PublicKey<6, true>;
+pub type Dilithium3AesSecretKey = SecretKey<6, 5, 4, true>;
+pub type Dilithium3AesSignature = Signature<6, 5, 20, 55, true>;
+pub type Dilithium3AesSigningContext<'a> = SigningContext<'a, 6, 5, 4, 20, 4, 55, true>;
+pub type Dilithium3AesVerifyingContext<'a> = VerifyingContext<'a, 6, 5, 4, 20, 4, 55, true>;
+pub type Dilithium3AesPreparedSigningKey = PreparedSigningKey<6, 5, true>;
+pub type Dilithium3AesPreparedVerifyingKey = PreparedVerifyingKey<6, 5, true>;
+
+pub type Dilithium5AesPublicKey = PublicKey<8, true>;
+pub type Dilithium5AesSecretKey = SecretKey<8, 7, 3, true>;
+pub type Dilithium5AesSignature = Signature<8, 7, 20, 75, true>;
+pub type Dilithium5AesSigningContext<'a> = SigningContext<'a, 8, 7, 3, 20, 4, 75, true>;
+pub type Dilithium5AesVerifyingContext<'a> = VerifyingContext<'a, 8, 7, 3, 20, 4, 75, true>;
+pub type Dilithium5AesPreparedSigningKey = PreparedSigningKey<8, 7, true>;
+pub type Dilithium5AesPreparedVerifyingKey = PreparedVerifyingKey<8, 7, true>;
@@ -0,0 +1,213 @@
+//! Signing and verification keys with the matrix `A` and the NTT of the key vectors precomputed, to
+//! amortize the expansion cost over many signatures
+
+use super::{
+    Dilithium, DilithiumError, Prf, PublicKey, SecretKey, Signature, CRH_BYTES, SEED_BYTES,
+};
+use crate::polymat::DilithiumMatrix;
+use crate::polyvec::{DilithiumPolyVec, PolynomialVector};
+
+use rand::{CryptoRng, RngCore};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+#[inline]
+fn gen_a<const K: usize, const L: usize, const AES: bool>(
+    rho: &[u8; SEED_BYTES],
+) -> DilithiumMatrix<K, L> {
+    if AES {
+        DilithiumMatrix::gen_a_aes(rho)
+    } else {
+        DilithiumMatrix::gen_a(rho)
+    }
+}
+
+/// Expanded secret key holding `A`, `s1`, `s2`, `t0` in NTT domain, together with `key` and `tr`
+/// `AES` selects the expansion of `A` of the Dilithium-AES variants.
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct PreparedSigningKey<const K: usize, const L: usize, const AES: bool = false> {
+    pub(crate) mat: DilithiumMatrix<K, L>,
+    pub(crate) s1: DilithiumPolyVec<L>,
+    pub(crate) s2: DilithiumPolyVec<K>,
+    pub(crate) t0: DilithiumPolyVec<K>,
+    pub(crate) key: [u8; SEED_BYTES],
+    pub(crate) tr: [u8; SEED_BYTES],
+}
+
+impl<const K: usize, const L: usize, const AES: bool> PreparedSigningKey<K, L, AES> {
+    pub fn new<const ETA_BITS: usize>(sk: &SecretKey<K, L, ETA_BITS, AES>) -> Self {
+        let mut psk = Self {
+            mat: gen_a::<K, L, AES>(&sk.rho),
+            s1: Default::default(),
+            s2: Default::default(),
+            t0: Default::default(),
+            key: sk.key,
+            tr: sk.tr,
+        };
+        psk.s1.unpack_eta(&sk.s1);
+        psk.s2.unpack_eta(&sk.s2);
+        psk.t0.unpack_t0(&sk.t0);
+        psk.s1.ntt();
+        psk.s2.ntt();
+        psk.t0.ntt();
+        psk
+    }
+}
+
+/// Expanded public key holding `A` and `t1 * 2^D` in NTT domain, together with `tr = H(pk)`
+/// `AES` selects the expansion of `A` of the Dilithium-AES variants.
+pub struct PreparedVerifyingKey<const K: usize, const L: usize, const AES: bool = false> {
+    pub(crate) mat: DilithiumMatrix<K, L>,
+    pub(crate) t1: DilithiumPolyVec<K>,
+    pub(crate) tr: [u8; SEED_BYTES],
+}
+
+impl<const K: usize, const L: usize, const AES: bool> PreparedVerifyingKey<K, L, AES> {
+    pub fn new(pk: &PublicKey<K, AES>) -> Self {
+        let mut pvk = Self {
+            mat: gen_a::<K, L, AES>(&pk.rho),
+            t1: Default::default(),
+            tr: [0; SEED_BYTES],
+        };
+        pk.hash(&mut Prf::default(), &mut pvk.tr);
+        pvk.t1.unpack_t1(&pk.t1);
+        pvk.t1.shiftl();
+        pvk.t1.ntt();
+        pvk
+    }
+}
+
+impl<
+        const K: usize,
+        const L: usize,
+        const ETA_BITS: usize,
+        const Z_BITS: usize,
+        const W1_BITS: usize,
+        const OMEGA: usize,
+        const AES: bool,
+    > Dilithium<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>
+{
+    /// Deterministic signature of `msg` using the expanded secret key `psk`
+    pub fn sign_prepared(
+        msg: &[u8],
+        psk: &PreparedSigningKey<K, L, AES>,
+        sig: &mut Signature<K, L, Z_BITS, OMEGA, AES>,
+    ) {
+        let mut mu = [0u8; CRH_BYTES];
+        Self::compute_mu(&mut Prf::default(), &psk.tr, msg, &mut mu);
+        let mut rhoprime = [0u8; CRH_BYTES];
+        Self::deterministic_rhoprime(&psk.key, &mu, &mut rhoprime);
+        Self::sign_prepared_internal(&mu, &rhoprime, psk, sig, |_| {});
+    }
+
+    /// Randomized signature of `msg` using the expanded secret key `psk`, with the per-signature
+    /// randomness drawn from `rng`
+    pub fn sign_prepared_randomized<R: RngCore + CryptoRng>(
+        msg: &[u8],
+        psk: &PreparedSigningKey<K, L, AES>,
+        rng: &mut R,
+        sig: &mut Signature<K, L, Z_BITS, OMEGA, AES>,
+    ) -> Result<(), DilithiumError> {
+        let mut mu = [0u8; CRH_BYTES];
+        Self::compute_mu(&mut Prf::default(), &psk.tr, msg, &mut mu);
+        let mut rhoprime = [0u8; CRH_BYTES];
+        rng.try_fill_bytes(&mut rhoprime)
+            .or(Err(DilithiumError::RngFailure))?;
+        Self::sign_prepared_internal(&mu, &rhoprime, psk, sig, |_| {});
+        Ok(())
+    }
+
+    /// Verify signature `sig` of message `msg` using the expanded public key `pvk`
+    pub fn verify_prepared(
+        msg: &[u8],
+        sig: &Signature<K, L, Z_BITS, OMEGA, AES>,
+        pvk: &PreparedVerifyingKey<K, L, AES>,
+    ) -> Result<(), DilithiumError> {
+        let mut mu = [0u8; CRH_BYTES];
+        Self::compute_mu(&mut Prf::default(), &pvk.tr, msg, &mut mu);
+        Self::verify_with(&mu, sig, &pvk.t1, |z, w1| pvk.mat.mult_vec(z, w1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    const NUM_TESTS: usize = if cfg!(miri) { 1 } else { 10 };
+
+    fn prepared_vs_unprepared<
+        const K: usize,
+        const L: usize,
+        const ETA_BITS: usize,
+        const Z_BITS: usize,
+        const W1_BITS: usize,
+        const OMEGA: usize,
+        const AES: bool,
+    >() {
+        let mut rng = rand::thread_rng();
+        let mut sk = SecretKey::<K, L, ETA_BITS, AES>::default();
+        let mut pk = PublicKey::<K, AES>::default();
+        let mut sig = Signature::<K, L, Z_BITS, OMEGA, AES>::default();
+        let mut sig_prepared = Signature::<K, L, Z_BITS, OMEGA, AES>::default();
+
+        for _ in 0..NUM_TESTS {
+            let seed: [u8; SEED_BYTES] = rng.gen();
+            let mut msg = [0u8; 47];
+            rng.fill(&mut msg[..]);
+
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::keypair(
+                &seed, &mut sk, &mut pk,
+            );
+            let psk = PreparedSigningKey::new(&sk);
+            let pvk = PreparedVerifyingKey::<K, L, AES>::new(&pk);
+
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::sign(&msg, &sk, &mut sig);
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::sign_prepared(
+                &msg,
+                &psk,
+                &mut sig_prepared,
+            );
+            assert_eq!(sig_prepared, sig);
+            assert_eq!(
+                Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::verify_prepared(
+                    &msg, &sig, &pvk
+                ),
+                Ok(())
+            );
+
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::sign_prepared_randomized(
+                &msg,
+                &psk,
+                &mut rng,
+                &mut sig_prepared,
+            )
+            .unwrap();
+            assert_eq!(
+                Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::verify(
+                    &msg,
+                    &sig_prepared,
+                    &pk
+                ),
+                Ok(())
+            );
+
+            msg[0] ^= 1;
+            assert_eq!(
+                Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::verify_prepared(
+                    &msg, &sig, &pvk
+                ),
+                Err(DilithiumError::InvalidSignature)
+            );
+        }
+    }
+
+    #[test]
+    fn prepared_sign_verify() {
+        prepared_vs_unprepared::<4, 4, 3, 18, 6, 80, false>();
+        prepared_vs_unprepared::<6, 5, 4, 20, 4, 55, false>();
+        prepared_vs_unprepared::<8, 7, 3, 20, 4, 75, false>();
+        prepared_vs_unprepared::<4, 4, 3, 18, 6, 80, true>();
+        prepared_vs_unprepared::<6, 5, 4, 20, 4, 55, true>();
+        prepared_vs_unprepared::<8, 7, 3, 20, 4, 75, true>();
+    }
+}
@@ -0,0 +1,208 @@
+//! Seed-only secret keys and recomputation of the public key
+//!
+//! A [`SeedSecretKey`] stores only the 32-byte key generation seed, from which the full secret key and the
+//! public key are rederived on demand, e.g. to keep the secret minimal in a secure element.
+
+use super::{Dilithium, DilithiumError, PublicKey, SecretKey, SEED_BYTES};
+use crate::poly::dilithium::T0_BITS;
+use crate::polyvec::DilithiumPolyVec;
+use crate::utils::flatten::FlattenTwice;
+
+use rand::{CryptoRng, RngCore};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Secret key stored as the key generation seed only
+#[derive(Clone, Default, Zeroize, ZeroizeOnDrop)]
+pub struct SeedSecretKey {
+    pub seed: [u8; SEED_BYTES],
+}
+
+impl SeedSecretKey {
+    pub const BYTES: usize = SEED_BYTES;
+
+    pub fn new(seed: &[u8; SEED_BYTES]) -> Self {
+        Self { seed: *seed }
+    }
+
+    /// Draw a fresh seed from `rng`
+    pub fn generate<R: RngCore + CryptoRng>(rng: &mut R) -> Result<Self, DilithiumError> {
+        let mut sk = Self::default();
+        rng.try_fill_bytes(&mut sk.seed)
+            .or(Err(DilithiumError::RngFailure))?;
+        Ok(sk)
+    }
+
+    /// Panics if `bytes.len() != Self::BYTES`
+    pub fn write_bytes(&self, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&self.seed);
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DilithiumError> {
+        Ok(Self {
+            seed: bytes.try_into().or(Err(DilithiumError::InvalidLength))?,
+        })
+    }
+}
+
+impl<
+        const K: usize,
+        const L: usize,
+        const ETA_BITS: usize,
+        const Z_BITS: usize,
+        const W1_BITS: usize,
+        const OMEGA: usize,
+        const AES: bool,
+    > Dilithium<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>
+{
+    /// Expand the seed-only secret key `seed` to the full secret key `sk`
+    pub fn expand(seed: &SeedSecretKey, sk: &mut SecretKey<K, L, ETA_BITS, AES>) {
+        Self::keypair(&seed.seed, sk, &mut PublicKey::default());
+    }
+
+    /// Recompute the public key of the seed-only secret key `seed`
+    pub fn public_key_from_seed(seed: &SeedSecretKey, pk: &mut PublicKey<K, AES>) {
+        let mut sk = SecretKey::<K, L, ETA_BITS, AES>::default();
+        Self::keypair(&seed.seed, &mut sk, pk);
+        sk.zeroize();
+    }
+
+    /// Recompute the public key of `sk` as `t1` of `Power2Round(A * s1 + s2)`
+    ///
+    /// Fails with [`DilithiumError::InvalidKey`] if `t0` or `tr = H(pk)` of `sk` don't match the recomputed
+    /// values, i.e. if `sk` is corrupted. `pk` is left unchanged then.
+    pub fn public_key(
+        sk: &SecretKey<K, L, ETA_BITS, AES>,
+        pk: &mut PublicKey<K, AES>,
+    ) -> Result<(), DilithiumError> {
+        let mut s1 = DilithiumPolyVec::<L>::default();
+        let mut s2 = DilithiumPolyVec::<K>::default();
+        s1.unpack_eta(&sk.s1);
+        s2.unpack_eta(&sk.s2);
+
+        let mut t1 = DilithiumPolyVec::<K>::default();
+        let mut t0 = DilithiumPolyVec::<K>::default();
+        Self::compute_t(&sk.rho, &s1, &s2, &mut t1, &mut t0);
+        s1.zeroize();
+        s2.zeroize();
+
+        let mut recomputed = PublicKey::<K, AES> {
+            rho: sk.rho,
+            ..Default::default()
+        };
+        t1.pack_t1(&mut recomputed.t1);
+
+        let mut t0_packed = [[[0u8; T0_BITS]; 32]; K];
+        t0.pack_t0(&mut t0_packed);
+        t0.zeroize();
+        let mut tr = [0u8; SEED_BYTES];
+        recomputed.hash(&mut Default::default(), &mut tr);
+
+        // constant-time comparison, as t0 is secret
+        let diff = t0_packed
+            .flatten_twice()
+            .iter()
+            .zip(sk.t0.flatten_twice())
+            .chain(tr.iter().zip(sk.tr.iter()))
+            .fold(0, |acc, (a, b)| acc | (a ^ b));
+        t0_packed.zeroize();
+        if diff != 0 {
+            return Err(DilithiumError::InvalidKey);
+        }
+        *pk = recomputed;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    fn seed_keys<
+        const K: usize,
+        const L: usize,
+        const ETA_BITS: usize,
+        const Z_BITS: usize,
+        const W1_BITS: usize,
+        const OMEGA: usize,
+        const AES: bool,
+    >() {
+        let mut rng = rand::thread_rng();
+        let mut sk = SecretKey::<K, L, ETA_BITS, AES>::default();
+        let mut pk = PublicKey::<K, AES>::default();
+
+        for _ in 0..5 {
+            let seed = SeedSecretKey::generate(&mut rng).unwrap();
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::keypair(
+                &seed.seed, &mut sk, &mut pk,
+            );
+
+            let mut sk2 = SecretKey::default();
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::expand(&seed, &mut sk2);
+            let mut bytes = vec![0u8; SecretKey::<K, L, ETA_BITS>::BYTES];
+            let mut bytes2 = bytes.clone();
+            sk.write_bytes(&mut bytes);
+            sk2.write_bytes(&mut bytes2);
+            assert_eq!(bytes, bytes2);
+            let mut pk2 = PublicKey::default();
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::public_key_from_seed(
+                &seed, &mut pk2,
+            );
+            assert_eq!(pk2, pk);
+
+            let mut pk3 = PublicKey::default();
+            assert_eq!(
+                Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::public_key(
+                    &sk2, &mut pk3
+                ),
+                Ok(())
+            );
+            assert_eq!(pk3, pk);
+
+            // corrupted secret keys, which leave the public key untouched
+            let mut pk4 = PublicKey::<K, AES>::default();
+            sk2.t0[rng.gen_range(0..K)][rng.gen_range(0..32)][0] ^= 1;
+            assert_eq!(
+                Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::public_key(
+                    &sk2, &mut pk4
+                ),
+                Err(DilithiumError::InvalidKey)
+            );
+            let mut sk3 = sk.clone();
+            sk3.tr[rng.gen_range(0..SEED_BYTES)] ^= 1;
+            assert_eq!(
+                Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::public_key(
+                    &sk3, &mut pk4
+                ),
+                Err(DilithiumError::InvalidKey)
+            );
+            assert_eq!(pk4, PublicKey::default());
+
+            let mut bytes = [0u8; SeedSecretKey::BYTES];
+            seed.write_bytes(&mut bytes);
+            assert_eq!(SeedSecretKey::from_bytes(&bytes).unwrap().seed, seed.seed);
+            assert_eq!(
+                SeedSecretKey::from_bytes(&bytes[1..]).err(),
+                Some(DilithiumError::InvalidLength)
+            );
+        }
+    }
+
+    #[test]
+    fn seed_keys_dilithium2() {
+        seed_keys::<4, 4, 3, 18, 6, 80, false>();
+        seed_keys::<4, 4, 3, 18, 6, 80, true>();
+    }
+
+    #[test]
+    fn seed_keys_dilithium3() {
+        seed_keys::<6, 5, 4, 20, 4, 55, false>();
+        seed_keys::<6, 5, 4, 20, 4, 55, true>();
+    }
+
+    #[test]
+    fn seed_keys_dilithium5() {
+        seed_keys::<8, 7, 3, 20, 4, 75, false>();
+        seed_keys::<8, 7, 3, 20, 4, 75, true>();
+    }
+}
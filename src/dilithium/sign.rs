@@ -0,0 +1,675 @@
+use super::stats::Rejection;
+use super::{
+    DilithiumError, PreparedSigningKey, Prf, PublicKey, SecretKey, Signature, CRH_BYTES, SEED_BYTES,
+};
+use crate::field::dilithium::{GAMMA2_32, GAMMA2_88};
+use crate::keccak::fips202::SpongeOps;
+use crate::poly::dilithium::DilithiumPoly;
+use crate::poly::SizedPolynomial;
+use crate::polyvec::{DilithiumPolyVec, PolynomialVector};
+use crate::utils::flatten::FlattenTwice;
+use crate::utils::split::ArraySplitter;
+
+use rand::{CryptoRng, RngCore};
+use zeroize::Zeroize;
+
+/// Dilithium signature scheme (round 3.1)
+///
+/// * `K`, `L`: dimensions of the matrix `A`
+/// * `ETA_BITS`: bits per packed coefficient of `s1`, `s2`; 3 for `ETA = 2` and 4 for `ETA = 4`
+/// * `Z_BITS`: bits per packed coefficient of `z`; 18 for `GAMMA1 = 2^17` and 20 for `GAMMA1 = 2^19`
+/// * `W1_BITS`: bits per packed coefficient of `w1`; 6 for `GAMMA2 = (Q-1)/88` and 4 for `GAMMA2 = (Q-1)/32`
+/// * `OMEGA`: maximum number of 1s in the hint
+/// * `AES`: expand the matrix `A`, the secret vectors and the mask `y` with AES-256-CTR instead of SHAKE
+///   (Dilithium-AES); keys and signatures have the same format as for the SHAKE variants
+pub struct Dilithium<
+    const K: usize,
+    const L: usize,
+    const ETA_BITS: usize,
+    const Z_BITS: usize,
+    const W1_BITS: usize,
+    const OMEGA: usize,
+    const AES: bool = false,
+>;
+
+impl<
+        const K: usize,
+        const L: usize,
+        const ETA_BITS: usize,
+        const Z_BITS: usize,
+        const W1_BITS: usize,
+        const OMEGA: usize,
+        const AES: bool,
+    > Dilithium<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>
+{
+    pub const ETA: usize = 1 << (ETA_BITS - 2);
+    pub const TAU: usize = match K {
+        4 => 39,
+        6 => 49,
+        8 => 60,
+        _ => unreachable!(),
+    };
+    pub const BETA: i32 = (Self::TAU * Self::ETA) as i32;
+    pub const GAMMA1: i32 = 1 << (Z_BITS - 1);
+    pub const GAMMA2: i32 = match W1_BITS {
+        6 => GAMMA2_88,
+        4 => GAMMA2_32,
+        _ => unreachable!(),
+    };
+
+    pub const PUBLIC_KEY_BYTES: usize = PublicKey::<K>::BYTES;
+    pub const SECRET_KEY_BYTES: usize = SecretKey::<K, L, ETA_BITS>::BYTES;
+    pub const SIGNATURE_BYTES: usize = Signature::<K, L, Z_BITS, OMEGA>::BYTES;
+
+    // The following helpers dispatch to the const-generic polynomial operations, as the parameters derived
+    // from the generic arguments can not be used as const generic arguments themselves.
+
+    #[inline(always)]
+    fn uniform_eta<const N: usize>(
+        v: &mut DilithiumPolyVec<N>,
+        prf: &mut Prf,
+        seed: &[u8; CRH_BYTES],
+        nonce: u16,
+    ) {
+        match (Self::ETA, AES) {
+            (2, false) => v.uniform_eta::<2>(prf, seed, nonce),
+            (4, false) => v.uniform_eta::<4>(prf, seed, nonce),
+            (2, true) => v.uniform_eta_aes::<2>(seed, nonce),
+            (4, true) => v.uniform_eta_aes::<4>(seed, nonce),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Sample the mask `y` with the i-th polynomial using the nonce `L * nonce + i`
+    #[inline(always)]
+    fn uniform_gamma1(
+        y: &mut DilithiumPolyVec<L>,
+        prf: &mut Prf,
+        seed: &[u8; CRH_BYTES],
+        nonce: u16,
+    ) {
+        if AES {
+            y.uniform_gamma1_aes::<Z_BITS>(seed, nonce);
+        } else {
+            y.uniform_gamma1::<Z_BITS>(prf, seed, nonce);
+        }
+    }
+
+    /// Expand row `i` of the matrix `A` from `rho`
+    #[inline(always)]
+    pub(crate) fn expand_row(row: &mut DilithiumPolyVec<L>, rho: &[u8; SEED_BYTES], i: usize) {
+        if AES {
+            row.uniform_aes(rho, i as u8);
+        } else {
+            row.uniform_xof::<false>(rho, i as u8);
+        }
+    }
+
+    /// Computes `r = A * v` expanding one row of `A` at a time
+    /// The coefficients of `r` are not reduced.
+    pub(crate) fn expand_mult_a(
+        rho: &[u8; SEED_BYTES],
+        v: &DilithiumPolyVec<L>,
+        r: &mut DilithiumPolyVec<K>,
+    ) {
+        let mut row = DilithiumPolyVec::<L>::default();
+        for (i, r) in r.as_mut().iter_mut().enumerate() {
+            Self::expand_row(&mut row, rho, i);
+            *r = DilithiumPoly::default();
+            r.vector_mul_acc(&row, v);
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn challenge(c: &mut DilithiumPoly, prf: &mut Prf, seed: &[u8; SEED_BYTES]) {
+        match Self::TAU {
+            39 => c.challenge::<39>(prf, seed),
+            49 => c.challenge::<49>(prf, seed),
+            60 => c.challenge::<60>(prf, seed),
+            _ => unreachable!(),
+        }
+    }
+
+    #[inline(always)]
+    fn decompose(v1: &mut DilithiumPolyVec<K>, v0: &mut DilithiumPolyVec<K>) {
+        match Self::GAMMA2 {
+            GAMMA2_88 => v1.decompose::<GAMMA2_88>(v0),
+            GAMMA2_32 => v1.decompose::<GAMMA2_32>(v0),
+            _ => unreachable!(),
+        }
+    }
+
+    #[inline(always)]
+    fn make_hint(
+        h: &mut DilithiumPolyVec<K>,
+        v0: &DilithiumPolyVec<K>,
+        v1: &DilithiumPolyVec<K>,
+    ) -> usize {
+        match Self::GAMMA2 {
+            GAMMA2_88 => h.make_hint::<GAMMA2_88>(v0, v1),
+            GAMMA2_32 => h.make_hint::<GAMMA2_32>(v0, v1),
+            _ => unreachable!(),
+        }
+    }
+
+    #[inline(always)]
+    fn use_hint(v: &mut DilithiumPolyVec<K>, h: &DilithiumPolyVec<K>) {
+        match Self::GAMMA2 {
+            GAMMA2_88 => v.use_hint::<GAMMA2_88>(h),
+            GAMMA2_32 => v.use_hint::<GAMMA2_32>(h),
+            _ => unreachable!(),
+        }
+    }
+
+    /// `c = H(mu || w1)`
+    #[inline]
+    fn hash_w1(
+        prf: &mut Prf,
+        mu: &[u8; CRH_BYTES],
+        w1: &DilithiumPolyVec<K>,
+        c: &mut [u8; SEED_BYTES],
+    ) {
+        let mut w1_packed = [[[0u8; W1_BITS]; 32]; K];
+        w1.pack_w1(&mut w1_packed);
+        prf.absorb_parts(&[mu, w1_packed.flatten_twice()]);
+        prf.squeeze(c);
+    }
+
+    /// `mu = CRH(tr || msg)`
+    #[inline]
+    pub(crate) fn compute_mu(
+        prf: &mut Prf,
+        tr: &[u8; SEED_BYTES],
+        msg: &[u8],
+        mu: &mut [u8; CRH_BYTES],
+    ) {
+        prf.absorb_parts(&[tr, msg]);
+        prf.squeeze(mu);
+    }
+
+    /// `(t1, t0) = Power2Round(A * s1 + s2)`
+    pub(crate) fn compute_t(
+        rho: &[u8; SEED_BYTES],
+        s1: &DilithiumPolyVec<L>,
+        s2: &DilithiumPolyVec<K>,
+        t1: &mut DilithiumPolyVec<K>,
+        t0: &mut DilithiumPolyVec<K>,
+    ) {
+        let mut s1hat = *s1;
+        s1hat.ntt();
+
+        Self::expand_mult_a(rho, &s1hat, t1);
+        s1hat.zeroize();
+        t1.reduce32();
+        t1.inv_ntt_tomont();
+        *t1 += s2;
+        t1.caddq();
+        t1.power2round(t0);
+    }
+
+    /// Generate key pair deterministically from `seed`
+    ///
+    /// The expanded seeds and the unpacked secret vectors are zeroized before returning.
+    pub fn keypair(
+        seed: &[u8; SEED_BYTES],
+        sk: &mut SecretKey<K, L, ETA_BITS, AES>,
+        pk: &mut PublicKey<K, AES>,
+    ) {
+        let mut prf = Prf::default();
+
+        let mut seedbuf = [0u8; 2 * SEED_BYTES + CRH_BYTES];
+        prf.absorb(seed);
+        prf.squeeze(&mut seedbuf);
+        let (rho, rest): (&[u8; SEED_BYTES], &[u8; SEED_BYTES + CRH_BYTES]) = seedbuf.dissect_ref();
+        let (rhoprime, key): (&[u8; CRH_BYTES], &[u8; SEED_BYTES]) = rest.dissect_ref();
+
+        let mut s1 = DilithiumPolyVec::<L>::default();
+        let mut s2 = DilithiumPolyVec::<K>::default();
+        Self::uniform_eta(&mut s1, &mut prf, rhoprime, 0);
+        Self::uniform_eta(&mut s2, &mut prf, rhoprime, L as u16);
+
+        let mut t1 = DilithiumPolyVec::<K>::default();
+        let mut t0 = DilithiumPolyVec::<K>::default();
+        Self::compute_t(rho, &s1, &s2, &mut t1, &mut t0);
+
+        pk.rho = *rho;
+        t1.pack_t1(&mut pk.t1);
+
+        sk.rho = *rho;
+        sk.key = *key;
+        pk.hash(&mut prf, &mut sk.tr);
+        s1.pack_eta(&mut sk.s1);
+        s2.pack_eta(&mut sk.s2);
+        t0.pack_t0(&mut sk.t0);
+        seedbuf.zeroize();
+        s1.zeroize();
+        s2.zeroize();
+        t0.zeroize();
+    }
+
+    /// Sign message representative `mu` using the randomness seed `rhoprime`
+    #[inline]
+    pub(crate) fn sign_internal<F: FnMut(Rejection)>(
+        mu: &[u8; CRH_BYTES],
+        rhoprime: &[u8; CRH_BYTES],
+        sk: &SecretKey<K, L, ETA_BITS, AES>,
+        sig: &mut Signature<K, L, Z_BITS, OMEGA, AES>,
+        observe: F,
+    ) {
+        let psk = PreparedSigningKey::new(sk);
+        Self::sign_prepared_internal(mu, rhoprime, &psk, sig, observe);
+    }
+
+    /// Sign message representative `mu` using the randomness seed `rhoprime` and the expanded key `psk`
+    /// The reason of each rejection is passed to `observe`, which is a no-op outside of instrumentation.
+    pub(crate) fn sign_prepared_internal<F: FnMut(Rejection)>(
+        mu: &[u8; CRH_BYTES],
+        rhoprime: &[u8; CRH_BYTES],
+        psk: &PreparedSigningKey<K, L, AES>,
+        sig: &mut Signature<K, L, Z_BITS, OMEGA, AES>,
+        mut observe: F,
+    ) {
+        let mut prf = Prf::default();
+        let (mat, s1, s2, t0) = (&psk.mat, &psk.s1, &psk.s2, &psk.t0);
+
+        let mut y = DilithiumPolyVec::<L>::default();
+        let mut w1 = DilithiumPolyVec::<K>::default();
+        let mut w0 = DilithiumPolyVec::<K>::default();
+        let mut h = DilithiumPolyVec::<K>::default();
+        let mut cp = DilithiumPoly::default();
+
+        let mut nonce = 0u16;
+        loop {
+            // Sample intermediate vector y
+            Self::uniform_gamma1(&mut y, &mut prf, rhoprime, nonce);
+            nonce = nonce.wrapping_add(1);
+
+            // Matrix-vector multiplication
+            let mut z = y;
+            z.ntt();
+            mat.mult_vec(&z, &mut w1);
+            w1.reduce32();
+            w1.inv_ntt_tomont();
+
+            // Decompose w and call the random oracle
+            w1.caddq();
+            Self::decompose(&mut w1, &mut w0);
+            Self::hash_w1(&mut prf, mu, &w1, &mut sig.c);
+            Self::challenge(&mut cp, &mut prf, &sig.c);
+            cp.ntt();
+
+            // Compute z, reject if it reveals secret
+            s1.pointwise_poly(&cp, &mut z);
+            z.inv_ntt_tomont();
+            z += &y;
+            z.reduce32();
+            if z.chknorm(Self::GAMMA1 - Self::BETA) {
+                observe(Rejection::ZNorm);
+                continue;
+            }
+
+            // Check that subtracting cs2 does not change high bits of w and low bits
+            // do not reveal secret information
+            s2.pointwise_poly(&cp, &mut h);
+            h.inv_ntt_tomont();
+            w0 -= &h;
+            w0.reduce32();
+            if w0.chknorm(Self::GAMMA2 - Self::BETA) {
+                observe(Rejection::R0Norm);
+                continue;
+            }
+
+            // Compute hints for w1
+            t0.pointwise_poly(&cp, &mut h);
+            h.inv_ntt_tomont();
+            h.reduce32();
+            if h.chknorm(Self::GAMMA2) {
+                observe(Rejection::Ct0Norm);
+                continue;
+            }
+
+            w0 += &h;
+            if Self::make_hint(&mut h, &w0, &w1) > OMEGA {
+                observe(Rejection::HintCount);
+                continue;
+            }
+
+            z.pack_z(&mut sig.z);
+            h.pack_hint(&mut sig.h_idx, &mut sig.h_cnt);
+            return;
+        }
+    }
+
+    /// Deterministic signature of `msg`
+    pub fn sign(
+        msg: &[u8],
+        sk: &SecretKey<K, L, ETA_BITS, AES>,
+        sig: &mut Signature<K, L, Z_BITS, OMEGA, AES>,
+    ) {
+        let mut prf = Prf::default();
+        let mut mu = [0u8; CRH_BYTES];
+        Self::compute_mu(&mut prf, &sk.tr, msg, &mut mu);
+        Self::sign_mu(&mu, sk, sig);
+    }
+
+    /// Deterministic signature of the message representative `mu`, e.g. as computed by
+    /// [`Self::external_mu`] on a different device than the one holding the secret key
+    pub fn sign_mu(
+        mu: &[u8; CRH_BYTES],
+        sk: &SecretKey<K, L, ETA_BITS, AES>,
+        sig: &mut Signature<K, L, Z_BITS, OMEGA, AES>,
+    ) {
+        let mut rhoprime = [0u8; CRH_BYTES];
+        Self::deterministic_rhoprime(&sk.key, mu, &mut rhoprime);
+        Self::sign_internal(mu, &rhoprime, sk, sig, |_| {});
+    }
+
+    /// `rhoprime = CRH(key || mu)`
+    #[inline]
+    pub(crate) fn deterministic_rhoprime(
+        key: &[u8; SEED_BYTES],
+        mu: &[u8; CRH_BYTES],
+        rhoprime: &mut [u8; CRH_BYTES],
+    ) {
+        let mut prf = Prf::default();
+        prf.absorb_parts(&[key, mu]);
+        prf.squeeze(rhoprime);
+    }
+
+    /// Randomized signature of `msg`, with the per-signature randomness drawn from `rng`
+    pub fn sign_randomized<R: RngCore + CryptoRng>(
+        msg: &[u8],
+        sk: &SecretKey<K, L, ETA_BITS, AES>,
+        rng: &mut R,
+        sig: &mut Signature<K, L, Z_BITS, OMEGA, AES>,
+    ) -> Result<(), DilithiumError> {
+        let mut prf = Prf::default();
+        let mut mu = [0u8; CRH_BYTES];
+        Self::compute_mu(&mut prf, &sk.tr, msg, &mut mu);
+        Self::sign_mu_randomized(&mu, sk, rng, sig)
+    }
+
+    /// Randomized signature of the message representative `mu`
+    pub fn sign_mu_randomized<R: RngCore + CryptoRng>(
+        mu: &[u8; CRH_BYTES],
+        sk: &SecretKey<K, L, ETA_BITS, AES>,
+        rng: &mut R,
+        sig: &mut Signature<K, L, Z_BITS, OMEGA, AES>,
+    ) -> Result<(), DilithiumError> {
+        let mut rhoprime = [0u8; CRH_BYTES];
+        rng.try_fill_bytes(&mut rhoprime)
+            .or(Err(DilithiumError::RngFailure))?;
+
+        Self::sign_internal(mu, &rhoprime, sk, sig, |_| {});
+        Ok(())
+    }
+
+    /// Verify signature `sig` of the message representative `mu`
+    pub fn verify_mu(
+        mu: &[u8; CRH_BYTES],
+        sig: &Signature<K, L, Z_BITS, OMEGA, AES>,
+        pk: &PublicKey<K, AES>,
+    ) -> Result<(), DilithiumError> {
+        let mut t1 = DilithiumPolyVec::<K>::default();
+        t1.unpack_t1(&pk.t1);
+        t1.shiftl();
+        t1.ntt();
+
+        Self::verify_with(mu, sig, &t1, |z, w1| Self::expand_mult_a(&pk.rho, z, w1))
+    }
+
+    /// Verify signature `sig` of `mu`, where `mult_a` computes the matrix-vector product `A * z` in NTT
+    /// domain and `t1` is `t1 * 2^D` in NTT domain
+    pub(crate) fn verify_with<F>(
+        mu: &[u8; CRH_BYTES],
+        sig: &Signature<K, L, Z_BITS, OMEGA, AES>,
+        t1: &DilithiumPolyVec<K>,
+        mult_a: F,
+    ) -> Result<(), DilithiumError>
+    where
+        F: FnOnce(&DilithiumPolyVec<L>, &mut DilithiumPolyVec<K>),
+    {
+        let mut prf = Prf::default();
+
+        let mut z = DilithiumPolyVec::<L>::default();
+        let mut h = DilithiumPolyVec::<K>::default();
+        z.unpack_z(&sig.z);
+        if !h.unpack_hint(&sig.h_idx, &sig.h_cnt) || z.chknorm(Self::GAMMA1 - Self::BETA) {
+            return Err(DilithiumError::InvalidSignature);
+        }
+
+        let mut cp = DilithiumPoly::default();
+        Self::challenge(&mut cp, &mut prf, &sig.c);
+
+        // Matrix-vector multiplication; compute Az - c2^dt1
+        z.ntt();
+        let mut w1 = DilithiumPolyVec::<K>::default();
+        mult_a(&z, &mut w1);
+
+        cp.ntt();
+        let mut ct1 = DilithiumPolyVec::<K>::default();
+        t1.pointwise_poly(&cp, &mut ct1);
+
+        w1 -= &ct1;
+        w1.reduce32();
+        w1.inv_ntt_tomont();
+
+        // Reconstruct w1
+        w1.caddq();
+        Self::use_hint(&mut w1, &h);
+
+        // Call random oracle and verify challenge
+        let mut c2 = [0u8; SEED_BYTES];
+        Self::hash_w1(&mut prf, mu, &w1, &mut c2);
+        if c2 != sig.c {
+            return Err(DilithiumError::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    /// Message representative `mu = CRH(H(pk) || msg)` of `msg`, which only depends on the public key
+    pub fn external_mu(pk: &PublicKey<K, AES>, msg: &[u8], mu: &mut [u8; CRH_BYTES]) {
+        let mut prf = Prf::default();
+        let mut tr = [0u8; SEED_BYTES];
+        pk.hash(&mut prf, &mut tr);
+        Self::compute_mu(&mut prf, &tr, msg, mu);
+    }
+
+    /// Verify signature `sig` of message `msg`
+    pub fn verify(
+        msg: &[u8],
+        sig: &Signature<K, L, Z_BITS, OMEGA, AES>,
+        pk: &PublicKey<K, AES>,
+    ) -> Result<(), DilithiumError> {
+        let mut mu = [0u8; CRH_BYTES];
+        Self::external_mu(pk, msg, &mut mu);
+        Self::verify_mu(&mu, sig, pk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dilithium::*;
+    use rand::{Rng, RngCore};
+
+    const NUM_TESTS: usize = if cfg!(miri) { 1 } else { 10 };
+
+    fn sign_verify<
+        const K: usize,
+        const L: usize,
+        const ETA_BITS: usize,
+        const Z_BITS: usize,
+        const W1_BITS: usize,
+        const OMEGA: usize,
+        const AES: bool,
+    >() {
+        let mut rng = rand::thread_rng();
+        let mut sk = SecretKey::<K, L, ETA_BITS, AES>::default();
+        let mut pk = PublicKey::<K, AES>::default();
+        let mut sig = Signature::<K, L, Z_BITS, OMEGA, AES>::default();
+
+        for _ in 0..NUM_TESTS {
+            let seed: [u8; SEED_BYTES] = rng.gen();
+            let mut msg = [0u8; 59];
+            rng.fill_bytes(&mut msg);
+
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::keypair(
+                &seed, &mut sk, &mut pk,
+            );
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::sign(&msg, &sk, &mut sig);
+            assert_eq!(
+                Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::verify(&msg, &sig, &pk),
+                Ok(())
+            );
+
+            // deterministic
+            let mut sig2 = Signature::default();
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::sign(&msg, &sk, &mut sig2);
+            assert_eq!(sig, sig2);
+
+            // external mu
+            let mut mu = [0u8; CRH_BYTES];
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::external_mu(
+                &pk, &msg, &mut mu,
+            );
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::sign_mu(&mu, &sk, &mut sig2);
+            assert_eq!(sig, sig2);
+            assert_eq!(
+                Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::verify_mu(&mu, &sig, &pk),
+                Ok(())
+            );
+            mu[0] ^= 1;
+            assert_eq!(
+                Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::verify_mu(&mu, &sig, &pk),
+                Err(DilithiumError::InvalidSignature)
+            );
+
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::sign_randomized(
+                &msg, &sk, &mut rng, &mut sig2,
+            )
+            .unwrap();
+            assert_eq!(
+                Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::verify(&msg, &sig2, &pk),
+                Ok(())
+            );
+
+            // wrong message
+            msg[rng.gen_range(0..msg.len())] ^= 1 << rng.gen_range(0..8);
+            assert_eq!(
+                Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::verify(&msg, &sig, &pk),
+                Err(DilithiumError::InvalidSignature)
+            );
+            msg[..].fill(0);
+
+            // tampered signature
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::sign(&msg, &sk, &mut sig);
+            let mut bytes = [0u8; 4595];
+            let bytes = &mut bytes[..Signature::<K, L, Z_BITS, OMEGA>::BYTES];
+            sig.write_bytes(bytes);
+            assert_eq!(Signature::from_bytes(bytes), Ok(sig));
+            bytes[rng.gen_range(0..SEED_BYTES + L * 32 * Z_BITS)] ^= 1 << rng.gen_range(0..8);
+            let sig2 = Signature::from_bytes(bytes).unwrap();
+            assert_eq!(
+                Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::verify(&msg, &sig2, &pk),
+                Err(DilithiumError::InvalidSignature)
+            );
+        }
+    }
+
+    #[test]
+    fn sign_verify_dilithium2() {
+        sign_verify::<4, 4, 3, 18, 6, 80, false>();
+    }
+
+    #[test]
+    fn sign_verify_dilithium3() {
+        sign_verify::<6, 5, 4, 20, 4, 55, false>();
+    }
+
+    #[test]
+    fn sign_verify_dilithium5() {
+        sign_verify::<8, 7, 3, 20, 4, 75, false>();
+    }
+
+    #[test]
+    fn sign_verify_dilithium2_aes() {
+        sign_verify::<4, 4, 3, 18, 6, 80, true>();
+    }
+
+    #[test]
+    fn sign_verify_dilithium3_aes() {
+        sign_verify::<6, 5, 4, 20, 4, 55, true>();
+    }
+
+    #[test]
+    fn sign_verify_dilithium5_aes() {
+        sign_verify::<8, 7, 3, 20, 4, 75, true>();
+    }
+
+    #[test]
+    fn aes_keypair_differs() {
+        let seed = [3; SEED_BYTES];
+        let mut sk = Dilithium2SecretKey::default();
+        let mut sk_aes = Dilithium2AesSecretKey::default();
+        let mut pk = Dilithium2PublicKey::default();
+        let mut pk_aes = Dilithium2AesPublicKey::default();
+        Dilithium2::keypair(&seed, &mut sk, &mut pk);
+        Dilithium2Aes::keypair(&seed, &mut sk_aes, &mut pk_aes);
+        assert_eq!(pk.rho, pk_aes.rho);
+        assert_ne!(pk.t1, pk_aes.t1);
+
+        let msg = [5u8; 10];
+        let mut sig = Dilithium2AesSignature::default();
+        Dilithium2Aes::sign(&msg, &sk_aes, &mut sig);
+        assert_eq!(Dilithium2Aes::verify(&msg, &sig, &pk_aes), Ok(()));
+
+        // the types only match after an explicit conversion through the encoding
+        let mut bytes = [0u8; Dilithium2::SIGNATURE_BYTES];
+        sig.write_bytes(&mut bytes);
+        let sig = Dilithium2Signature::from_bytes(&bytes).unwrap();
+        let mut bytes = [0u8; Dilithium2::PUBLIC_KEY_BYTES];
+        pk_aes.write_bytes(&mut bytes);
+        let pk_aes = Dilithium2PublicKey::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            Dilithium2::verify(&msg, &sig, &pk_aes),
+            Err(DilithiumError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn encoded_sizes() {
+        assert_eq!(Dilithium2::PUBLIC_KEY_BYTES, 1312);
+        assert_eq!(Dilithium2::SECRET_KEY_BYTES, 2528);
+        assert_eq!(Dilithium2::SIGNATURE_BYTES, 2420);
+        assert_eq!(Dilithium3::PUBLIC_KEY_BYTES, 1952);
+        assert_eq!(Dilithium3::SECRET_KEY_BYTES, 4000);
+        assert_eq!(Dilithium3::SIGNATURE_BYTES, 3293);
+        assert_eq!(Dilithium5::PUBLIC_KEY_BYTES, 2592);
+        assert_eq!(Dilithium5::SECRET_KEY_BYTES, 4864);
+        assert_eq!(Dilithium5::SIGNATURE_BYTES, 4595);
+    }
+
+    #[test]
+    fn key_encoding() {
+        let mut sk = Dilithium3SecretKey::default();
+        let mut pk = Dilithium3PublicKey::default();
+        Dilithium3::keypair(&[7; SEED_BYTES], &mut sk, &mut pk);
+
+        let mut pk_bytes = [0u8; Dilithium3::PUBLIC_KEY_BYTES];
+        pk.write_bytes(&mut pk_bytes);
+        assert_eq!(PublicKey::from_bytes(&pk_bytes), Ok(pk));
+        assert_eq!(
+            Dilithium3PublicKey::from_bytes(&pk_bytes[1..]),
+            Err(DilithiumError::InvalidLength)
+        );
+
+        let mut sk_bytes = [0u8; Dilithium3::SECRET_KEY_BYTES];
+        sk.write_bytes(&mut sk_bytes);
+        let mut sk_bytes2 = [0u8; Dilithium3::SECRET_KEY_BYTES];
+        Dilithium3SecretKey::from_bytes(&sk_bytes)
+            .unwrap()
+            .write_bytes(&mut sk_bytes2);
+        assert_eq!(sk_bytes, sk_bytes2);
+    }
+}
@@ -0,0 +1,229 @@
+//! Instrumentation of the rejection loop
+//!
+//! With the `instrumentation` feature, the `*_with_stats` signing functions report the number of iterations
+//! of the rejection loop and which condition caused each rejection, e.g. to estimate abort probabilities of
+//! parameter sets. The signatures are identical to those of the uninstrumented functions.
+//! Without the feature only the internal [`Rejection`] reasons exist, which compile away.
+
+#[cfg(feature = "instrumentation")]
+use super::{Dilithium, DilithiumError, PreparedSigningKey, Prf, SecretKey, Signature, CRH_BYTES};
+#[cfg(feature = "instrumentation")]
+use rand::{CryptoRng, RngCore};
+
+/// Condition of the rejection loop that caused an abort
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    /// `||z||_inf >= GAMMA1 - BETA`
+    ZNorm,
+    /// `||r0||_inf >= GAMMA2 - BETA`, with `r0` the low bits of `w - c s2`
+    R0Norm,
+    /// `||c t0||_inf >= GAMMA2`
+    Ct0Norm,
+    /// More than `OMEGA` hints
+    HintCount,
+}
+
+/// Statistics of a single signing operation
+///
+/// All signing functions reject at the same iterations, so `iterations` is the same for all of them. The
+/// per-condition counts are those of the first condition detected in each rejected iteration. The low-memory
+/// loop checks `w` row by row, so it can report a different condition than the other functions for the same
+/// iteration, e.g. `Ct0Norm` on row 0 where the others report `R0Norm` on row 1.
+#[cfg(feature = "instrumentation")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SigningStats {
+    /// Iterations of the rejection loop, including the final accepted one
+    pub iterations: u32,
+    pub z_norm: u32,
+    pub r0_norm: u32,
+    pub ct0_norm: u32,
+    pub hint_count: u32,
+}
+
+#[cfg(feature = "instrumentation")]
+impl SigningStats {
+    /// Number of rejections, `iterations - 1`
+    pub fn rejections(&self) -> u32 {
+        self.z_norm + self.r0_norm + self.ct0_norm + self.hint_count
+    }
+
+    /// Count rejection `r`
+    pub fn record(&mut self, r: Rejection) {
+        match r {
+            Rejection::ZNorm => self.z_norm += 1,
+            Rejection::R0Norm => self.r0_norm += 1,
+            Rejection::Ct0Norm => self.ct0_norm += 1,
+            Rejection::HintCount => self.hint_count += 1,
+        }
+    }
+
+    /// Reset and run `f`, which reports the rejections of one signing operation
+    fn collect<F: FnOnce(&mut dyn FnMut(Rejection))>(&mut self, f: F) {
+        *self = Self::default();
+        f(&mut |r| self.record(r));
+        self.iterations = self.rejections() + 1;
+    }
+}
+
+#[cfg(feature = "instrumentation")]
+impl<
+        const K: usize,
+        const L: usize,
+        const ETA_BITS: usize,
+        const Z_BITS: usize,
+        const W1_BITS: usize,
+        const OMEGA: usize,
+        const AES: bool,
+    > Dilithium<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>
+{
+    /// [`Self::sign`], with the statistics of the rejection loop written to `stats`
+    pub fn sign_with_stats(
+        msg: &[u8],
+        sk: &SecretKey<K, L, ETA_BITS, AES>,
+        sig: &mut Signature<K, L, Z_BITS, OMEGA, AES>,
+        stats: &mut SigningStats,
+    ) {
+        let mut mu = [0u8; CRH_BYTES];
+        Self::compute_mu(&mut Prf::default(), &sk.tr, msg, &mut mu);
+        let mut rhoprime = [0u8; CRH_BYTES];
+        Self::deterministic_rhoprime(&sk.key, &mu, &mut rhoprime);
+        stats.collect(|observe| Self::sign_internal(&mu, &rhoprime, sk, sig, observe));
+    }
+
+    /// [`Self::sign_randomized`], with the statistics of the rejection loop written to `stats`
+    pub fn sign_randomized_with_stats<R: RngCore + CryptoRng>(
+        msg: &[u8],
+        sk: &SecretKey<K, L, ETA_BITS, AES>,
+        rng: &mut R,
+        sig: &mut Signature<K, L, Z_BITS, OMEGA, AES>,
+        stats: &mut SigningStats,
+    ) -> Result<(), DilithiumError> {
+        let mut mu = [0u8; CRH_BYTES];
+        Self::compute_mu(&mut Prf::default(), &sk.tr, msg, &mut mu);
+        let mut rhoprime = [0u8; CRH_BYTES];
+        rng.try_fill_bytes(&mut rhoprime)
+            .or(Err(DilithiumError::RngFailure))?;
+        stats.collect(|observe| Self::sign_internal(&mu, &rhoprime, sk, sig, observe));
+        Ok(())
+    }
+
+    /// [`Self::sign_prepared`], with the statistics of the rejection loop written to `stats`
+    pub fn sign_prepared_with_stats(
+        msg: &[u8],
+        psk: &PreparedSigningKey<K, L, AES>,
+        sig: &mut Signature<K, L, Z_BITS, OMEGA, AES>,
+        stats: &mut SigningStats,
+    ) {
+        let mut mu = [0u8; CRH_BYTES];
+        Self::compute_mu(&mut Prf::default(), &psk.tr, msg, &mut mu);
+        let mut rhoprime = [0u8; CRH_BYTES];
+        Self::deterministic_rhoprime(&psk.key, &mu, &mut rhoprime);
+        stats.collect(|observe| Self::sign_prepared_internal(&mu, &rhoprime, psk, sig, observe));
+    }
+
+    /// [`Self::sign_low_ram`], with the statistics of the rejection loop written to `stats`
+    ///
+    /// Only `iterations` is comparable with the other functions, see [`SigningStats`].
+    pub fn sign_low_ram_with_stats(
+        msg: &[u8],
+        sk: &SecretKey<K, L, ETA_BITS, AES>,
+        sig: &mut Signature<K, L, Z_BITS, OMEGA, AES>,
+        stats: &mut SigningStats,
+    ) {
+        let mut mu = [0u8; CRH_BYTES];
+        Self::compute_mu(&mut Prf::default(), &sk.tr, msg, &mut mu);
+        let mut rhoprime = [0u8; CRH_BYTES];
+        Self::deterministic_rhoprime(&sk.key, &mu, &mut rhoprime);
+        stats.collect(|observe| Self::sign_low_ram_internal(&mu, &rhoprime, sk, sig, observe));
+    }
+}
+
+#[cfg(all(test, feature = "instrumentation"))]
+mod tests {
+    use super::*;
+    use crate::dilithium::PublicKey;
+    use rand::{Rng, RngCore};
+
+    fn signing_stats<
+        const K: usize,
+        const L: usize,
+        const ETA_BITS: usize,
+        const Z_BITS: usize,
+        const W1_BITS: usize,
+        const OMEGA: usize,
+    >() {
+        let mut rng = rand::thread_rng();
+        let mut sk = SecretKey::<K, L, ETA_BITS>::default();
+        let mut pk = PublicKey::<K>::default();
+        let mut sig = Signature::default();
+        let mut sig2 = Signature::default();
+        let mut stats = SigningStats::default();
+        let mut stats2 = SigningStats::default();
+        let mut total = SigningStats::default();
+
+        for _ in 0..50 {
+            let seed: [u8; 32] = rng.gen();
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA>::keypair(&seed, &mut sk, &mut pk);
+            let mut msg = [0u8; 33];
+            rng.fill_bytes(&mut msg);
+
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA>::sign_with_stats(
+                &msg, &sk, &mut sig, &mut stats,
+            );
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA>::sign(&msg, &sk, &mut sig2);
+            assert_eq!(sig, sig2);
+            assert_eq!(stats.iterations, stats.rejections() + 1);
+
+            let psk = PreparedSigningKey::new(&sk);
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA>::sign_prepared_with_stats(
+                &msg,
+                &psk,
+                &mut sig2,
+                &mut stats2,
+            );
+            assert_eq!(sig, sig2);
+            assert_eq!(stats2, stats);
+
+            // the low-memory loop rejects at the same iterations, but may detect a different condition
+            // first as it checks row by row
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA>::sign_low_ram_with_stats(
+                &msg,
+                &sk,
+                &mut sig2,
+                &mut stats2,
+            );
+            assert_eq!(sig, sig2);
+            assert_eq!(stats2.iterations, stats.iterations);
+
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA>::sign_randomized_with_stats(
+                &msg, &sk, &mut rng, &mut sig, &mut stats,
+            )
+            .unwrap();
+            assert_eq!(
+                Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA>::verify(&msg, &sig, &pk),
+                Ok(())
+            );
+            assert_eq!(stats.iterations, stats.rejections() + 1);
+            total.iterations += stats.iterations;
+            total.z_norm += stats.z_norm;
+        }
+        // the expected number of iterations is between 4 and 5.1 for all parameter sets
+        assert!(total.iterations > 50);
+        assert!(total.z_norm > 0);
+    }
+
+    #[test]
+    fn signing_stats_dilithium2() {
+        signing_stats::<4, 4, 3, 18, 6, 80>();
+    }
+
+    #[test]
+    fn signing_stats_dilithium3() {
+        signing_stats::<6, 5, 4, 20, 4, 55>();
+    }
+
+    #[test]
+    fn signing_stats_dilithium5() {
+        signing_stats::<8, 7, 3, 20, 4, 75>();
+    }
+}
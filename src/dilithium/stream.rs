@@ -0,0 +1,285 @@
+//! Incremental signing and verification of messages which are provided in chunks
+
+use super::{
+    Dilithium, DilithiumError, Prf, PublicKey, SecretKey, Signature, CRH_BYTES, SEED_BYTES,
+};
+use crate::keccak::fips202::{Absorber, Shake256Params};
+
+use rand::{CryptoRng, RngCore};
+
+/// Incremental hashing with `Prf` of inputs which are provided in chunks, e.g. `mu = CRH(tr || msg)`
+#[derive(Clone)]
+pub(crate) struct Hasher(Absorber<Shake256Params, Prf>);
+
+impl Hasher {
+    /// Start hashing with the first chunk `prefix`
+    #[inline]
+    pub(crate) fn new(prefix: &[u8]) -> Self {
+        let mut absorber = Absorber::new();
+        absorber.update(prefix);
+        Self(absorber)
+    }
+
+    #[inline]
+    pub(crate) fn update(&mut self, chunk: &[u8]) {
+        self.0.update(chunk);
+    }
+
+    #[inline]
+    pub(crate) fn finalize(self, out: &mut [u8]) {
+        self.0.finalize_xof().read(out);
+    }
+}
+
+/// Signs a message which is provided in chunks through `update`
+/// The result is the same as signing the concatenation of all chunks with `Dilithium::sign`.
+/// A context can be cloned after a common message prefix, to sign several messages sharing it.
+#[derive(Clone)]
+pub struct SigningContext<
+    'a,
+    const K: usize,
+    const L: usize,
+    const ETA_BITS: usize,
+    const Z_BITS: usize,
+    const W1_BITS: usize,
+    const OMEGA: usize,
+    const AES: bool = false,
+> {
+    sk: &'a SecretKey<K, L, ETA_BITS, AES>,
+    hasher: Hasher,
+}
+
+impl<
+        'a,
+        const K: usize,
+        const L: usize,
+        const ETA_BITS: usize,
+        const Z_BITS: usize,
+        const W1_BITS: usize,
+        const OMEGA: usize,
+        const AES: bool,
+    > SigningContext<'a, K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>
+{
+    pub fn new(sk: &'a SecretKey<K, L, ETA_BITS, AES>) -> Self {
+        Self {
+            sk,
+            hasher: Hasher::new(&sk.tr),
+        }
+    }
+
+    /// Append `chunk` to the message
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.hasher.update(chunk);
+    }
+
+    /// Deterministic signature of the message
+    pub fn finalize(self, sig: &mut Signature<K, L, Z_BITS, OMEGA, AES>) {
+        let mut mu = [0u8; CRH_BYTES];
+        self.hasher.finalize(&mut mu);
+        Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::sign_mu(&mu, self.sk, sig);
+    }
+
+    /// Randomized signature of the message, with the per-signature randomness drawn from `rng`
+    pub fn finalize_randomized<R: RngCore + CryptoRng>(
+        self,
+        rng: &mut R,
+        sig: &mut Signature<K, L, Z_BITS, OMEGA, AES>,
+    ) -> Result<(), DilithiumError> {
+        let mut mu = [0u8; CRH_BYTES];
+        self.hasher.finalize(&mut mu);
+        Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::sign_mu_randomized(
+            &mu, self.sk, rng, sig,
+        )
+    }
+}
+
+/// Verifies the signature of a message which is provided in chunks through `update`
+/// Like [`SigningContext`] it can be cloned after a common message prefix.
+#[derive(Clone)]
+pub struct VerifyingContext<
+    'a,
+    const K: usize,
+    const L: usize,
+    const ETA_BITS: usize,
+    const Z_BITS: usize,
+    const W1_BITS: usize,
+    const OMEGA: usize,
+    const AES: bool = false,
+> {
+    pk: &'a PublicKey<K, AES>,
+    hasher: Hasher,
+}
+
+impl<
+        'a,
+        const K: usize,
+        const L: usize,
+        const ETA_BITS: usize,
+        const Z_BITS: usize,
+        const W1_BITS: usize,
+        const OMEGA: usize,
+        const AES: bool,
+    > VerifyingContext<'a, K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>
+{
+    pub fn new(pk: &'a PublicKey<K, AES>) -> Self {
+        let mut prf = Prf::default();
+        let mut tr = [0u8; SEED_BYTES];
+        pk.hash(&mut prf, &mut tr);
+        Self {
+            pk,
+            hasher: Hasher::new(&tr),
+        }
+    }
+
+    /// Append `chunk` to the message
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.hasher.update(chunk);
+    }
+
+    /// Verify signature `sig` of the message
+    pub fn verify(self, sig: &Signature<K, L, Z_BITS, OMEGA, AES>) -> Result<(), DilithiumError> {
+        let mut mu = [0u8; CRH_BYTES];
+        self.hasher.finalize(&mut mu);
+        Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::verify_mu(&mu, sig, self.pk)
+    }
+}
+
+#[cfg(feature = "std")]
+mod io {
+    use super::*;
+    use std::io::{Result, Write};
+
+    impl<
+            'a,
+            const K: usize,
+            const L: usize,
+            const ETA_BITS: usize,
+            const Z_BITS: usize,
+            const W1_BITS: usize,
+            const OMEGA: usize,
+            const AES: bool,
+        > Write for SigningContext<'a, K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>
+    {
+        #[inline]
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            self.update(buf);
+            Ok(buf.len())
+        }
+
+        #[inline]
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<
+            'a,
+            const K: usize,
+            const L: usize,
+            const ETA_BITS: usize,
+            const Z_BITS: usize,
+            const W1_BITS: usize,
+            const OMEGA: usize,
+            const AES: bool,
+        > Write for VerifyingContext<'a, K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>
+    {
+        #[inline]
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            self.update(buf);
+            Ok(buf.len())
+        }
+
+        #[inline]
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dilithium::*;
+    use rand::Rng;
+
+    const NUM_TESTS: usize = if cfg!(miri) { 1 } else { 10 };
+
+    fn streaming_vs_oneshot<
+        const K: usize,
+        const L: usize,
+        const ETA_BITS: usize,
+        const Z_BITS: usize,
+        const W1_BITS: usize,
+        const OMEGA: usize,
+        const AES: bool,
+    >() {
+        let mut rng = rand::thread_rng();
+        let mut sk = SecretKey::<K, L, ETA_BITS, AES>::default();
+        let mut pk = PublicKey::<K, AES>::default();
+        let mut sig = Signature::<K, L, Z_BITS, OMEGA, AES>::default();
+        let mut sig_stream = Signature::<K, L, Z_BITS, OMEGA, AES>::default();
+
+        for _ in 0..NUM_TESTS {
+            let seed: [u8; SEED_BYTES] = rng.gen();
+            let mut msg = [0u8; 1000];
+            rng.fill(&mut msg[..]);
+            let msg = &msg[..rng.gen_range(0..=msg.len())];
+
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::keypair(
+                &seed, &mut sk, &mut pk,
+            );
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::sign(msg, &sk, &mut sig);
+
+            let mut signer =
+                SigningContext::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::new(&sk);
+            let mut verifier =
+                VerifyingContext::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::new(&pk);
+            let mut rest = msg;
+            while !rest.is_empty() {
+                let (chunk, r) = rest.split_at(rng.gen_range(0..=rest.len().min(300)));
+                signer.update(chunk);
+                verifier.update(chunk);
+                rest = r;
+            }
+            signer.finalize(&mut sig_stream);
+            assert_eq!(sig_stream, sig);
+            assert_eq!(verifier.verify(&sig), Ok(()));
+
+            let mut verifier =
+                VerifyingContext::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::new(&pk);
+            verifier.update(msg);
+            verifier.update(&[0]);
+            assert_eq!(verifier.verify(&sig), Err(DilithiumError::InvalidSignature));
+
+            // a shared prefix absorbed once
+            let split = rng.gen_range(0..=msg.len());
+            let mut signer =
+                SigningContext::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::new(&sk);
+            let mut verifier =
+                VerifyingContext::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::new(&pk);
+            signer.update(&msg[..split]);
+            verifier.update(&msg[..split]);
+            let mut other_signer = signer.clone();
+            let mut other_verifier = verifier.clone();
+            signer.update(&msg[split..]);
+            verifier.update(&msg[split..]);
+            other_signer.update(b"other");
+            signer.finalize(&mut sig_stream);
+            assert_eq!(sig_stream, sig);
+            assert_eq!(verifier.verify(&sig), Ok(()));
+            other_signer.finalize(&mut sig_stream);
+            other_verifier.update(b"other");
+            assert_eq!(other_verifier.verify(&sig_stream), Ok(()));
+        }
+    }
+
+    #[test]
+    fn streaming_sign_verify() {
+        streaming_vs_oneshot::<4, 4, 3, 18, 6, 80, false>();
+        streaming_vs_oneshot::<6, 5, 4, 20, 4, 55, false>();
+        streaming_vs_oneshot::<8, 7, 3, 20, 4, 75, false>();
+        streaming_vs_oneshot::<4, 4, 3, 18, 6, 80, true>();
+        streaming_vs_oneshot::<6, 5, 4, 20, 4, 55, true>();
+        streaming_vs_oneshot::<8, 7, 3, 20, 4, 75, true>();
+    }
+}
@@ -0,0 +1,239 @@
+//! Implementations of the [`signature`] traits for the Dilithium key and signature types
+
+use super::*;
+
+use signature::rand_core::CryptoRngCore;
+use signature::{Error, Keypair, RandomizedSigner, SignatureEncoding, Signer, Verifier};
+
+/// Secret key together with its public key, as required by [`Keypair`]
+#[derive(Clone)]
+pub struct SigningKey<
+    const K: usize,
+    const L: usize,
+    const ETA_BITS: usize,
+    const AES: bool = false,
+> {
+    pub secret_key: SecretKey<K, L, ETA_BITS, AES>,
+    pub public_key: PublicKey<K, AES>,
+}
+
+impl<const K: usize, const L: usize, const ETA_BITS: usize, const AES: bool>
+    SigningKey<K, L, ETA_BITS, AES>
+{
+    pub fn new(secret_key: SecretKey<K, L, ETA_BITS, AES>, public_key: PublicKey<K, AES>) -> Self {
+        Self {
+            secret_key,
+            public_key,
+        }
+    }
+}
+
+impl<const K: usize, const L: usize, const ETA_BITS: usize, const AES: bool> Keypair
+    for SigningKey<K, L, ETA_BITS, AES>
+{
+    type VerifyingKey = PublicKey<K, AES>;
+
+    fn verifying_key(&self) -> Self::VerifyingKey {
+        self.public_key
+    }
+}
+
+impl<
+        'a,
+        const K: usize,
+        const L: usize,
+        const Z_BITS: usize,
+        const OMEGA: usize,
+        const AES: bool,
+    > TryFrom<&'a [u8]> for Signature<K, L, Z_BITS, OMEGA, AES>
+{
+    type Error = Error;
+
+    fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
+        Self::from_bytes(bytes).map_err(|_| Error::new())
+    }
+}
+
+macro_rules! impl_signature_traits {
+    (
+        $scheme:ident,
+        $signing_key:ident,
+        $public_key:ident,
+        $secret_key:ident,
+        $signature:ident,
+        $K:literal,
+        $L:literal,
+        $ETA_BITS:literal,
+        $AES:literal
+    ) => {
+        pub type $signing_key = SigningKey<$K, $L, $ETA_BITS, $AES>;
+
+        impl $signing_key {
+            /// Generate a key pair from `seed`
+            pub fn from_seed(seed: &[u8; SEED_BYTES]) -> Self {
+                let mut secret_key = $secret_key::default();
+                let mut public_key = $public_key::default();
+                $scheme::keypair(seed, &mut secret_key, &mut public_key);
+                Self::new(secret_key, public_key)
+            }
+        }
+
+        impl From<$signature> for [u8; $scheme::SIGNATURE_BYTES] {
+            fn from(sig: $signature) -> Self {
+                let mut bytes = [0u8; $scheme::SIGNATURE_BYTES];
+                sig.write_bytes(&mut bytes);
+                bytes
+            }
+        }
+
+        impl SignatureEncoding for $signature {
+            type Repr = [u8; $scheme::SIGNATURE_BYTES];
+        }
+
+        impl Signer<$signature> for $signing_key {
+            fn try_sign(&self, msg: &[u8]) -> Result<$signature, Error> {
+                let mut sig = $signature::default();
+                $scheme::sign(msg, &self.secret_key, &mut sig);
+                Ok(sig)
+            }
+        }
+
+        impl RandomizedSigner<$signature> for $signing_key {
+            fn try_sign_with_rng(
+                &self,
+                rng: &mut impl CryptoRngCore,
+                msg: &[u8],
+            ) -> Result<$signature, Error> {
+                let mut sig = $signature::default();
+                $scheme::sign_randomized(msg, &self.secret_key, rng, &mut sig)
+                    .map_err(|_| Error::new())?;
+                Ok(sig)
+            }
+        }
+
+        impl Verifier<$signature> for $public_key {
+            fn verify(&self, msg: &[u8], sig: &$signature) -> Result<(), Error> {
+                $scheme::verify(msg, sig, self).map_err(|_| Error::new())
+            }
+        }
+    };
+}
+
+impl_signature_traits!(
+    Dilithium2,
+    Dilithium2SigningKey,
+    Dilithium2PublicKey,
+    Dilithium2SecretKey,
+    Dilithium2Signature,
+    4,
+    4,
+    3,
+    false
+);
+impl_signature_traits!(
+    Dilithium2Aes,
+    Dilithium2AesSigningKey,
+    Dilithium2AesPublicKey,
+    Dilithium2AesSecretKey,
+    Dilithium2AesSignature,
+    4,
+    4,
+    3,
+    true
+);
+impl_signature_traits!(
+    Dilithium3,
+    Dilithium3SigningKey,
+    Dilithium3PublicKey,
+    Dilithium3SecretKey,
+    Dilithium3Signature,
+    6,
+    5,
+    4,
+    false
+);
+impl_signature_traits!(
+    Dilithium3Aes,
+    Dilithium3AesSigningKey,
+    Dilithium3AesPublicKey,
+    Dilithium3AesSecretKey,
+    Dilithium3AesSignature,
+    6,
+    5,
+    4,
+    true
+);
+impl_signature_traits!(
+    Dilithium5,
+    Dilithium5SigningKey,
+    Dilithium5PublicKey,
+    Dilithium5SecretKey,
+    Dilithium5Signature,
+    8,
+    7,
+    3,
+    false
+);
+impl_signature_traits!(
+    Dilithium5Aes,
+    Dilithium5AesSigningKey,
+    Dilithium5AesPublicKey,
+    Dilithium5AesSecretKey,
+    Dilithium5AesSignature,
+    8,
+    7,
+    3,
+    true
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, RngCore};
+
+    macro_rules! test_signature_traits {
+        ($name:ident, $signing_key:ident, $signature:ident) => {
+            #[test]
+            fn $name() {
+                let mut rng = rand::thread_rng();
+                let signing_key = $signing_key::from_seed(&rng.gen());
+                let verifying_key = signing_key.verifying_key();
+                let mut msg = [0u8; 33];
+                rng.fill_bytes(&mut msg);
+
+                let sig: $signature = signing_key.sign(&msg);
+                assert!(verifying_key.verify(&msg, &sig).is_ok());
+
+                let bytes = sig.to_bytes();
+                assert_eq!(bytes.len(), $signature::BYTES);
+                assert_eq!($signature::try_from(&bytes[..]).unwrap(), sig);
+                assert!($signature::try_from(&bytes[1..]).is_err());
+
+                let sig: $signature = signing_key.sign_with_rng(&mut rng, &msg);
+                assert!(verifying_key.verify(&msg, &sig).is_ok());
+
+                msg[0] ^= 1;
+                assert!(verifying_key.verify(&msg, &sig).is_err());
+            }
+        };
+    }
+
+    test_signature_traits!(dilithium2_traits, Dilithium2SigningKey, Dilithium2Signature);
+    test_signature_traits!(dilithium3_traits, Dilithium3SigningKey, Dilithium3Signature);
+    test_signature_traits!(dilithium5_traits, Dilithium5SigningKey, Dilithium5Signature);
+    test_signature_traits!(
+        dilithium2_aes_traits,
+        Dilithium2AesSigningKey,
+        Dilithium2AesSignature
+    );
+    test_signature_traits!(
+        dilithium3_aes_traits,
+        Dilithium3AesSigningKey,
+        Dilithium3AesSignature
+    );
+    test_signature_traits!(
+        dilithium5_aes_traits,
+        Dilithium5AesSigningKey,
+        Dilithium5AesSignature
+    );
+}
@@ -0,0 +1,338 @@
+//! Deterministic random bit generators, to drive key generation and signing reproducibly
+//!
+//! [`Aes256CtrDrbg`] is the AES-256 CTR_DRBG of NIST SP 800-90A, without derivation function and prediction
+//! resistance, as in `rng.c` of the NIST PQC submission package. This is the generator behind the `.rsp` KAT
+//! files: `PQCgenKAT` seeds one instance with the bytes `0..48` to draw the per-test seeds, and reseeds a
+//! second instance with each test's seed before running the scheme with it as `randombytes`. Like `rng.c`, no
+//! reseed interval or request length limit is enforced.
+//!
+//! [`ShakeRng`] is the SHAKE256 output stream of a 32-byte seed, for simulations and fuzzing.
+//!
+//! Neither is meant as a general-purpose RNG: all output is determined by the seed.
+
+use crate::aes::{Aes256, AES256_KEY_BYTES, AES_BLOCK_BYTES, AES_PARALLEL_BLOCKS};
+use crate::keccak::fips202::{Shake256Absorber, Shake256Reader};
+use crate::utils::flatten::FlattenSlice;
+
+use rand::{CryptoRng, RngCore, SeedableRng};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub const CTR_DRBG_SEED_BYTES: usize = AES256_KEY_BYTES + AES_BLOCK_BYTES;
+
+/// AES-256 CTR_DRBG, the `randombytes` of the NIST PQC KAT generator
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct Aes256CtrDrbg {
+    aes: Aes256,
+    v: [u8; AES_BLOCK_BYTES],
+}
+
+impl Aes256CtrDrbg {
+    /// Instantiate from `entropy_input`, the `randombytes_init` of `rng.c` without personalization
+    pub fn new(entropy_input: &[u8; CTR_DRBG_SEED_BYTES]) -> Self {
+        let mut drbg = Self {
+            aes: Aes256::new(&[0u8; AES256_KEY_BYTES]),
+            v: [0u8; AES_BLOCK_BYTES],
+        };
+        drbg.update(Some(entropy_input));
+        drbg
+    }
+
+    /// Instantiate from `entropy_input` xored with `personalization`
+    pub fn with_personalization(
+        entropy_input: &[u8; CTR_DRBG_SEED_BYTES],
+        personalization: &[u8; CTR_DRBG_SEED_BYTES],
+    ) -> Self {
+        let mut seed_material = *entropy_input;
+        for (s, p) in seed_material.iter_mut().zip(personalization.iter()) {
+            *s ^= p;
+        }
+        let drbg = Self::new(&seed_material);
+        seed_material.zeroize();
+        drbg
+    }
+
+    /// Fill `blocks` with the encryptions of the next `blocks.len()` counter values, incrementing `V` as a
+    /// 128-bit big-endian integer before each block
+    #[inline]
+    fn next_blocks<const N: usize>(&mut self, blocks: &mut [[u8; AES_BLOCK_BYTES]; N]) {
+        let mut v = u128::from_be_bytes(self.v);
+        for block in blocks.iter_mut() {
+            v = v.wrapping_add(1);
+            *block = v.to_be_bytes();
+        }
+        self.v = v.to_be_bytes();
+        self.aes.encrypt_blocks(blocks);
+    }
+
+    /// `CTR_DRBG_Update`: derive a new key and `V` from the next three blocks xored with `provided_data`
+    fn update(&mut self, provided_data: Option<&[u8; CTR_DRBG_SEED_BYTES]>) {
+        let mut temp = [[0u8; AES_BLOCK_BYTES]; CTR_DRBG_SEED_BYTES / AES_BLOCK_BYTES];
+        self.next_blocks(&mut temp);
+        if let Some(provided_data) = provided_data {
+            for (t, d) in temp.iter_mut().flatten().zip(provided_data.iter()) {
+                *t ^= d;
+            }
+        }
+        let temp_bytes = temp.flatten_slice();
+        let mut key = [0u8; AES256_KEY_BYTES];
+        key.copy_from_slice(&temp_bytes[..AES256_KEY_BYTES]);
+        self.aes = Aes256::new(&key);
+        self.v.copy_from_slice(&temp_bytes[AES256_KEY_BYTES..]);
+        key.zeroize();
+        temp.zeroize();
+    }
+
+    /// Generate `out.len()` bytes as a single request, i.e. one call of `randombytes`
+    ///
+    /// The output depends on how it is split into requests, since the state is updated after each.
+    pub fn generate(&mut self, out: &mut [u8]) {
+        let mut blocks = [[0u8; AES_BLOCK_BYTES]; AES_PARALLEL_BLOCKS];
+        for chunk in out.chunks_mut(AES_BLOCK_BYTES * AES_PARALLEL_BLOCKS) {
+            let num_blocks = (chunk.len() + AES_BLOCK_BYTES - 1) / AES_BLOCK_BYTES;
+            if num_blocks == AES_PARALLEL_BLOCKS {
+                self.next_blocks(&mut blocks);
+            } else {
+                for block in blocks[..num_blocks].iter_mut() {
+                    self.next_blocks(core::array::from_mut(block));
+                }
+            }
+            chunk.copy_from_slice(&blocks.flatten_slice()[..chunk.len()]);
+        }
+        blocks.zeroize();
+        self.update(None);
+    }
+}
+
+impl RngCore for Aes256CtrDrbg {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+        self.generate(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.generate(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.generate(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.generate(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for Aes256CtrDrbg {}
+
+pub const SHAKE_RNG_SEED_BYTES: usize = 32;
+const SHAKE_RNG_CHAINING_BYTES: usize = 64;
+
+/// Deterministic RNG whose output is SHAKE256(`seed`)
+pub struct ShakeRng {
+    reader: Shake256Reader,
+}
+
+impl ShakeRng {
+    fn from_parts(parts: &[&[u8]]) -> Self {
+        let mut absorber = Shake256Absorber::new();
+        for part in parts {
+            absorber.update(part);
+        }
+        Self {
+            reader: absorber.finalize_xof(),
+        }
+    }
+
+    /// Split off an independent generator, seeded from the next `SHAKE_RNG_SEED_BYTES` of the output
+    ///
+    /// The child's output is that of `ShakeRng::from_seed` of these bytes, which `self` skips.
+    pub fn fork(&mut self) -> Self {
+        let mut seed = [0u8; SHAKE_RNG_SEED_BYTES];
+        self.reader.read(&mut seed);
+        let rng = Self::from_seed(seed);
+        seed.zeroize();
+        rng
+    }
+
+    /// Mix `entropy` into the state: continue with SHAKE256 of the next 64 output bytes followed by `entropy`
+    pub fn reseed(&mut self, entropy: &[u8]) {
+        let mut chaining = [0u8; SHAKE_RNG_CHAINING_BYTES];
+        self.reader.read(&mut chaining);
+        *self = Self::from_parts(&[&chaining, entropy]);
+        chaining.zeroize();
+    }
+}
+
+impl SeedableRng for ShakeRng {
+    type Seed = [u8; SHAKE_RNG_SEED_BYTES];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::from_parts(&[&seed])
+    }
+}
+
+impl RngCore for ShakeRng {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+        self.reader.read(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.reader.read(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.reader.read(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.reader.read(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for ShakeRng {}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::dilithium::SeedSecretKey;
+    use crate::poly::kyber::KyberPoly;
+    use hex_literal::hex;
+    use rand::Rng;
+    use sha3::digest::{ExtendableOutput, Update, XofReader};
+    use std::*;
+
+    fn kat_entropy() -> [u8; CTR_DRBG_SEED_BYTES] {
+        let mut entropy_input = [0u8; CTR_DRBG_SEED_BYTES];
+        for (i, e) in entropy_input.iter_mut().enumerate() {
+            *e = i as u8;
+        }
+        entropy_input
+    }
+
+    #[test]
+    fn kat_seeds() {
+        // the seeds of the first three tests of every PQCgenKAT `.rsp` file
+        let mut drbg = Aes256CtrDrbg::new(&kat_entropy());
+        let mut seed = [0u8; CTR_DRBG_SEED_BYTES];
+        drbg.fill_bytes(&mut seed);
+        assert_eq!(
+            seed,
+            hex!("061550234D158C5EC95595FE04EF7A25767F2E24CC2BC479D09D86DC9ABCFDE7056A8C266F9EF97ED08541DBD2E1FFA1")
+        );
+        drbg.fill_bytes(&mut seed);
+        assert_eq!(
+            seed,
+            hex!("D81C4D8D734FCBFBEADE3D3F8A039FAA2A2C9957E835AD55B22E75BF57BB556AC81ADDE6AEEB4A5A875C3BFCADFA958F")
+        );
+        drbg.fill_bytes(&mut seed);
+        assert_eq!(
+            seed,
+            hex!("64335BF29E5DE62842C941766BA129B0643B5E7121CA26CFC190EC7DC3543830557FDD5C03CF123A456D48EFEA43C868")
+        );
+    }
+
+    #[test]
+    fn requests() {
+        let mut drbg = Aes256CtrDrbg::new(&kat_entropy());
+        let mut out = [0u8; 75];
+        drbg.fill_bytes(&mut out[..5]);
+        drbg.fill_bytes(&mut out[5..]);
+        assert_eq!(
+            out,
+            hex!(
+                "061550234d"
+                "7bada89bf0e1852e7998951ea7268f7f573c52a713871f895bab3c59cd75068f80971c93d09d31b5"
+                "ee0e76829e134c178c4a7694a6ca36362ac36407a1346ae9b6b071a12ec0"
+            )
+        );
+
+        let mut personalization = [0u8; CTR_DRBG_SEED_BYTES];
+        for (i, p) in personalization.iter_mut().enumerate() {
+            *p = (i + CTR_DRBG_SEED_BYTES) as u8;
+        }
+        let mut drbg = Aes256CtrDrbg::with_personalization(&kat_entropy(), &personalization);
+        let mut out = [0u8; 32];
+        drbg.fill_bytes(&mut out);
+        assert_eq!(
+            out,
+            hex!("632716e962c9fe0a9474a3e69507237f265ac66de778c53f776d004234a6b7dd")
+        );
+    }
+
+    fn shake256(parts: &[&[u8]], out: &mut [u8]) {
+        let mut shake = sha3::Shake256::default();
+        for part in parts {
+            shake.update(part);
+        }
+        shake.finalize_xof().read(out);
+    }
+
+    #[test]
+    fn shake_rng() {
+        let mut rng = rand::thread_rng();
+        let seed: [u8; SHAKE_RNG_SEED_BYTES] = rng.gen();
+        let mut golden = [0u8; 1000];
+        shake256(&[&seed], &mut golden);
+
+        let mut shake_rng = ShakeRng::from_seed(seed);
+        let mut out = vec![0u8; 1000 - 12];
+        let split = rng.gen_range(0..=out.len());
+        shake_rng.fill_bytes(&mut out[..split]);
+        shake_rng.fill_bytes(&mut out[split..]);
+        assert_eq!(out, golden[..1000 - 12]);
+        assert_eq!(shake_rng.next_u32().to_le_bytes(), golden[988..992]);
+        assert_eq!(shake_rng.next_u64().to_le_bytes(), golden[992..]);
+    }
+
+    #[test]
+    fn shake_rng_fork_reseed() {
+        let seed = [7u8; SHAKE_RNG_SEED_BYTES];
+        let mut golden = [0u8; SHAKE_RNG_SEED_BYTES + 16 + SHAKE_RNG_CHAINING_BYTES];
+        shake256(&[&seed], &mut golden);
+
+        let mut parent = ShakeRng::from_seed(seed);
+        let mut child = parent.fork();
+        let mut out = [0u8; 16];
+        parent.fill_bytes(&mut out);
+        assert_eq!(out, golden[SHAKE_RNG_SEED_BYTES..SHAKE_RNG_SEED_BYTES + 16]);
+        let mut child_out = [0u8; 16];
+        child.fill_bytes(&mut child_out);
+        let mut child_golden = [0u8; 16];
+        shake256(&[&golden[..SHAKE_RNG_SEED_BYTES]], &mut child_golden);
+        assert_eq!(child_out, child_golden);
+
+        parent.reseed(b"entropy");
+        parent.fill_bytes(&mut out);
+        let mut reseed_golden = [0u8; 16];
+        shake256(
+            &[&golden[SHAKE_RNG_SEED_BYTES + 16..], b"entropy"],
+            &mut reseed_golden,
+        );
+        assert_eq!(out, reseed_golden);
+    }
+
+    #[test]
+    fn shake_rng_keygen() {
+        let mut rng1 = ShakeRng::seed_from_u64(42);
+        let mut rng2 = ShakeRng::seed_from_u64(42);
+        assert_eq!(
+            KyberPoly::new_random(&mut rng1),
+            KyberPoly::new_random(&mut rng2)
+        );
+        assert_eq!(
+            SeedSecretKey::generate(&mut rng1).unwrap().seed,
+            SeedSecretKey::generate(&mut rng2).unwrap().seed
+        );
+    }
+}
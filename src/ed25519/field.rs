@@ -0,0 +1,296 @@
+//! Arithmetic in GF(2^255 - 19) with 5 limbs of 51 bits
+//!
+//! All operations return weakly reduced elements (limbs below 2^52), which are valid inputs to all other
+//! operations. Only [`Fe::to_bytes`] produces the canonical representative.
+
+use crate::lib::ops::{Add, Mul, Neg, Sub};
+
+use zeroize::Zeroize;
+
+const MASK51: u64 = (1 << 51) - 1;
+
+#[derive(Debug, Clone, Copy, Default, Zeroize)]
+pub(crate) struct Fe(pub(crate) [u64; 5]);
+
+pub(crate) const EDWARDS_D: Fe = Fe([
+    0x34dca135978a3,
+    0x1a8283b156ebd,
+    0x5e7a26001c029,
+    0x739c663a03cbb,
+    0x52036cee2b6ff,
+]);
+
+pub(crate) const EDWARDS_D2: Fe = Fe([
+    0x69b9426b2f159,
+    0x35050762add7a,
+    0x3cf44c0038052,
+    0x6738cc7407977,
+    0x2406d9dc56dff,
+]);
+
+/// `sqrt(-1)`
+pub(crate) const SQRT_M1: Fe = Fe([
+    0x61b274a0ea0b0,
+    0x0d5a5fc8f189d,
+    0x7ef5e9cbd0c60,
+    0x78595a6804c9e,
+    0x2b8324804fc1d,
+]);
+
+/// `(p - 5) / 8` in little-endian bytes
+const P58: [u8; 32] = {
+    let mut e = [0xff; 32];
+    e[0] = 0xfd;
+    e[31] = 0x0f;
+    e
+};
+
+/// `p - 2` in little-endian bytes
+const P_MINUS_2: [u8; 32] = {
+    let mut e = [0xff; 32];
+    e[0] = 0xeb;
+    e[31] = 0x7f;
+    e
+};
+
+impl Fe {
+    pub(crate) const ZERO: Self = Self([0; 5]);
+    pub(crate) const ONE: Self = Self([1, 0, 0, 0, 0]);
+
+    /// Carry propagation; limbs up to 2^64 - 1 are reduced below 2^51 + 2^18
+    #[inline(always)]
+    fn weak_reduce(mut l: [u64; 5]) -> Self {
+        let c = [l[0] >> 51, l[1] >> 51, l[2] >> 51, l[3] >> 51, l[4] >> 51];
+        l[0] &= MASK51;
+        l[1] &= MASK51;
+        l[2] &= MASK51;
+        l[3] &= MASK51;
+        l[4] &= MASK51;
+        l[0] += c[4] * 19;
+        l[1] += c[0];
+        l[2] += c[1];
+        l[3] += c[2];
+        l[4] += c[3];
+        Self(l)
+    }
+
+    /// Decode 32 little-endian bytes, ignoring the most significant bit.
+    /// Values in `[p, 2^255)` are not rejected, see [`Self::to_bytes`] for a canonicity check.
+    pub(crate) fn from_bytes(bytes: &[u8; 32]) -> Self {
+        let load = |i: usize| u64::from_le_bytes(bytes[i..i + 8].try_into().unwrap());
+        Self([
+            load(0) & MASK51,
+            (load(6) >> 3) & MASK51,
+            (load(12) >> 6) & MASK51,
+            (load(19) >> 1) & MASK51,
+            (load(24) >> 12) & MASK51,
+        ])
+    }
+
+    /// Canonical little-endian encoding
+    pub(crate) fn to_bytes(self) -> [u8; 32] {
+        let mut l = Self::weak_reduce(self.0).0;
+
+        // q = 1 iff l >= p
+        let mut q = (l[0] + 19) >> 51;
+        q = (l[1] + q) >> 51;
+        q = (l[2] + q) >> 51;
+        q = (l[3] + q) >> 51;
+        q = (l[4] + q) >> 51;
+
+        l[0] += 19 * q;
+        l[1] += l[0] >> 51;
+        l[0] &= MASK51;
+        l[2] += l[1] >> 51;
+        l[1] &= MASK51;
+        l[3] += l[2] >> 51;
+        l[2] &= MASK51;
+        l[4] += l[3] >> 51;
+        l[3] &= MASK51;
+        l[4] &= MASK51;
+
+        let words = [
+            l[0] | (l[1] << 51),
+            (l[1] >> 13) | (l[2] << 38),
+            (l[2] >> 26) | (l[3] << 25),
+            (l[3] >> 39) | (l[4] << 12),
+        ];
+        let mut bytes = [0u8; 32];
+        for (chunk, w) in bytes.chunks_exact_mut(8).zip(words) {
+            chunk.copy_from_slice(&w.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Least significant bit of the canonical representative
+    #[inline]
+    pub(crate) fn is_negative(&self) -> u8 {
+        self.to_bytes()[0] & 1
+    }
+
+    #[inline]
+    pub(crate) fn is_zero(&self) -> bool {
+        self.to_bytes() == [0; 32]
+    }
+
+    #[inline]
+    pub(crate) fn ct_eq(&self, other: &Self) -> bool {
+        (*self - *other).is_zero()
+    }
+
+    /// `b` if `choice == 1`, else `a`
+    #[inline(always)]
+    pub(crate) fn select(a: &Self, b: &Self, choice: u8) -> Self {
+        let mask = (choice as u64).wrapping_neg();
+        let mut r = *a;
+        for (r, b) in r.0.iter_mut().zip(b.0.iter()) {
+            *r ^= (*r ^ b) & mask;
+        }
+        r
+    }
+
+    #[inline]
+    pub(crate) fn square(&self) -> Self {
+        *self * *self
+    }
+
+    /// `self^e`, where the (public) exponent `e` is in little-endian bytes
+    fn pow(&self, e: &[u8; 32]) -> Self {
+        let mut r = Self::ONE;
+        for byte in e.iter().rev() {
+            for i in (0..8).rev() {
+                r = r.square();
+                if (byte >> i) & 1 == 1 {
+                    r = r * *self;
+                }
+            }
+        }
+        r
+    }
+
+    pub(crate) fn invert(&self) -> Self {
+        self.pow(&P_MINUS_2)
+    }
+
+    /// `self^((p - 5) / 8)`
+    pub(crate) fn pow_p58(&self) -> Self {
+        self.pow(&P58)
+    }
+}
+
+impl Add for Fe {
+    type Output = Self;
+
+    #[inline(always)]
+    fn add(self, rhs: Self) -> Self {
+        let (a, b) = (self.0, rhs.0);
+        Self::weak_reduce([
+            a[0] + b[0],
+            a[1] + b[1],
+            a[2] + b[2],
+            a[3] + b[3],
+            a[4] + b[4],
+        ])
+    }
+}
+
+impl Sub for Fe {
+    type Output = Self;
+
+    /// `self + 16p - rhs`, which doesn't underflow for weakly reduced inputs
+    #[inline(always)]
+    fn sub(self, rhs: Self) -> Self {
+        const P16_0: u64 = 16 * ((1 << 51) - 19);
+        const P16: u64 = 16 * ((1 << 51) - 1);
+        let (a, b) = (self.0, rhs.0);
+        Self::weak_reduce([
+            (a[0] + P16_0) - b[0],
+            (a[1] + P16) - b[1],
+            (a[2] + P16) - b[2],
+            (a[3] + P16) - b[3],
+            (a[4] + P16) - b[4],
+        ])
+    }
+}
+
+impl Neg for Fe {
+    type Output = Self;
+
+    #[inline(always)]
+    fn neg(self) -> Self {
+        Self::ZERO - self
+    }
+}
+
+impl Mul for Fe {
+    type Output = Self;
+
+    #[inline(always)]
+    fn mul(self, rhs: Self) -> Self {
+        #[inline(always)]
+        fn m(x: u64, y: u64) -> u128 {
+            x as u128 * y as u128
+        }
+
+        let (a, b) = (self.0, rhs.0);
+        let b1 = b[1] * 19;
+        let b2 = b[2] * 19;
+        let b3 = b[3] * 19;
+        let b4 = b[4] * 19;
+
+        let c0 = m(a[0], b[0]) + m(a[4], b1) + m(a[3], b2) + m(a[2], b3) + m(a[1], b4);
+        let mut c1 = m(a[1], b[0]) + m(a[0], b[1]) + m(a[4], b2) + m(a[3], b3) + m(a[2], b4);
+        let mut c2 = m(a[2], b[0]) + m(a[1], b[1]) + m(a[0], b[2]) + m(a[4], b3) + m(a[3], b4);
+        let mut c3 = m(a[3], b[0]) + m(a[2], b[1]) + m(a[1], b[2]) + m(a[0], b[3]) + m(a[4], b4);
+        let mut c4 = m(a[4], b[0]) + m(a[3], b[1]) + m(a[2], b[2]) + m(a[1], b[3]) + m(a[0], b[4]);
+
+        c1 += c0 >> 51;
+        c2 += c1 >> 51;
+        c3 += c2 >> 51;
+        c4 += c3 >> 51;
+        let mut l = [
+            c0 as u64 & MASK51,
+            c1 as u64 & MASK51,
+            c2 as u64 & MASK51,
+            c3 as u64 & MASK51,
+            c4 as u64 & MASK51,
+        ];
+        // c4 < 2^111 for limbs below 2^52, so the carry times 19 fits into 64 bits
+        l[0] += (c4 >> 51) as u64 * 19;
+        l[1] += l[0] >> 51;
+        l[0] &= MASK51;
+        Self(l)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn fe_invert() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let mut bytes: [u8; 32] = rng.gen();
+            bytes[31] &= 0x3f;
+            let a = Fe::from_bytes(&bytes);
+            assert_eq!(a.to_bytes(), bytes);
+            assert_eq!((a * a.invert()).to_bytes(), Fe::ONE.to_bytes());
+            assert_eq!((a - a).to_bytes(), [0; 32]);
+            assert_eq!((a + -a).to_bytes(), [0; 32]);
+        }
+        assert_eq!((SQRT_M1.square() + Fe::ONE).to_bytes(), [0; 32]);
+    }
+
+    #[test]
+    fn fe_canonical() {
+        // p and p + 1 encode as 0 and 1
+        let mut p = [0xff; 32];
+        p[0] = 0xed;
+        p[31] = 0x7f;
+        assert_eq!(Fe::from_bytes(&p).to_bytes(), [0; 32]);
+        p[0] += 1;
+        assert_eq!(Fe::from_bytes(&p).to_bytes(), Fe::ONE.to_bytes());
+    }
+}
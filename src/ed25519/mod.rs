@@ -0,0 +1,289 @@
+//! Ed25519 signatures (RFC 8032)
+//!
+//! Signing is constant-time: the secret scalar and the nonce are only used in the fixed-window scalar
+//! multiplication and the bitwise reduction modulo the group order. Verification uses the cofactorless
+//! equation `[S] B = R + [k] A` and rejects non-canonical `S` and public key encodings.
+
+mod field;
+mod point;
+mod scalar;
+
+use crate::sha2::{Sha512, SHA512_DIGEST_BYTES};
+use point::{EdwardsPoint, BASEPOINT};
+use scalar::Scalar;
+
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub const SEED_BYTES: usize = 32;
+pub const PUBLIC_KEY_BYTES: usize = 32;
+pub const SECRET_KEY_BYTES: usize = SEED_BYTES;
+pub const SIGNATURE_BYTES: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ed25519Error {
+    /// Encoded key or signature has the wrong length
+    InvalidLength,
+    /// Signature verification failed
+    InvalidSignature,
+}
+
+/// Encoded point `A`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PublicKey(pub [u8; PUBLIC_KEY_BYTES]);
+
+impl PublicKey {
+    pub const BYTES: usize = PUBLIC_KEY_BYTES;
+
+    /// Panics if `bytes.len() != Self::BYTES`
+    pub fn write_bytes(&self, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&self.0);
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Ed25519Error> {
+        Ok(Self(bytes.try_into().or(Err(Ed25519Error::InvalidLength))?))
+    }
+}
+
+/// Secret key `seed`, expanded into the clamped secret scalar `a` and the nonce `prefix`
+#[derive(Clone, Default, Zeroize, ZeroizeOnDrop)]
+pub struct SecretKey {
+    seed: [u8; SEED_BYTES],
+    scalar: [u8; 32],
+    prefix: [u8; 32],
+    #[zeroize(skip)]
+    public_key: PublicKey,
+}
+
+impl SecretKey {
+    pub const BYTES: usize = SECRET_KEY_BYTES;
+
+    /// Expand `seed` and derive the public key
+    pub fn from_seed(seed: &[u8; SEED_BYTES]) -> Self {
+        let mut h = [0u8; SHA512_DIGEST_BYTES];
+        Sha512::digest_parts(&[seed], &mut h);
+
+        let mut sk = Self {
+            seed: *seed,
+            ..Default::default()
+        };
+        sk.scalar.copy_from_slice(&h[..32]);
+        sk.prefix.copy_from_slice(&h[32..]);
+        h.zeroize();
+        sk.scalar[0] &= 248;
+        sk.scalar[31] &= 127;
+        sk.scalar[31] |= 64;
+        sk.public_key = PublicKey(BASEPOINT.mul(&sk.scalar).compress());
+        sk
+    }
+
+    pub fn seed(&self) -> &[u8; SEED_BYTES] {
+        &self.seed
+    }
+
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    /// Panics if `bytes.len() != Self::BYTES`
+    pub fn write_bytes(&self, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&self.seed);
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Ed25519Error> {
+        let seed = bytes.try_into().or(Err(Ed25519Error::InvalidLength))?;
+        Ok(Self::from_seed(seed))
+    }
+}
+
+/// Signature `(R, S)`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Signature {
+    pub r: [u8; 32],
+    pub s: [u8; 32],
+}
+
+impl Signature {
+    pub const BYTES: usize = SIGNATURE_BYTES;
+
+    /// Panics if `bytes.len() != Self::BYTES`
+    pub fn write_bytes(&self, bytes: &mut [u8]) {
+        assert_eq!(bytes.len(), Self::BYTES);
+        bytes[..32].copy_from_slice(&self.r);
+        bytes[32..].copy_from_slice(&self.s);
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Ed25519Error> {
+        if bytes.len() != Self::BYTES {
+            return Err(Ed25519Error::InvalidLength);
+        }
+        let mut sig = Self::default();
+        sig.r.copy_from_slice(&bytes[..32]);
+        sig.s.copy_from_slice(&bytes[32..]);
+        Ok(sig)
+    }
+}
+
+pub struct Ed25519;
+
+impl Ed25519 {
+    /// `k = SHA-512(R || A || msg) mod L`
+    fn challenge(r: &[u8; 32], pk: &PublicKey, msg: &[u8]) -> Scalar {
+        let mut h = [0u8; SHA512_DIGEST_BYTES];
+        Sha512::digest_parts(&[r, &pk.0, msg], &mut h);
+        Scalar::from_bytes_wide(&h)
+    }
+
+    /// Generate key pair deterministically from `seed`
+    pub fn keypair(seed: &[u8; SEED_BYTES], sk: &mut SecretKey, pk: &mut PublicKey) {
+        *sk = SecretKey::from_seed(seed);
+        *pk = sk.public_key;
+    }
+
+    /// Deterministic signature of `msg`
+    pub fn sign(msg: &[u8], sk: &SecretKey, sig: &mut Signature) {
+        let mut h = [0u8; SHA512_DIGEST_BYTES];
+        Sha512::digest_parts(&[&sk.prefix, msg], &mut h);
+        let mut r = Scalar::from_bytes_wide(&h);
+        h.zeroize();
+
+        sig.r = BASEPOINT.mul(&r.to_bytes()).compress();
+        let k = Self::challenge(&sig.r, &sk.public_key, msg);
+        sig.s = k.mul_add(&sk.scalar, &r).to_bytes();
+        r.zeroize();
+    }
+
+    /// Verify signature `sig` of message `msg`
+    pub fn verify(msg: &[u8], sig: &Signature, pk: &PublicKey) -> Result<(), Ed25519Error> {
+        let a = EdwardsPoint::decompress(&pk.0).ok_or(Ed25519Error::InvalidSignature)?;
+        let s = Scalar::from_canonical_bytes(&sig.s).ok_or(Ed25519Error::InvalidSignature)?;
+        let k = Self::challenge(&sig.r, pk, msg);
+
+        // R' = [S] B - [k] A
+        let r = BASEPOINT
+            .mul(&s.to_bytes())
+            .add(&a.neg().mul(&k.to_bytes()));
+        if r.compress() != sig.r {
+            return Err(Ed25519Error::InvalidSignature);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rand::{Rng, RngCore};
+
+    fn check_vector(seed: [u8; 32], pk: [u8; 32], msg: &[u8], sig: [u8; 64]) {
+        let mut sk_ = SecretKey::default();
+        let mut pk_ = PublicKey::default();
+        Ed25519::keypair(&seed, &mut sk_, &mut pk_);
+        assert_eq!(pk_.0, pk);
+
+        let mut sig_ = Signature::default();
+        Ed25519::sign(msg, &sk_, &mut sig_);
+        let mut bytes = [0u8; SIGNATURE_BYTES];
+        sig_.write_bytes(&mut bytes);
+        assert_eq!(bytes, sig);
+        assert_eq!(Ed25519::verify(msg, &sig_, &pk_), Ok(()));
+    }
+
+    /// RFC 8032, Section 7.1
+    #[test]
+    fn ed25519_rfc8032() {
+        check_vector(
+            hex!("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60"),
+            hex!("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"),
+            b"",
+            hex!(
+                "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e06522490155"
+                "5fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"
+            ),
+        );
+        check_vector(
+            hex!("4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb"),
+            hex!("3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c"),
+            &hex!("72"),
+            hex!(
+                "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da"
+                "085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00"
+            ),
+        );
+        check_vector(
+            hex!("c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7"),
+            hex!("fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025"),
+            &hex!("af82"),
+            hex!(
+                "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac"
+                "18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a"
+            ),
+        );
+    }
+
+    #[test]
+    fn ed25519_sign_verify() {
+        let mut rng = rand::thread_rng();
+        let mut sk = SecretKey::default();
+        let mut pk = PublicKey::default();
+        let mut sig = Signature::default();
+
+        for _ in 0..10 {
+            let seed: [u8; SEED_BYTES] = rng.gen();
+            let mut msg = [0u8; 59];
+            rng.fill_bytes(&mut msg);
+            Ed25519::keypair(&seed, &mut sk, &mut pk);
+            Ed25519::sign(&msg, &sk, &mut sig);
+            assert_eq!(Ed25519::verify(&msg, &sig, &pk), Ok(()));
+
+            let mut bytes = [0u8; SECRET_KEY_BYTES];
+            sk.write_bytes(&mut bytes);
+            let sk2 = SecretKey::from_bytes(&bytes).unwrap();
+            let mut sig2 = Signature::default();
+            Ed25519::sign(&msg, &sk2, &mut sig2);
+            assert_eq!(sig, sig2);
+
+            // wrong message
+            msg[rng.gen_range(0..msg.len())] ^= 1 << rng.gen_range(0..8);
+            assert_eq!(
+                Ed25519::verify(&msg, &sig, &pk),
+                Err(Ed25519Error::InvalidSignature)
+            );
+            msg[..].fill(0);
+
+            // tampered signature
+            Ed25519::sign(&msg, &sk, &mut sig);
+            let mut bytes = [0u8; SIGNATURE_BYTES];
+            sig.write_bytes(&mut bytes);
+            bytes[rng.gen_range(0..SIGNATURE_BYTES)] ^= 1 << rng.gen_range(0..8);
+            let sig2 = Signature::from_bytes(&bytes).unwrap();
+            assert_eq!(
+                Ed25519::verify(&msg, &sig2, &pk),
+                Err(Ed25519Error::InvalidSignature)
+            );
+        }
+    }
+
+    #[test]
+    fn ed25519_non_canonical_s() {
+        let mut sk = SecretKey::default();
+        let mut pk = PublicKey::default();
+        let mut sig = Signature::default();
+        Ed25519::keypair(&[7; SEED_BYTES], &mut sk, &mut pk);
+        Ed25519::sign(b"msg", &sk, &mut sig);
+
+        // S + L is a solution of the verification equation too, but must be rejected
+        let mut carry = 0u128;
+        for (i, l) in scalar::L.iter().enumerate() {
+            let s = u64::from_le_bytes(sig.s[8 * i..8 * i + 8].try_into().unwrap());
+            let t = s as u128 + *l as u128 + carry;
+            sig.s[8 * i..8 * i + 8].copy_from_slice(&(t as u64).to_le_bytes());
+            carry = t >> 64;
+        }
+        assert_eq!(carry, 0);
+        assert_eq!(
+            Ed25519::verify(b"msg", &sig, &pk),
+            Err(Ed25519Error::InvalidSignature)
+        );
+    }
+}
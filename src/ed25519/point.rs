@@ -0,0 +1,202 @@
+//! Points of the twisted Edwards curve `-x^2 + y^2 = 1 + d x^2 y^2` in extended coordinates `(X:Y:Z:T)` with
+//! `x = X/Z`, `y = Y/Z` and `x y = T/Z`
+
+use super::field::{Fe, EDWARDS_D, EDWARDS_D2, SQRT_M1};
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct EdwardsPoint {
+    x: Fe,
+    y: Fe,
+    z: Fe,
+    t: Fe,
+}
+
+/// The base point `B` with `y = 4/5` and positive `x`
+pub(crate) const BASEPOINT: EdwardsPoint = EdwardsPoint {
+    x: Fe([
+        0x62d608f25d51a,
+        0x412a4b4f6592a,
+        0x75b7171a4b31d,
+        0x1ff60527118fe,
+        0x216936d3cd6e5,
+    ]),
+    y: Fe([
+        0x6666666666658,
+        0x4cccccccccccc,
+        0x1999999999999,
+        0x3333333333333,
+        0x6666666666666,
+    ]),
+    z: Fe::ONE,
+    t: Fe([
+        0x68ab3a5b7dda3,
+        0x00eea2a5eadbb,
+        0x2af8df483c27e,
+        0x332b375274732,
+        0x67875f0fd78b7,
+    ]),
+};
+
+impl EdwardsPoint {
+    pub(crate) const IDENTITY: Self = Self {
+        x: Fe::ZERO,
+        y: Fe::ONE,
+        z: Fe::ONE,
+        t: Fe::ZERO,
+    };
+
+    /// Complete addition, "add-2008-hwcd-3" for `a = -1`
+    pub(crate) fn add(&self, other: &Self) -> Self {
+        let a = (self.y - self.x) * (other.y - other.x);
+        let b = (self.y + self.x) * (other.y + other.x);
+        let c = self.t * EDWARDS_D2 * other.t;
+        let d = self.z * (other.z + other.z);
+        let (e, f, g, h) = (b - a, d - c, d + c, b + a);
+        Self {
+            x: e * f,
+            y: g * h,
+            z: f * g,
+            t: e * h,
+        }
+    }
+
+    /// "dbl-2008-hwcd" for `a = -1`
+    pub(crate) fn double(&self) -> Self {
+        let a = self.x.square();
+        let b = self.y.square();
+        let c = self.z.square() + self.z.square();
+        let h = a + b;
+        let e = h - (self.x + self.y).square();
+        let g = a - b;
+        let f = c + g;
+        Self {
+            x: e * f,
+            y: g * h,
+            z: f * g,
+            t: e * h,
+        }
+    }
+
+    pub(crate) fn neg(&self) -> Self {
+        Self {
+            x: -self.x,
+            y: self.y,
+            z: self.z,
+            t: -self.t,
+        }
+    }
+
+    /// `b` if `choice == 1`, else `a`
+    #[inline(always)]
+    fn select(a: &Self, b: &Self, choice: u8) -> Self {
+        Self {
+            x: Fe::select(&a.x, &b.x, choice),
+            y: Fe::select(&a.y, &b.y, choice),
+            z: Fe::select(&a.z, &b.z, choice),
+            t: Fe::select(&a.t, &b.t, choice),
+        }
+    }
+
+    /// `[k] self` for the 256-bit little-endian integer `k`, with a fixed 4-bit window and a
+    /// constant-time table lookup
+    pub(crate) fn mul(&self, k: &[u8; 32]) -> Self {
+        let mut table = [Self::IDENTITY; 16];
+        for i in 1..16 {
+            table[i] = table[i - 1].add(self);
+        }
+
+        let mut r = Self::IDENTITY;
+        for byte in k.iter().rev() {
+            for nibble in [byte >> 4, byte & 0xf] {
+                r = r.double().double().double().double();
+                let mut p = Self::IDENTITY;
+                for (i, q) in table.iter().enumerate() {
+                    let eq = ((i as u8 ^ nibble).wrapping_sub(1) >> 7) & 1;
+                    p = Self::select(&p, q, eq);
+                }
+                r = r.add(&p);
+            }
+        }
+        r
+    }
+
+    pub(crate) fn compress(&self) -> [u8; 32] {
+        let zinv = self.z.invert();
+        let x = self.x * zinv;
+        let y = self.y * zinv;
+        let mut bytes = y.to_bytes();
+        bytes[31] |= x.is_negative() << 7;
+        bytes
+    }
+
+    /// Decoding as in RFC 8032, Section 5.1.3; `None` if `bytes` is not the canonical encoding of a point
+    pub(crate) fn decompress(bytes: &[u8; 32]) -> Option<Self> {
+        let sign = bytes[31] >> 7;
+        let y = Fe::from_bytes(bytes);
+        let mut canonical = y.to_bytes();
+        canonical[31] |= sign << 7;
+        if canonical != *bytes {
+            return None;
+        }
+
+        // x^2 = u / v
+        let yy = y.square();
+        let u = yy - Fe::ONE;
+        let v = yy * EDWARDS_D + Fe::ONE;
+
+        // x = u v^3 (u v^7)^((p - 5) / 8)
+        let v3 = v.square() * v;
+        let mut x = u * v3 * (u * v3.square() * v).pow_p58();
+        let vxx = v * x.square();
+        if vxx.ct_eq(&-u) {
+            x = x * SQRT_M1;
+        } else if !vxx.ct_eq(&u) {
+            return None;
+        }
+        if x.is_zero() && sign == 1 {
+            return None;
+        }
+        if x.is_negative() != sign {
+            x = -x;
+        }
+        Some(Self {
+            x,
+            y,
+            z: Fe::ONE,
+            t: x * y,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basepoint() {
+        let b = BASEPOINT.compress();
+        let mut expected = [0x66; 32];
+        expected[0] = 0x58;
+        assert_eq!(b, expected);
+        assert_eq!(EdwardsPoint::decompress(&b).unwrap().compress(), b);
+
+        // [L] B = 0
+        let l =
+            hex_literal::hex!("edd3f55c1a631258d69cf7a2def9de1400000000000000000000000000000010");
+        assert_eq!(
+            BASEPOINT.mul(&l).compress(),
+            EdwardsPoint::IDENTITY.compress()
+        );
+
+        // [2] B = B + B, [3] B = 2B + B
+        let mut two = [0u8; 32];
+        two[0] = 2;
+        let bb = BASEPOINT.double();
+        assert_eq!(BASEPOINT.mul(&two).compress(), bb.compress());
+        assert_eq!(
+            bb.add(&BASEPOINT).compress(),
+            BASEPOINT.add(&BASEPOINT).add(&BASEPOINT).compress()
+        );
+        assert_eq!(bb.add(&BASEPOINT.neg()).compress(), BASEPOINT.compress());
+    }
+}
@@ -0,0 +1,139 @@
+//! Integers modulo the order `L = 2^252 + 27742317777372353535851937790883648493` of the base point
+//!
+//! The reduction shifts the input in bit by bit with a conditional subtraction of `L`, which is slow but
+//! simple, and constant-time.
+
+use zeroize::Zeroize;
+
+/// `L` in little-endian 64-bit words
+pub(crate) const L: [u64; 4] = [
+    0x5812631a5cf5d3ed,
+    0x14def9dea2f79cd6,
+    0x0000000000000000,
+    0x1000000000000000,
+];
+
+/// Reduced scalar in little-endian 64-bit words
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Zeroize)]
+pub(crate) struct Scalar(pub(crate) [u64; 4]);
+
+fn words<const N: usize>(bytes: &[u8]) -> [u64; N] {
+    let mut w = [0u64; N];
+    for (w, chunk) in w.iter_mut().zip(bytes.chunks_exact(8)) {
+        *w = u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+    w
+}
+
+/// `a - b` and the borrow
+#[inline(always)]
+fn sub_borrow(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], u64) {
+    let mut r = [0u64; 4];
+    let mut borrow = 0;
+    for i in 0..4 {
+        let (d, b1) = a[i].overflowing_sub(b[i]);
+        let (d, b2) = d.overflowing_sub(borrow);
+        r[i] = d;
+        borrow = (b1 | b2) as u64;
+    }
+    (r, borrow)
+}
+
+impl Scalar {
+    /// Reduce the little-endian integer in `w` modulo `L`
+    fn reduce<const N: usize>(w: &[u64; N]) -> Self {
+        let mut acc = [0u64; 4];
+        for i in (0..64 * N).rev() {
+            // acc < L < 2^253, so the shift doesn't overflow
+            acc[3] = (acc[3] << 1) | (acc[2] >> 63);
+            acc[2] = (acc[2] << 1) | (acc[1] >> 63);
+            acc[1] = (acc[1] << 1) | (acc[0] >> 63);
+            acc[0] = (acc[0] << 1) | ((w[i / 64] >> (i % 64)) & 1);
+
+            let (d, borrow) = sub_borrow(&acc, &L);
+            let mask = borrow.wrapping_sub(1);
+            for (a, d) in acc.iter_mut().zip(d) {
+                *a ^= (*a ^ d) & mask;
+            }
+        }
+        Self(acc)
+    }
+
+    /// Reduce 64 little-endian bytes, e.g. a SHA-512 digest
+    pub(crate) fn from_bytes_wide(bytes: &[u8; 64]) -> Self {
+        Self::reduce::<8>(&words(bytes))
+    }
+
+    /// Decode 32 little-endian bytes, `None` if they don't encode an integer below `L`
+    pub(crate) fn from_canonical_bytes(bytes: &[u8; 32]) -> Option<Self> {
+        let w = words(bytes);
+        let (_, borrow) = sub_borrow(&w, &L);
+        if borrow == 1 {
+            Some(Self(w))
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn to_bytes(self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (chunk, w) in bytes.chunks_exact_mut(8).zip(self.0) {
+            chunk.copy_from_slice(&w.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// `(self * a + c) mod L`, where `a` is an arbitrary 256-bit integer in little-endian bytes
+    pub(crate) fn mul_add(&self, a: &[u8; 32], c: &Self) -> Self {
+        let a: [u64; 4] = words(a);
+        let mut prod = [0u64; 8];
+        for i in 0..4 {
+            let mut carry = 0u128;
+            for j in 0..4 {
+                let t = self.0[i] as u128 * a[j] as u128 + prod[i + j] as u128 + carry;
+                prod[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            prod[i + 4] = carry as u64;
+        }
+        // self * a < 2^509, so adding c < 2^253 doesn't overflow
+        let mut carry = 0u128;
+        for (i, p) in prod.iter_mut().enumerate() {
+            let t = *p as u128 + *c.0.get(i).unwrap_or(&0) as u128 + carry;
+            *p = t as u64;
+            carry = t >> 64;
+        }
+        let r = Self::reduce(&prod);
+        prod.zeroize();
+        r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scalar_reduce() {
+        let mut l_bytes = Scalar(L).to_bytes();
+        assert_eq!(Scalar::from_canonical_bytes(&l_bytes), None);
+        assert_eq!(Scalar::reduce(&L), Scalar::default());
+        l_bytes[0] -= 1;
+        assert_eq!(
+            Scalar::from_canonical_bytes(&l_bytes),
+            Some(Scalar(words(&l_bytes)))
+        );
+
+        // 2^512 - 1 mod L
+        let r = Scalar::from_bytes_wide(&[0xff; 64]);
+        assert_eq!(
+            r.to_bytes(),
+            hex_literal::hex!("000f9c44e31106a447938568a71b0ed065bef517d273ecce3d9a307c1b419903")
+        );
+
+        // (L - 1) * (L - 1) + 1 = L^2 - 2L + 2 = 2 mod L
+        let m1 = Scalar::from_canonical_bytes(&l_bytes).unwrap();
+        let one = Scalar([1, 0, 0, 0]);
+        assert_eq!(m1.mul_add(&l_bytes, &one), Scalar([2, 0, 0, 0]));
+    }
+}
@@ -10,7 +10,7 @@ pub(crate) const MONT: i32 = -4186625; // 2^32 % Q
 
 pub(crate) const QINV: i32 = 58_728_449; // q^(-1) mod 2^32
 
-#[derive(Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Zeroize)]
 pub struct DilithiumFq(pub i32);
 
 impl Debug for DilithiumFq {
@@ -92,6 +92,118 @@ fn freeze(a: i32) -> i32 {
     caddq(reduce32(a))
 }
 
+/// Number of dropped bits from `t`
+pub(crate) const D: u32 = 13;
+
+/// Low-order rounding range for Dilithium2
+pub const GAMMA2_88: i32 = (DILITHIUM_Q - 1) / 88;
+
+/// Low-order rounding range for Dilithium3 and Dilithium5
+pub const GAMMA2_32: i32 = (DILITHIUM_Q - 1) / 32;
+
+/// For `a` in `[0, Q)`, compute `(a1, a0)` such that `a = a1 * 2^D + a0` with `-2^{D-1} < a0 <= 2^{D-1}`
+#[inline(always)]
+fn power2round(a: i32) -> (i32, i32) {
+    debug_assert!((0..DILITHIUM_Q).contains(&a));
+    let a1 = (a + (1 << (D - 1)) - 1) >> D;
+    (a1, a - (a1 << D))
+}
+
+/// For `a` in `[0, Q)`, compute high and low bits `(a1, a0)` such that `a = a1 * 2 * GAMMA2 + a0 mod Q` with
+/// `-GAMMA2 < a0 <= GAMMA2`, except for the case `a1 = (Q-1)/(2*GAMMA2)`, where `a1` is set to 0 and
+/// `-GAMMA2 <= a0 = a - Q < 0`
+#[inline(always)]
+fn decompose<const GAMMA2: i32>(a: i32) -> (i32, i32) {
+    debug_assert!((0..DILITHIUM_Q).contains(&a));
+    let mut a1 = (a + 127) >> 7;
+    match GAMMA2 {
+        GAMMA2_32 => {
+            a1 = (a1 * 1025 + (1 << 21)) >> 22;
+            a1 &= 15;
+        }
+        GAMMA2_88 => {
+            a1 = (a1 * 11275 + (1 << 23)) >> 24;
+            a1 ^= ((43 - a1) >> 31) & a1;
+        }
+        _ => unreachable!(),
+    }
+    let mut a0 = a - a1 * 2 * GAMMA2;
+    a0 -= (((DILITHIUM_Q - 1) / 2 - a0) >> 31) & DILITHIUM_Q;
+    (a1, a0)
+}
+
+/// Compute hint bit indicating whether the low bits `a0` overflow into the high bits `a1`
+/// Returns 1 if overflow, 0 otherwise
+#[inline(always)]
+fn make_hint<const GAMMA2: i32>(a0: i32, a1: i32) -> i32 {
+    // all-ones mask if `x` is non-zero
+    #[inline(always)]
+    fn nonzero_mask(x: i32) -> i32 {
+        (x | x.wrapping_neg()) >> 31
+    }
+    let gt = (GAMMA2 - a0) >> 31; // a0 > GAMMA2
+    let lt = (a0 + GAMMA2) >> 31; // a0 < -GAMMA2
+    let eq = !nonzero_mask(a0 + GAMMA2) & nonzero_mask(a1); // a0 == -GAMMA2 && a1 != 0
+    (gt | lt | eq) & 1
+}
+
+/// Correct high bits of `a` according to the hint bit `hint` (0 or 1)
+#[inline(always)]
+fn use_hint<const GAMMA2: i32>(a: i32, hint: i32) -> i32 {
+    debug_assert!(hint == 0 || hint == 1);
+    let (a1, a0) = decompose::<GAMMA2>(a);
+    // +1 if a0 > 0, -1 otherwise
+    let delta = (((-a0) >> 31) & 2) - 1;
+    let a1 = a1 + (hint.wrapping_neg() & delta);
+    match GAMMA2 {
+        GAMMA2_32 => a1 & 15,
+        GAMMA2_88 => {
+            let a1 = a1 + ((a1 >> 31) & 44);
+            a1 & !((43 - a1) >> 31)
+        }
+        _ => unreachable!(),
+    }
+}
+
+impl DilithiumFq {
+    /// Representative of `self` in `[-6283009, 6283007]`, for `self <= 2^31 - 2^22 - 1`
+    #[inline(always)]
+    pub fn reduce32(self) -> Self {
+        Self(reduce32(self.0))
+    }
+}
+
+/// Rounding operations
+impl DilithiumFq {
+    /// Split standard representative `a` into `(a1, a0)` with `a = a1 * 2^D + a0` and
+    /// `-2^{D-1} < a0 <= 2^{D-1}`
+    #[inline(always)]
+    pub fn power2round(self) -> (Self, Self) {
+        let (a1, a0) = power2round(self.0);
+        (Self(a1), Self(a0))
+    }
+
+    /// Split standard representative `a` into high and low bits `(a1, a0)` with
+    /// `a = a1 * 2 * GAMMA2 + a0 mod Q` and `-GAMMA2 <= a0 <= GAMMA2`
+    #[inline(always)]
+    pub fn decompose<const GAMMA2: i32>(self) -> (Self, Self) {
+        let (a1, a0) = decompose::<GAMMA2>(self.0);
+        (Self(a1), Self(a0))
+    }
+
+    /// Hint bit (0 or 1) for low bits `self` and high bits `a1`
+    #[inline(always)]
+    pub fn make_hint<const GAMMA2: i32>(self, a1: Self) -> Self {
+        Self(make_hint::<GAMMA2>(self.0, a1.0))
+    }
+
+    /// High bits of standard representative `self`, corrected according to `hint` (0 or 1)
+    #[inline(always)]
+    pub fn use_hint<const GAMMA2: i32>(self, hint: Self) -> Self {
+        Self(use_hint::<GAMMA2>(self.0, hint.0))
+    }
+}
+
 impl Mul<i32> for DilithiumFq {
     type Output = Self;
 
@@ -174,4 +286,108 @@ mod tests {
             );
         }
     }
+
+    /// stride for exhaustive tests over all residues mod Q
+    const STRIDE: usize = if cfg!(miri) { 100_003 } else { 1 };
+
+    /// centered remainder `a mod± alpha` in `(-alpha/2, alpha/2]` for even `alpha`
+    fn mod_pm(a: i32, alpha: i32) -> i32 {
+        let r = a.rem_euclid(alpha);
+        if r > alpha / 2 {
+            r - alpha
+        } else {
+            r
+        }
+    }
+
+    fn decompose_spec(a: i32, gamma2: i32) -> (i32, i32) {
+        let r0 = mod_pm(a, 2 * gamma2);
+        if a - r0 == DILITHIUM_Q - 1 {
+            (0, r0 - 1)
+        } else {
+            ((a - r0) / (2 * gamma2), r0)
+        }
+    }
+
+    #[test]
+    fn power2round_spec() {
+        for a in (0..DILITHIUM_Q).step_by(STRIDE) {
+            let r0 = mod_pm(a, 1 << D);
+            assert_eq!(power2round(a), ((a - r0) >> D, r0), "a={a}");
+        }
+    }
+
+    fn rounding_spec<const GAMMA2: i32>() {
+        let m = (DILITHIUM_Q - 1) / (2 * GAMMA2);
+        for a in (0..DILITHIUM_Q).step_by(STRIDE) {
+            let (a1, a0) = decompose_spec(a, GAMMA2);
+            assert_eq!(decompose::<GAMMA2>(a), (a1, a0), "a={a}");
+            assert_eq!(use_hint::<GAMMA2>(a, 0), a1, "a={a}");
+            let a1_hinted = if a0 > 0 {
+                (a1 + 1) % m
+            } else {
+                (a1 - 1).rem_euclid(m)
+            };
+            assert_eq!(use_hint::<GAMMA2>(a, 1), a1_hinted, "a={a}");
+        }
+        for a0 in (-DILITHIUM_Q..=DILITHIUM_Q).step_by(STRIDE) {
+            for a1 in [0, 1, m - 1] {
+                let h = (a0 > GAMMA2 || a0 < -GAMMA2 || (a0 == -GAMMA2 && a1 != 0)) as i32;
+                assert_eq!(make_hint::<GAMMA2>(a0, a1), h, "a0={a0} a1={a1}");
+            }
+        }
+        for a0 in [-GAMMA2 - 1, -GAMMA2, -GAMMA2 + 1, GAMMA2, GAMMA2 + 1] {
+            for a1 in 0..m {
+                let h = (a0 > GAMMA2 || a0 < -GAMMA2 || (a0 == -GAMMA2 && a1 != 0)) as i32;
+                assert_eq!(make_hint::<GAMMA2>(a0, a1), h, "a0={a0} a1={a1}");
+            }
+        }
+    }
+
+    #[test]
+    fn rounding_spec_88() {
+        rounding_spec::<GAMMA2_88>();
+    }
+
+    #[test]
+    fn rounding_spec_32() {
+        rounding_spec::<GAMMA2_32>();
+    }
+
+    /// `K` selects the reference parameter set matching `GAMMA2`
+    fn rounding_vs_ref<const K: usize, const GAMMA2: i32>() {
+        use crystals_cref::dilithium as cref;
+
+        for a in (0..DILITHIUM_Q).step_by(STRIDE) {
+            let (a1, a0) = decompose::<GAMMA2>(a);
+            assert_eq!((a1, a0), cref::decompose::<K>(a), "a={a}");
+            for hint in 0..2 {
+                assert_eq!(
+                    use_hint::<GAMMA2>(a, hint),
+                    cref::use_hint::<K>(a, hint as u32),
+                    "a={a} hint={hint}"
+                );
+            }
+            // low bits of a perturbed value: covers all of a0 in [-2*GAMMA2, 2*GAMMA2]
+            let b0 = a0 + (a % (GAMMA2 + 1)) * if a & 1 == 0 { 1 } else { -1 };
+            assert_eq!(
+                make_hint::<GAMMA2>(b0, a1) as u32,
+                cref::make_hint::<K>(b0, a1),
+                "a0={b0} a1={a1}"
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn rounding_exhaustive_vs_ref() {
+        use crystals_cref::dilithium as cref;
+
+        for a in 0..DILITHIUM_Q {
+            assert_eq!(power2round(a), cref::power2round(a), "a={a}");
+        }
+        rounding_vs_ref::<4, GAMMA2_88>();
+        rounding_vs_ref::<6, GAMMA2_32>();
+        rounding_vs_ref::<8, GAMMA2_32>();
+    }
 }
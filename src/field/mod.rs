@@ -1,6 +1,7 @@
 use core::fmt::Debug;
 use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Rem, Sub, SubAssign};
 use rand::distributions::uniform::SampleUniform;
+use zeroize::Zeroize;
 
 pub mod dilithium;
 pub mod kyber;
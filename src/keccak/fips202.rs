@@ -1,24 +1,67 @@
-use super::{keccak_f1600::Keccak1600, *};
-use crate::{kyber::NOISE_SEED_BYTES, poly::UNIFORM_SEED_BYTES};
+use super::{keccak_f1600::Keccak1600, keccak_x4::Keccak1600x4, *};
+use crate::lib::{fmt, marker::PhantomData};
+use crate::{dilithium::CRH_BYTES, kyber::NOISE_SEED_BYTES, poly::UNIFORM_SEED_BYTES};
 
-#[derive(Default, Zeroize, ZeroizeOnDrop)]
+#[derive(Clone, Default, Zeroize, ZeroizeOnDrop)]
+pub struct Sha3_224(Keccak1600);
+
+#[derive(Clone, Default, Zeroize, ZeroizeOnDrop)]
 pub struct Sha3_256(Keccak1600);
 
-#[derive(Default, Zeroize, ZeroizeOnDrop)]
+#[derive(Clone, Default, Zeroize, ZeroizeOnDrop)]
+pub struct Sha3_384(Keccak1600);
+
+#[derive(Clone, Default, Zeroize, ZeroizeOnDrop)]
 pub struct Sha3_512(Keccak1600);
 
-#[derive(Default, Zeroize, ZeroizeOnDrop)]
+/// Keccak-224 as submitted to the SHA-3 competition, before the FIPS 202 domain separation
+#[derive(Clone, Default, Zeroize, ZeroizeOnDrop)]
+pub struct Keccak224(Keccak1600);
+
+/// Keccak-256 as submitted to the SHA-3 competition, e.g. used by Ethereum
+#[derive(Clone, Default, Zeroize, ZeroizeOnDrop)]
+pub struct Keccak256(Keccak1600);
+
+/// Keccak-384 as submitted to the SHA-3 competition
+#[derive(Clone, Default, Zeroize, ZeroizeOnDrop)]
+pub struct Keccak384(Keccak1600);
+
+/// Keccak-512 as submitted to the SHA-3 competition
+#[derive(Clone, Default, Zeroize, ZeroizeOnDrop)]
+pub struct Keccak512(Keccak1600);
+
+#[derive(Clone, Default, Zeroize, ZeroizeOnDrop)]
 pub struct Shake128(Keccak1600);
 
-#[derive(Default, Zeroize, ZeroizeOnDrop)]
+#[derive(Clone, Default, Zeroize, ZeroizeOnDrop)]
 pub struct Shake256(Keccak1600);
 
+/// Four SHAKE128 instances running in parallel on [`Keccak1600x4`]
+#[derive(Clone, Default, Zeroize, ZeroizeOnDrop)]
+pub struct Shake128x4(Keccak1600x4);
+
+/// Four SHAKE256 instances running in parallel on [`Keccak1600x4`]
+#[derive(Clone, Default, Zeroize, ZeroizeOnDrop)]
+pub struct Shake256x4(Keccak1600x4);
+
+pub struct Sha3_224Params;
+
+impl KeccakParams for Sha3_224Params {
+    const RATE_LANES: u8 = 18;
+    const DELIM: u8 = 0x06;
+}
 pub struct Sha3_256Params;
 
 impl KeccakParams for Sha3_256Params {
     const RATE_LANES: u8 = 17;
     const DELIM: u8 = 0x06;
 }
+pub struct Sha3_384Params;
+
+impl KeccakParams for Sha3_384Params {
+    const RATE_LANES: u8 = 13;
+    const DELIM: u8 = 0x06;
+}
 pub struct Sha3_512Params;
 
 impl KeccakParams for Sha3_512Params {
@@ -26,6 +69,31 @@ impl KeccakParams for Sha3_512Params {
     const DELIM: u8 = 0x06;
 }
 
+pub struct Keccak224Params;
+
+impl KeccakParams for Keccak224Params {
+    const RATE_LANES: u8 = 18;
+    const DELIM: u8 = 0x01;
+}
+pub struct Keccak256Params;
+
+impl KeccakParams for Keccak256Params {
+    const RATE_LANES: u8 = 17;
+    const DELIM: u8 = 0x01;
+}
+pub struct Keccak384Params;
+
+impl KeccakParams for Keccak384Params {
+    const RATE_LANES: u8 = 13;
+    const DELIM: u8 = 0x01;
+}
+pub struct Keccak512Params;
+
+impl KeccakParams for Keccak512Params {
+    const RATE_LANES: u8 = 9;
+    const DELIM: u8 = 0x01;
+}
+
 pub struct Shake128Params;
 
 impl KeccakParams for Shake128Params {
@@ -59,30 +127,33 @@ pub trait Digest<P: KeccakParams, const DIGEST_BYTES: usize>:
     }
 }
 
-impl Digest<Sha3_256Params, 32> for Sha3_256 {}
-impl Digest<Sha3_512Params, 64> for Sha3_512 {}
+macro_rules! digest_impl {
+    ($digest:ident, $params:ident, $digest_bytes:literal) => {
+        impl Digest<$params, $digest_bytes> for $digest {}
 
-impl<T> SpongeOps<Sha3_256Params> for T where T: Digest<Sha3_256Params, 32> {}
-impl<T> SpongeOps<Sha3_512Params> for T where T: Digest<Sha3_512Params, 64> {}
+        impl<T> SpongeOps<$params> for T where T: Digest<$params, $digest_bytes> {}
 
-impl SpongeOps<Shake128Params> for Shake128 {}
-impl SpongeOps<Shake256Params> for Shake256 {}
-
-impl HasKeccak<Sha3_256Params> for Sha3_256 {
-    type Keccak = Keccak1600;
-    #[inline(always)]
-    fn keccak(&mut self) -> &mut Self::Keccak {
-        &mut self.0
-    }
+        impl HasKeccak<$params> for $digest {
+            type Keccak = Keccak1600;
+            #[inline(always)]
+            fn keccak(&mut self) -> &mut Self::Keccak {
+                &mut self.0
+            }
+        }
+    };
 }
 
-impl HasKeccak<Sha3_512Params> for Sha3_512 {
-    type Keccak = Keccak1600;
-    #[inline(always)]
-    fn keccak(&mut self) -> &mut Self::Keccak {
-        &mut self.0
-    }
-}
+digest_impl!(Sha3_224, Sha3_224Params, 28);
+digest_impl!(Sha3_256, Sha3_256Params, 32);
+digest_impl!(Sha3_384, Sha3_384Params, 48);
+digest_impl!(Sha3_512, Sha3_512Params, 64);
+digest_impl!(Keccak224, Keccak224Params, 28);
+digest_impl!(Keccak256, Keccak256Params, 32);
+digest_impl!(Keccak384, Keccak384Params, 48);
+digest_impl!(Keccak512, Keccak512Params, 64);
+
+impl SpongeOps<Shake128Params> for Shake128 {}
+impl SpongeOps<Shake256Params> for Shake256 {}
 
 impl HasKeccak<Shake128Params> for Shake128 {
     type Keccak = Keccak1600;
@@ -109,6 +180,176 @@ pub trait SpongeOps<P: KeccakParams>: Default + HasKeccak<P> {
     fn squeeze(&mut self, out: &mut [u8]) {
         self.keccak().squeeze(out);
     }
+
+    /// Absorb the concatenation of all `parts`
+    #[inline]
+    fn absorb_parts(&mut self, parts: &[&[u8]]) {
+        let keccak = self.keccak();
+        keccak.reset();
+        let mut pos = 0;
+        for part in parts {
+            pos = KeccakOps::<P>::absorb_at(keccak, pos, part);
+        }
+        KeccakOps::<P>::pad_at(keccak, pos);
+    }
+}
+
+/// Incremental absorbing into the sponge `S`: data can be provided in chunks of arbitrary size through
+/// `update`, with partial blocks buffered in the state.
+/// The output is the same as for absorbing the concatenation of all chunks at once.
+///
+/// A common prefix can be absorbed once and the state then [`fork`](Self::fork)ed for each continuation.
+pub struct Absorber<P: KeccakParams, S: SpongeOps<P>> {
+    sponge: S,
+    pos: usize,
+    params: PhantomData<P>,
+}
+
+/// Output of a finalized [`Absorber`], which can be read in chunks of arbitrary size
+/// The output is the same as for a single `read` of the total length.
+pub struct XofReader<P: KeccakParams, S: SpongeOps<P>> {
+    sponge: S,
+    pos: usize,
+    params: PhantomData<P>,
+}
+
+pub type Sha3_224Absorber = Absorber<Sha3_224Params, Sha3_224>;
+pub type Sha3_256Absorber = Absorber<Sha3_256Params, Sha3_256>;
+pub type Sha3_384Absorber = Absorber<Sha3_384Params, Sha3_384>;
+pub type Sha3_512Absorber = Absorber<Sha3_512Params, Sha3_512>;
+pub type Keccak224Absorber = Absorber<Keccak224Params, Keccak224>;
+pub type Keccak256Absorber = Absorber<Keccak256Params, Keccak256>;
+pub type Keccak384Absorber = Absorber<Keccak384Params, Keccak384>;
+pub type Keccak512Absorber = Absorber<Keccak512Params, Keccak512>;
+pub type Shake128Absorber = Absorber<Shake128Params, Shake128>;
+pub type Shake256Absorber = Absorber<Shake256Params, Shake256>;
+pub type Shake128Reader = XofReader<Shake128Params, Shake128>;
+pub type Shake256Reader = XofReader<Shake256Params, Shake256>;
+
+impl<P: KeccakParams, S: SpongeOps<P>> Default for Absorber<P, S> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            sponge: S::default(),
+            pos: 0,
+            params: PhantomData,
+        }
+    }
+}
+
+impl<P: KeccakParams, S: SpongeOps<P> + Clone> Clone for Absorber<P, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            sponge: self.sponge.clone(),
+            pos: self.pos,
+            params: PhantomData,
+        }
+    }
+}
+
+/// The state is secret, only the type is shown
+impl<P: KeccakParams, S: SpongeOps<P>> fmt::Debug for Absorber<P, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Absorber").finish_non_exhaustive()
+    }
+}
+
+impl<P: KeccakParams, S: SpongeOps<P>> fmt::Debug for XofReader<P, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("XofReader").finish_non_exhaustive()
+    }
+}
+
+impl<P: KeccakParams, S: SpongeOps<P> + Clone> Clone for XofReader<P, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            sponge: self.sponge.clone(),
+            pos: self.pos,
+            params: PhantomData,
+        }
+    }
+}
+
+impl<P: KeccakParams, S: SpongeOps<P>> Absorber<P, S> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Snapshot of the data absorbed so far, to be continued independently of `self`
+    ///
+    /// Restoring the snapshot is assigning a fork of it back, `absorber = snapshot.fork()`.
+    #[inline]
+    pub fn fork(&self) -> Self
+    where
+        S: Clone,
+    {
+        self.clone()
+    }
+
+    #[inline]
+    pub fn update(&mut self, data: &[u8]) {
+        let keccak = HasKeccak::<P>::keccak(&mut self.sponge);
+        self.pos = KeccakOps::<P>::absorb_at(keccak, self.pos, data);
+    }
+
+    /// Pad and switch to squeezing
+    pub fn finalize_xof(mut self) -> XofReader<P, S> {
+        let keccak = HasKeccak::<P>::keccak(&mut self.sponge);
+        KeccakOps::<P>::pad_at(keccak, self.pos);
+        XofReader {
+            sponge: self.sponge,
+            pos: P::RATE_BYTES,
+            params: PhantomData,
+        }
+    }
+
+    /// Pad with the domain separation byte `domain` instead of `P::DELIM` and switch to squeezing, for the
+    /// functions which share the sponge of `P` but not its padding
+    pub(crate) fn finalize_xof_with_domain(mut self, domain: u8) -> XofReader<P, S> {
+        let keccak = HasKeccak::<P>::keccak(&mut self.sponge);
+        KeccakOps::<P>::pad_domain_at(keccak, self.pos, domain);
+        XofReader {
+            sponge: self.sponge,
+            pos: P::RATE_BYTES,
+            params: PhantomData,
+        }
+    }
+
+    /// Complete the current block with zeros, so the next data starts a new block
+    pub(crate) fn zero_pad_block(&mut self) {
+        // the zero padding leaves the state unchanged, only the permutation is applied
+        if self.pos != 0 {
+            KeccakOps::<P>::permute(HasKeccak::<P>::keccak(&mut self.sponge));
+            self.pos = 0;
+        }
+    }
+
+    /// Write the digest of all absorbed data to `out`
+    pub fn finalize<const DIGEST_BYTES: usize>(self, out: &mut [u8; DIGEST_BYTES])
+    where
+        S: Digest<P, DIGEST_BYTES>,
+    {
+        self.finalize_xof().read(out);
+    }
+}
+
+impl<P: KeccakParams, S: SpongeOps<P>> XofReader<P, S> {
+    /// Snapshot of the reader, which yields the same output as `self` from the current position on
+    #[inline]
+    pub fn fork(&self) -> Self
+    where
+        S: Clone,
+    {
+        self.clone()
+    }
+
+    #[inline]
+    pub fn read(&mut self, out: &mut [u8]) {
+        let keccak = HasKeccak::<P>::keccak(&mut self.sponge);
+        self.pos = KeccakOps::<P>::squeeze_at(keccak, self.pos, out);
+    }
 }
 
 pub trait HasParams<P: KeccakParams> {
@@ -138,17 +379,62 @@ pub trait CrystalsXof<P: KeccakParams>: OneBlockAbsorb<P, UNIFORM_SEED_BYTES> {
     }
 }
 
-pub trait CrystalsPrf<P: KeccakParams>: OneBlockAbsorb<P, { NOISE_SEED_BYTES }> {
+pub trait CrystalsPrf<P: KeccakParams, const SEED_BYTES: usize = NOISE_SEED_BYTES>:
+    OneBlockAbsorb<P, SEED_BYTES>
+{
     #[inline(always)]
-    fn absorb_prf(&mut self, data: &[u8; NOISE_SEED_BYTES], nonce: u8) {
+    fn absorb_prf(&mut self, data: &[u8; SEED_BYTES], nonce: u8) {
         let pad_word = (P::DELIM as u64) << 8 | nonce as u64;
         self.absorb_crystal_pad(data, pad_word);
     }
+
+    /// absorb `data || nonce` with a 16-bit little-endian nonce (as used in Dilithium)
+    #[inline(always)]
+    fn absorb_prf_u16(&mut self, data: &[u8; SEED_BYTES], nonce: u16) {
+        let pad_word = (P::DELIM as u64) << 16 | nonce as u64;
+        self.absorb_crystal_pad(data, pad_word);
+    }
+}
+
+impl Shake128x4 {
+    /// Four-way [`CrystalsXof::absorb_xof_with_nonces`], absorbing `seed || nonces[k]` into the k-th instance
+    #[inline]
+    pub fn absorb_xof_with_nonces(
+        &mut self,
+        seed: &[u8; UNIFORM_SEED_BYTES],
+        nonces: [(u8, u8); 4],
+    ) {
+        let pad_words = nonces
+            .map(|(n1, n2)| u64::from_le_bytes([n1, n2, Shake128Params::DELIM, 0, 0, 0, 0, 0]));
+        self.0.absorb_one_block::<Shake128Params>(seed, pad_words);
+    }
+
+    /// Squeeze `N` bytes from each instance, permuting before every block as `SpongeOps::squeeze`
+    #[inline]
+    pub fn squeeze<const N: usize>(&mut self, out: &mut [[u8; N]; 4]) {
+        self.0.squeeze::<Shake128Params, N>(out);
+    }
+}
+
+impl Shake256x4 {
+    /// Four-way [`CrystalsPrf::absorb_prf`], absorbing `seed || nonces[k]` into the k-th instance
+    #[inline]
+    pub fn absorb_prf(&mut self, seed: &[u8; NOISE_SEED_BYTES], nonces: [u8; 4]) {
+        let pad_words = nonces.map(|nonce| (Shake256Params::DELIM as u64) << 8 | nonce as u64);
+        self.0.absorb_one_block::<Shake256Params>(seed, pad_words);
+    }
+
+    /// Squeeze `N` bytes from each instance, permuting before every block as `SpongeOps::squeeze`
+    #[inline]
+    pub fn squeeze<const N: usize>(&mut self, out: &mut [[u8; N]; 4]) {
+        self.0.squeeze::<Shake256Params, N>(out);
+    }
 }
 
 impl CrystalsXof<Shake128Params> for Shake128 {}
 
 impl CrystalsPrf<Shake256Params> for Shake256 {}
+impl CrystalsPrf<Shake256Params, CRH_BYTES> for Shake256 {}
 
 impl<T, P, const ABSORB_BYTES: usize> OneBlockAbsorb<P, ABSORB_BYTES> for T
 where
@@ -169,17 +455,18 @@ where
             .iter_mut()
             .zip(data.as_array_chunks::<LANE_BYTES>())
         {
-            *lane = u64::from_le_bytes(*bytes);
+            *lane = KeccakLane::from_u64(u64::from_le_bytes(*bytes));
         }
 
-        keccak.state_mut()[<Self as OneBlockAbsorb<_, ABSORB_BYTES>>::ABSORB_LANES] = pad_word;
+        keccak.state_mut()[<Self as OneBlockAbsorb<_, ABSORB_BYTES>>::ABSORB_LANES] =
+            KeccakLane::from_u64(pad_word);
 
         if keccak.1 {
             for lane in keccak.state_mut()
                 [<Self as OneBlockAbsorb<_, ABSORB_BYTES>>::ABSORB_LANES + 1..]
                 .iter_mut()
             {
-                *lane = 0;
+                *lane = Default::default();
             }
         }
         keccak.1 = true;
@@ -193,7 +480,9 @@ mod tests {
     extern crate std;
 
     use super::*;
-    use rand::RngCore;
+    use crate::poly::kyber::KYBER_N;
+    use rand::{Rng, RngCore};
+    use std::vec::Vec;
     use std::*;
 
     #[test]
@@ -272,6 +561,48 @@ mod tests {
         }
     }
 
+    fn digest_rand<P, D, G, const N: usize>()
+    where
+        P: KeccakParams,
+        D: Digest<P, N>,
+        G: sha3::digest::Digest,
+    {
+        let mut digest = D::default();
+        let mut out = [0; N];
+        let mut rng = rand::thread_rng();
+
+        const MAX_LEN: usize = if cfg!(miri) { 10 } else { 300 };
+
+        for n in 0..=MAX_LEN {
+            let mut data = vec![0u8; n];
+            rng.fill_bytes(&mut data);
+
+            digest.digest(&data, &mut out);
+            assert_eq!(G::digest(&data).as_slice(), &out[..]);
+        }
+    }
+
+    #[test]
+    fn sha3_224_384_rand() {
+        digest_rand::<Sha3_224Params, Sha3_224, sha3::Sha3_224, 28>();
+        digest_rand::<Sha3_384Params, Sha3_384, sha3::Sha3_384, 48>();
+    }
+
+    #[test]
+    fn keccak_rand() {
+        digest_rand::<Keccak224Params, Keccak224, sha3::Keccak224, 28>();
+        digest_rand::<Keccak256Params, Keccak256, sha3::Keccak256, 32>();
+        digest_rand::<Keccak384Params, Keccak384, sha3::Keccak384, 48>();
+        digest_rand::<Keccak512Params, Keccak512, sha3::Keccak512, 64>();
+
+        let mut digest = [0; 32];
+        Keccak256::default().digest(&[], &mut digest);
+        assert_eq!(
+            digest,
+            hex_literal::hex!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+        );
+    }
+
     #[test]
     fn sha3_512() {
         let mut sha3 = fips202::Sha3_512::default();
@@ -359,4 +690,233 @@ mod tests {
             assert_eq!(&golden_xof_out, &xof_out);
         }
     }
+
+    #[test]
+    fn shake256_absorb_prf_u16() {
+        let mut shake = Shake256::default();
+        let mut golden_xof_out = [0u8; 2 * Shake256Params::RATE_BYTES];
+        let mut rng = rand::thread_rng();
+
+        const N_TESTS: usize = if cfg!(miri) { 6 } else { 666 };
+
+        let mut data = [0u8; CRH_BYTES];
+        let mut data_aug = [0u8; CRH_BYTES + 2];
+
+        let mut xof_out = [0u8; 2 * Shake256Params::RATE_BYTES];
+
+        for _ in 0..N_TESTS {
+            let nonce = rand::random::<u16>();
+            rng.fill_bytes(&mut data);
+
+            data_aug[..CRH_BYTES].copy_from_slice(&data);
+            data_aug[CRH_BYTES..].copy_from_slice(&nonce.to_le_bytes());
+
+            shake.absorb_prf_u16(&data, nonce);
+            shake.squeeze(&mut xof_out);
+
+            {
+                use sha3::digest::{ExtendableOutput, Update, XofReader};
+                let mut another_shake = sha3::Shake256::default();
+                another_shake.update(&data_aug);
+                let mut reader = another_shake.finalize_xof();
+                reader.read(&mut golden_xof_out);
+            }
+
+            assert_eq!(&golden_xof_out, &xof_out);
+        }
+    }
+
+    #[test]
+    fn shake_x4() {
+        let mut rng = rand::thread_rng();
+        let mut seed = [0u8; 32];
+        let mut out128 = [[0u8; 3 * Shake128Params::RATE_BYTES]; 4];
+        let mut out256 = [[0u8; 3 * KYBER_N / 4]; 4];
+        let mut golden128 = [0u8; 3 * Shake128Params::RATE_BYTES];
+        let mut golden256 = [0u8; 3 * KYBER_N / 4];
+
+        for _ in 0..100 {
+            rng.fill_bytes(&mut seed);
+            let nonces: [(u8, u8); 4] = rng.gen();
+            let mut shake128 = Shake128x4::default();
+            shake128.absorb_xof_with_nonces(&seed, nonces);
+            shake128.squeeze(&mut out128);
+            let mut shake256 = Shake256x4::default();
+            shake256.absorb_prf(&seed, nonces.map(|(n, _)| n));
+            shake256.squeeze(&mut out256);
+
+            for k in 0..4 {
+                let mut shake = Shake128::default();
+                shake.absorb_xof_with_nonces(&seed, nonces[k].0, nonces[k].1);
+                shake.squeeze(&mut golden128);
+                assert_eq!(golden128, out128[k]);
+
+                let mut shake = Shake256::default();
+                shake.absorb_prf(&seed, nonces[k].0);
+                shake.squeeze(&mut golden256);
+                assert_eq!(golden256, out256[k]);
+            }
+        }
+    }
+
+    /// Split `data` at random positions
+    fn random_chunks<'a>(rng: &mut impl Rng, mut data: &'a [u8]) -> Vec<&'a [u8]> {
+        let mut chunks = Vec::new();
+        while !data.is_empty() {
+            let (chunk, rest) = data.split_at(rng.gen_range(0..=data.len().min(200)));
+            chunks.push(chunk);
+            data = rest;
+        }
+        chunks
+    }
+
+    #[test]
+    fn shake_update_read() {
+        let mut rng = rand::thread_rng();
+
+        const MAX_LEN: usize = if cfg!(miri) { 20 } else { 500 };
+
+        for n in 0..MAX_LEN {
+            let mut data = vec![0u8; n];
+            rng.fill_bytes(&mut data);
+            let out_len = rng.gen_range(0..3 * Shake128Params::RATE_BYTES);
+
+            let mut shake128 = Shake128Absorber::new();
+            let mut shake256 = Shake256Absorber::new();
+            for chunk in random_chunks(&mut rng, &data) {
+                shake128.update(chunk);
+                shake256.update(chunk);
+            }
+            let mut readers = (shake128.finalize_xof(), shake256.finalize_xof());
+            let mut out = (vec![0u8; out_len], vec![0u8; out_len]);
+            let mut i = 0;
+            while i < out_len {
+                let j = rng.gen_range(i..=out_len.min(i + 2 * Shake256Params::RATE_BYTES / 3));
+                readers.0.read(&mut out.0[i..j]);
+                readers.1.read(&mut out.1[i..j]);
+                i = j;
+            }
+
+            use sha3::digest::{ExtendableOutput, Update, XofReader};
+            let mut golden = (vec![0u8; out_len], vec![0u8; out_len]);
+            let mut another_shake = sha3::Shake128::default();
+            another_shake.update(&data);
+            another_shake.finalize_xof().read(&mut golden.0);
+            let mut another_shake = sha3::Shake256::default();
+            another_shake.update(&data);
+            another_shake.finalize_xof().read(&mut golden.1);
+
+            assert_eq!(golden, out);
+        }
+
+        // byte-by-byte squeezing across block boundaries
+        let mut shake = Shake256::default();
+        let mut xof_out = [0u8; 3 * Shake256Params::RATE_BYTES];
+        shake.absorb(b"abc");
+        shake.squeeze(&mut xof_out);
+        let mut absorber = Shake256Absorber::new();
+        absorber.update(b"abc");
+        let mut reader = absorber.finalize_xof();
+        for b in xof_out {
+            let mut byte = [0u8; 1];
+            reader.read(&mut byte);
+            assert_eq!(byte[0], b);
+        }
+    }
+
+    #[test]
+    fn sha3_update_finalize() {
+        let mut rng = rand::thread_rng();
+        let mut digest256 = [0u8; 32];
+        let mut digest512 = [0u8; 64];
+
+        const MAX_LEN: usize = if cfg!(miri) { 20 } else { 500 };
+
+        for n in 0..MAX_LEN {
+            let mut data = vec![0u8; n];
+            rng.fill_bytes(&mut data);
+
+            let mut sha3_256 = Sha3_256Absorber::new();
+            let mut sha3_512 = Sha3_512Absorber::new();
+            for chunk in random_chunks(&mut rng, &data) {
+                sha3_256.update(chunk);
+                sha3_512.update(chunk);
+            }
+            sha3_256.finalize(&mut digest256);
+            sha3_512.finalize(&mut digest512);
+
+            use sha3::digest::Digest;
+            assert_eq!(sha3::Sha3_256::digest(&data).as_slice(), &digest256[..]);
+            assert_eq!(sha3::Sha3_512::digest(&data).as_slice(), &digest512[..]);
+        }
+    }
+
+    #[test]
+    fn absorber_fork() {
+        let mut rng = rand::thread_rng();
+        let mut digest = [0u8; 32];
+
+        const MAX_LEN: usize = if cfg!(miri) { 20 } else { 500 };
+
+        for n in (0..MAX_LEN).step_by(7) {
+            let mut prefix = vec![0u8; n];
+            rng.fill_bytes(&mut prefix);
+            let mut absorber = Sha3_256Absorber::new();
+            absorber.update(&prefix);
+            let snapshot = absorber.fork();
+
+            for suffix_len in [0, 1, 100, 300] {
+                let mut data = prefix.clone();
+                data.resize(n + suffix_len, 0);
+                rng.fill_bytes(&mut data[n..]);
+
+                absorber.update(&data[n..]);
+                absorber.finalize(&mut digest);
+                absorber = snapshot.fork();
+
+                use sha3::digest::Digest;
+                assert_eq!(sha3::Sha3_256::digest(&data).as_slice(), &digest[..]);
+            }
+        }
+
+        // a forked reader continues from the same position
+        let mut absorber = Shake128Absorber::new();
+        absorber.update(b"abc");
+        let mut reader = absorber.finalize_xof();
+        let mut out = [0u8; 200];
+        reader.read(&mut out[..77]);
+        let mut forked = reader.fork();
+        let mut out_forked = [0u8; 123];
+        reader.read(&mut out[77..]);
+        forked.read(&mut out_forked);
+        assert_eq!(out[77..], out_forked);
+    }
+
+    #[test]
+    fn shake256_absorb_parts() {
+        let mut shake = Shake256::default();
+        let mut golden_xof_out = [0u8; Shake256Params::RATE_BYTES];
+        let mut xof_out = [0u8; Shake256Params::RATE_BYTES];
+        let mut rng = rand::thread_rng();
+
+        const MAX_LEN: usize = if cfg!(miri) { 20 } else { 600 };
+
+        for n in 0..MAX_LEN {
+            let mut data = vec![0u8; n];
+            rng.fill_bytes(&mut data);
+            let i = rng.gen_range(0..=n);
+            let j = rng.gen_range(i..=n);
+
+            shake.absorb_parts(&[&data[..i], &data[i..j], &[], &data[j..]]);
+            shake.squeeze(&mut xof_out);
+
+            use sha3::digest::{ExtendableOutput, Update, XofReader};
+            let mut another_shake = sha3::Shake256::default();
+            another_shake.update(&data);
+            let mut reader = another_shake.finalize_xof();
+            reader.read(&mut golden_xof_out);
+
+            assert_eq!(&golden_xof_out, &xof_out);
+        }
+    }
 }
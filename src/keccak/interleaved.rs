@@ -0,0 +1,227 @@
+//! Keccak-f[1600] with bit-interleaved lanes for 32-bit targets
+//!
+//! Each 64-bit lane is stored as two `u32` words holding its even and its odd bits. A rotation of the lane is
+//! then a rotation of both words, by half the amount, with the words swapped for odd amounts, instead of the
+//! shifts and ORs across two registers of a `u64` rotation on a 32-bit core. Lanes are converted when data is
+//! absorbed or squeezed, the permutation itself works on the interleaved words only.
+//!
+//! [`super::keccak_f1600::Keccak1600`] uses this backend on targets with 32-bit pointers, or on any target with
+//! the `keccak32` feature.
+
+use super::{Keccak, KeccakLane, RC};
+use crate::lib::ops::{BitAnd, BitXor, BitXorAssign, Not};
+
+use zeroize::Zeroize;
+
+/// A lane as its even bits `even` and odd bits `odd`: bit `i` of `even` is bit `2 i` of the lane, bit `i`
+/// of `odd` is bit `2 i + 1`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Zeroize)]
+pub struct InterleavedLane {
+    pub even: u32,
+    pub odd: u32,
+}
+
+pub type Keccak1600_32 = Keccak<InterleavedLane, 25>;
+
+/// Gather the even bits of `x` in the lower and the odd bits in the upper half
+const fn unshuffle(mut x: u32) -> u32 {
+    let mut t = (x ^ (x >> 1)) & 0x2222_2222;
+    x ^= t ^ (t << 1);
+    t = (x ^ (x >> 2)) & 0x0c0c_0c0c;
+    x ^= t ^ (t << 2);
+    t = (x ^ (x >> 4)) & 0x00f0_00f0;
+    x ^= t ^ (t << 4);
+    t = (x ^ (x >> 8)) & 0x0000_ff00;
+    x ^ t ^ (t << 8)
+}
+
+/// Inverse of `unshuffle`, the same swaps in reverse order
+const fn shuffle(mut x: u32) -> u32 {
+    let mut t = (x ^ (x >> 8)) & 0x0000_ff00;
+    x ^= t ^ (t << 8);
+    t = (x ^ (x >> 4)) & 0x00f0_00f0;
+    x ^= t ^ (t << 4);
+    t = (x ^ (x >> 2)) & 0x0c0c_0c0c;
+    x ^= t ^ (t << 2);
+    t = (x ^ (x >> 1)) & 0x2222_2222;
+    x ^ t ^ (t << 1)
+}
+
+impl InterleavedLane {
+    pub const fn interleave(x: u64) -> Self {
+        let lo = unshuffle(x as u32);
+        let hi = unshuffle((x >> 32) as u32);
+        Self {
+            even: (lo & 0xffff) | (hi << 16),
+            odd: (lo >> 16) | (hi & 0xffff_0000),
+        }
+    }
+
+    pub const fn deinterleave(self) -> u64 {
+        let lo = shuffle((self.even & 0xffff) | (self.odd << 16));
+        let hi = shuffle((self.even >> 16) | (self.odd & 0xffff_0000));
+        (hi as u64) << 32 | lo as u64
+    }
+}
+
+impl KeccakLane for InterleavedLane {
+    const RC: [Self; 24] = {
+        let mut rc = [Self { even: 0, odd: 0 }; 24];
+        let mut i = 0;
+        while i < rc.len() {
+            rc[i] = Self::interleave(RC[i]);
+            i += 1;
+        }
+        rc
+    };
+
+    #[inline(always)]
+    fn from_u64(x: u64) -> Self {
+        Self::interleave(x)
+    }
+
+    #[inline(always)]
+    fn to_u64(self) -> u64 {
+        self.deinterleave()
+    }
+
+    #[inline(always)]
+    fn rotate_left(self, n: u32) -> Self {
+        if n % 2 == 0 {
+            Self {
+                even: self.even.rotate_left(n / 2),
+                odd: self.odd.rotate_left(n / 2),
+            }
+        } else {
+            Self {
+                even: self.odd.rotate_left(n / 2 + 1),
+                odd: self.even.rotate_left(n / 2),
+            }
+        }
+    }
+}
+
+impl BitXor for InterleavedLane {
+    type Output = Self;
+
+    #[inline(always)]
+    fn bitxor(self, rhs: Self) -> Self {
+        Self {
+            even: self.even ^ rhs.even,
+            odd: self.odd ^ rhs.odd,
+        }
+    }
+}
+
+impl BitXorAssign for InterleavedLane {
+    #[inline(always)]
+    fn bitxor_assign(&mut self, rhs: Self) {
+        self.even ^= rhs.even;
+        self.odd ^= rhs.odd;
+    }
+}
+
+impl BitAnd for InterleavedLane {
+    type Output = Self;
+
+    #[inline(always)]
+    fn bitand(self, rhs: Self) -> Self {
+        Self {
+            even: self.even & rhs.even,
+            odd: self.odd & rhs.odd,
+        }
+    }
+}
+
+impl Not for InterleavedLane {
+    type Output = Self;
+
+    #[inline(always)]
+    fn not(self) -> Self {
+        Self {
+            even: !self.even,
+            odd: !self.odd,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::keccak::fips202::{Sha3_512Params, Shake128Params};
+    use crate::keccak::keccak_f1600::Keccak1600_64;
+    use crate::keccak::turboshake::TurboShake256Params;
+    use crate::keccak::{KeccakOps, KeccakParams};
+    use rand::{Rng, RngCore};
+    use std::*;
+
+    #[test]
+    fn interleaved_lane() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let x: u64 = rng.gen();
+            let lane = InterleavedLane::interleave(x);
+            assert_eq!(lane.deinterleave(), x);
+            assert_eq!(lane.even & 1, (x & 1) as u32);
+            assert_eq!(lane.odd >> 31, (x >> 63) as u32);
+            for n in 0..64 {
+                assert_eq!(
+                    KeccakLane::rotate_left(lane, n).deinterleave(),
+                    x.rotate_left(n)
+                );
+            }
+        }
+        for (rc, rc64) in InterleavedLane::RC.iter().zip(RC) {
+            assert_eq!(rc.deinterleave(), rc64);
+        }
+    }
+
+    fn sponge_vs_64<P: KeccakParams>() {
+        let mut rng = rand::thread_rng();
+
+        for n in (0..500).step_by(11) {
+            let mut keccak = Keccak1600_64::default();
+            let mut keccak32 = Keccak1600_32::default();
+            for (lane, lane32) in keccak.0.iter_mut().zip(keccak32.0.iter_mut()) {
+                *lane = rng.gen();
+                *lane32 = InterleavedLane::interleave(*lane);
+            }
+            KeccakOps::<P>::permute(&mut keccak);
+            KeccakOps::<P>::permute(&mut keccak32);
+            for (lane, lane32) in keccak.0.iter().zip(keccak32.0.iter()) {
+                assert_eq!(lane32.deinterleave(), *lane);
+            }
+
+            let mut data = vec![0u8; n];
+            rng.fill_bytes(&mut data);
+            let split = rng.gen_range(0..=n);
+            let mut out = [0u8; 300];
+            let mut out32 = [0u8; 300];
+
+            let mut pos = KeccakOps::<P>::absorb_at(&mut keccak, 0, &data[..split]);
+            pos = KeccakOps::<P>::absorb_at(&mut keccak, pos, &data[split..]);
+            KeccakOps::<P>::pad_at(&mut keccak, pos);
+            KeccakOps::<P>::squeeze_at(&mut keccak, P::RATE_BYTES, &mut out);
+            let mut pos = KeccakOps::<P>::absorb_at(&mut keccak32, 0, &data[..split]);
+            pos = KeccakOps::<P>::absorb_at(&mut keccak32, pos, &data[split..]);
+            KeccakOps::<P>::pad_at(&mut keccak32, pos);
+            KeccakOps::<P>::squeeze_at(&mut keccak32, P::RATE_BYTES, &mut out32);
+            assert_eq!(out, out32);
+
+            KeccakOps::<P>::absorb(&mut keccak, &data);
+            KeccakOps::<P>::squeeze(&mut keccak, &mut out);
+            KeccakOps::<P>::absorb(&mut keccak32, &data);
+            KeccakOps::<P>::squeeze(&mut keccak32, &mut out32);
+            assert_eq!(out, out32);
+        }
+    }
+
+    #[test]
+    fn interleaved_vs_64() {
+        sponge_vs_64::<Shake128Params>();
+        sponge_vs_64::<Sha3_512Params>();
+        sponge_vs_64::<TurboShake256Params>();
+    }
+}
@@ -1,4 +1,6 @@
-use super::{Keccak, KeccakOps, KeccakParams, KeccakState};
+use super::{Keccak, KeccakLane, KeccakOps, KeccakParams, KeccakState};
+use crate::lib::cmp::min;
+use crate::lib::mem;
 use crate::lib::slice::{Iter, IterMut};
 use crate::utils::split::Splitter;
 
@@ -6,10 +8,18 @@ use array_init::array_init;
 use crunchy::unroll;
 use zeroize::Zeroize;
 
-pub type Keccak1600 = Keccak<u64, 25>;
+/// Keccak-f[1600] with native 64-bit lanes
+pub type Keccak1600_64 = Keccak<u64, 25>;
+
+/// The state of all sponges: with bit-interleaved lanes on 32-bit targets or with the `keccak32` feature,
+/// see [`super::interleaved`]
+#[cfg(not(any(target_pointer_width = "32", feature = "keccak32")))]
+pub type Keccak1600 = Keccak1600_64;
+#[cfg(any(target_pointer_width = "32", feature = "keccak32"))]
+pub type Keccak1600 = super::interleaved::Keccak1600_32;
 
 // starting from lane (0, 1)
-const RHO: [u32; 24] = {
+pub(super) const RHO: [u32; 24] = {
     let mut rho = [0u32; 24];
     let mut t = 0;
     let (mut i, mut j) = (0, 1);
@@ -21,23 +31,23 @@ const RHO: [u32; 24] = {
     rho
 };
 
-const PI: [u8; 24] = [
+pub(super) const PI: [u8; 24] = [
     10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
 ];
 
-impl KeccakState for Keccak1600 {
+impl<L: KeccakLane> KeccakState for Keccak<L, 25> {
     const NUM_LANES: usize = 25;
 
-    type Lane = u64;
+    type Lane = L;
     type State = [Self::Lane; 25];
 
     #[inline(always)]
-    fn state(&self) -> &[Self::Lane; Self::NUM_LANES] {
+    fn state(&self) -> &[Self::Lane; 25] {
         &self.0
     }
 
     #[inline(always)]
-    fn state_mut(&mut self) -> &mut [Self::Lane; Self::NUM_LANES] {
+    fn state_mut(&mut self) -> &mut [Self::Lane; 25] {
         &mut self.0
     }
 
@@ -61,7 +71,8 @@ impl KeccakState for Keccak1600 {
 
 impl<T, P: KeccakParams> KeccakOps<P> for T
 where
-    T: KeccakState<Lane = u64>,
+    T: KeccakState,
+    T::Lane: KeccakLane,
 {
     type Params = P;
 
@@ -70,7 +81,7 @@ where
         const LANE_BYTES: usize = 8;
         assert!(LANE_BYTES == P::LANE_BYTES);
         const FINALIZE_CONST: u64 = 1 << (LANE_BYTES * 8 - 1); // 1 << 63
-        self.state_mut()[P::RATE_LANES as usize - 1] ^= FINALIZE_CONST;
+        self.state_mut()[P::RATE_LANES as usize - 1] ^= T::Lane::from_u64(FINALIZE_CONST);
     }
 
     fn absorb(&mut self, mut data: &[u8]) {
@@ -86,14 +97,14 @@ where
             for lane in self.lanes_iter_mut::<P>() {
                 (data_chunk, data) = data.try_split_array_ref::<LANE_BYTES>();
                 match data_chunk {
-                    Some(chunk) => *lane ^= u64::from_le_bytes(*chunk),
+                    Some(chunk) => *lane ^= T::Lane::from_u64(u64::from_le_bytes(*chunk)),
                     None => {
                         // the loops always end here
                         let mut buf = [0u8; LANE_BYTES];
                         let rem_len = data.len();
                         buf[..rem_len].copy_from_slice(data);
                         buf[rem_len] = P::DELIM;
-                        *lane ^= u64::from_le_bytes(buf);
+                        *lane ^= T::Lane::from_u64(u64::from_le_bytes(buf));
                         KeccakOps::<P>::finalize_xor(self);
                         return;
                     }
@@ -104,11 +115,68 @@ where
         // unreachable!();
     }
 
+    fn absorb_at(&mut self, mut pos: usize, mut data: &[u8]) -> usize {
+        const LANE_BYTES: usize = 8;
+        assert!(LANE_BYTES == P::LANE_BYTES);
+        debug_assert!(pos < P::RATE_BYTES);
+
+        while !data.is_empty() {
+            let lane = &mut self.state_mut()[pos / LANE_BYTES];
+            let offset = pos % LANE_BYTES;
+            match data.try_split_array_ref::<LANE_BYTES>() {
+                // fast path for whole and aligned lanes
+                (Some(chunk), rest) if offset == 0 => {
+                    *lane ^= T::Lane::from_u64(u64::from_le_bytes(*chunk));
+                    data = rest;
+                    pos += LANE_BYTES;
+                }
+                _ => {
+                    *lane ^= T::Lane::from_u64((data[0] as u64) << (8 * offset));
+                    data = &data[1..];
+                    pos += 1;
+                }
+            }
+            if pos == P::RATE_BYTES {
+                KeccakOps::<P>::permute(self);
+                pos = 0;
+            }
+        }
+        pos
+    }
+
+    fn pad_domain_at(&mut self, pos: usize, domain: u8) {
+        const LANE_BYTES: usize = 8;
+        debug_assert!(pos < P::RATE_BYTES);
+        self.state_mut()[pos / LANE_BYTES] ^=
+            T::Lane::from_u64((domain as u64) << (8 * (pos % LANE_BYTES)));
+        KeccakOps::<P>::finalize_xor(self);
+    }
+
+    fn squeeze_at(&mut self, mut pos: usize, mut out: &mut [u8]) -> usize {
+        const LANE_BYTES: usize = 8;
+        debug_assert!(pos <= P::RATE_BYTES);
+
+        while !out.is_empty() {
+            if pos == P::RATE_BYTES {
+                KeccakOps::<P>::permute(self);
+                pos = 0;
+            }
+            let lane = self.state()[pos / LANE_BYTES].to_u64().to_le_bytes();
+            let offset = pos % LANE_BYTES;
+            let n = min(LANE_BYTES - offset, out.len());
+            let (head, rest) = mem::take(&mut out).split_at_mut(n);
+            head.copy_from_slice(&lane[offset..offset + n]);
+            out = rest;
+            pos += n;
+        }
+        pos
+    }
+
     fn squeeze(&mut self, out: &mut [u8]) {
         for out_blocks in out.chunks_mut(P::RATE_BYTES) {
             KeccakOps::<P>::permute(self);
             for (lane, out_bytes) in self.lanes_iter::<P>().zip(out_blocks.chunks_mut(8)) {
-                out_bytes.copy_from_slice(&lane.to_le_bytes());
+                out_bytes.copy_from_slice(&lane.to_u64().to_le_bytes()[..out_bytes.len()]);
             }
         }
     }
@@ -118,7 +186,7 @@ where
     #[inline(always)]
     fn theta(&mut self) {
         let state = self.state_mut();
-        let mut parity: [u64; 5] = array_init(|i| state[i]);
+        let mut parity: [T::Lane; 5] = array_init(|i| state[i]);
 
         unroll! {
             for j in 0..5{
@@ -166,7 +234,7 @@ where
         // for plane in self.0.into_array_chunks_mut::<5>() {
 
         for plane in self.state_mut().as_mut().chunks_exact_mut(5) {
-            let mut tmp = [0; 2];
+            let mut tmp = [T::Lane::default(); 2];
             unroll! {
                 // due to an unroll! bug
                 for j in (3..5).step_by(1) {
@@ -188,7 +256,7 @@ where
 
     /// ι (iota): the first lane is XORed with the round constant
     #[inline(always)]
-    fn iota(&mut self, rc: &u64) {
-        self.state_mut()[0] ^= rc;
+    fn iota(&mut self, round: usize) {
+        self.state_mut()[0] ^= T::Lane::RC[round];
     }
 }
@@ -0,0 +1,318 @@
+//! Four independent Keccak-f[1600] states processed in parallel, like `KeccakF1600x4` of the AVX2
+//! implementations of Kyber and Dilithium
+//!
+//! The portable permutation works on [`Lane4`]s. With the `std` feature on x86/x86_64, an implementation with
+//! AVX2 intrinsics, holding each `Lane4` in a 256-bit register, is selected at runtime if the CPU supports it.
+
+use super::keccak_f1600::{PI, RHO};
+use super::{Keccak, KeccakParams, KeccakState, RC};
+use crate::lib::ops::{BitXor, BitXorAssign};
+use crate::lib::slice::{Iter, IterMut};
+
+use zeroize::Zeroize;
+
+/// The same lane of four states
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Zeroize)]
+pub struct Lane4(pub [u64; 4]);
+
+impl Lane4 {
+    #[inline(always)]
+    pub const fn splat(x: u64) -> Self {
+        Self([x; 4])
+    }
+
+    #[inline(always)]
+    fn rotate_left(self, n: u32) -> Self {
+        Self(self.0.map(|x| x.rotate_left(n)))
+    }
+
+    /// `!self & other`
+    #[inline(always)]
+    fn andnot(self, other: Self) -> Self {
+        let mut r = other;
+        for (r, a) in r.0.iter_mut().zip(self.0) {
+            *r &= !a;
+        }
+        r
+    }
+}
+
+impl BitXorAssign for Lane4 {
+    #[inline(always)]
+    fn bitxor_assign(&mut self, rhs: Self) {
+        for (a, b) in self.0.iter_mut().zip(rhs.0) {
+            *a ^= b;
+        }
+    }
+}
+
+impl BitXor for Lane4 {
+    type Output = Self;
+
+    #[inline(always)]
+    fn bitxor(mut self, rhs: Self) -> Self {
+        self ^= rhs;
+        self
+    }
+}
+
+pub type Keccak1600x4 = Keccak<Lane4, 25>;
+
+impl KeccakState for Keccak1600x4 {
+    const NUM_LANES: usize = 25;
+
+    type Lane = Lane4;
+    type State = [Self::Lane; 25];
+
+    #[inline(always)]
+    fn state(&self) -> &[Self::Lane; Self::NUM_LANES] {
+        &self.0
+    }
+
+    #[inline(always)]
+    fn state_mut(&mut self) -> &mut [Self::Lane; Self::NUM_LANES] {
+        &mut self.0
+    }
+
+    #[inline(always)]
+    fn reset(&mut self) {
+        if self.1 {
+            self.zeroize();
+        }
+        self.1 = true;
+    }
+
+    #[inline(always)]
+    fn lanes_iter_mut<P: KeccakParams>(&mut self) -> IterMut<'_, Self::Lane> {
+        self.0[..{ P::RATE_LANES as usize }].iter_mut()
+    }
+    #[inline(always)]
+    fn lanes_iter<P: KeccakParams>(&self) -> Iter<'_, Self::Lane> {
+        self.0[..{ P::RATE_LANES as usize }].iter()
+    }
+}
+
+fn permute_portable(state: &mut [Lane4; 25]) {
+    for rc in RC {
+        // θ
+        let mut parity = [Lane4::default(); 5];
+        for (j, p) in parity.iter_mut().enumerate() {
+            *p = state[j] ^ state[5 + j] ^ state[10 + j] ^ state[15 + j] ^ state[20 + j];
+        }
+        for j in 0..5 {
+            let d = parity[(j + 4) % 5] ^ parity[(j + 1) % 5].rotate_left(1);
+            for i in 0..5 {
+                state[5 * i + j] ^= d;
+            }
+        }
+
+        // ρ and π
+        let mut last = state[PI[23] as usize];
+        for (&pi, &rho) in PI.iter().zip(RHO.iter()) {
+            let pi = pi as usize;
+            let t = state[pi];
+            state[pi] = last.rotate_left(rho);
+            last = t;
+        }
+
+        // χ
+        for plane in state.chunks_exact_mut(5) {
+            let p = [plane[0], plane[1], plane[2], plane[3], plane[4]];
+            for j in 0..5 {
+                plane[j] ^= p[(j + 1) % 5].andnot(p[(j + 2) % 5]);
+            }
+        }
+
+        // ι
+        state[0] ^= Lane4::splat(rc);
+    }
+}
+
+/// The same steps as `permute_portable`, with each `Lane4` in a 256-bit register
+#[cfg(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+#[allow(unsafe_code)]
+mod avx2 {
+    use super::{Lane4, PI, RC, RHO};
+    use crunchy::unroll;
+
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+
+    #[inline(always)]
+    unsafe fn rotate_left(x: __m256i, n: u32) -> __m256i {
+        let shl = _mm_cvtsi32_si128(n as i32);
+        let shr = _mm_cvtsi32_si128(64 - n as i32);
+        _mm256_or_si256(_mm256_sll_epi64(x, shl), _mm256_srl_epi64(x, shr))
+    }
+
+    /// Safety: the CPU needs to support AVX2
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn permute(state: &mut [Lane4; 25]) {
+        let mut a = [_mm256_setzero_si256(); 25];
+        for (a, lane) in a.iter_mut().zip(state.iter()) {
+            *a = _mm256_loadu_si256(lane.0.as_ptr() as *const __m256i);
+        }
+
+        for rc in RC {
+            // θ
+            let mut parity = [_mm256_setzero_si256(); 5];
+            unroll! {
+                for j in 0..5 {
+                    parity[j] = _mm256_xor_si256(
+                        _mm256_xor_si256(_mm256_xor_si256(a[j], a[5 + j]), a[10 + j]),
+                        _mm256_xor_si256(a[15 + j], a[20 + j]),
+                    );
+                }
+            }
+            unroll! {
+                for j in 0..5 {
+                    let d = _mm256_xor_si256(
+                        parity[(j + 4) % 5],
+                        rotate_left(parity[(j + 1) % 5], 1),
+                    );
+                    unroll! {
+                        for i in 0..5 {
+                            a[5 * i + j] = _mm256_xor_si256(a[5 * i + j], d);
+                        }
+                    }
+                }
+            }
+
+            // ρ and π
+            let mut b = [_mm256_setzero_si256(); 25];
+            b[0] = a[0];
+            unroll! {
+                for i in 0..24 {
+                    b[PI[i] as usize] = rotate_left(a[PI[(i + 23) % 24] as usize], RHO[i]);
+                }
+            }
+
+            // χ
+            unroll! {
+                for i in 0..5 {
+                    unroll! {
+                        for j in 0..5 {
+                            a[5 * i + j] = _mm256_xor_si256(
+                                b[5 * i + j],
+                                _mm256_andnot_si256(b[5 * i + (j + 1) % 5], b[5 * i + (j + 2) % 5]),
+                            );
+                        }
+                    }
+                }
+            }
+
+            // ι
+            a[0] = _mm256_xor_si256(a[0], _mm256_set1_epi64x(rc as i64));
+        }
+
+        for (a, lane) in a.iter().zip(state.iter_mut()) {
+            _mm256_storeu_si256(lane.0.as_mut_ptr() as *mut __m256i, *a);
+        }
+    }
+}
+
+impl Keccak1600x4 {
+    /// Apply Keccak-f[1600] to all four states
+    #[inline]
+    pub fn permute(&mut self) {
+        #[cfg(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+        if std::is_x86_feature_detected!("avx2") {
+            // Safety: the CPU supports AVX2
+            #[allow(unsafe_code)]
+            unsafe {
+                avx2::permute(&mut self.0)
+            };
+            return;
+        }
+        permute_portable(&mut self.0);
+    }
+
+    /// Absorb `data || pad_words[k]` into the k-th state as a single padded block
+    /// `data` needs to be a whole number of lanes, shorter than the rate.
+    pub(crate) fn absorb_one_block<P: KeccakParams>(&mut self, data: &[u8], pad_words: [u64; 4]) {
+        const LANE_BYTES: usize = 8;
+        assert!(data.len() % LANE_BYTES == 0 && data.len() < P::RATE_BYTES - LANE_BYTES);
+
+        self.zeroize();
+        let n = data.len() / LANE_BYTES;
+        for (lane, chunk) in self.0.iter_mut().zip(data.chunks_exact(LANE_BYTES)) {
+            *lane = Lane4::splat(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        self.0[n] = Lane4(pad_words);
+        self.0[P::RATE_LANES as usize - 1] ^= Lane4::splat(1 << 63);
+    }
+
+    /// Squeeze the outputs of the four states, permuting before each block
+    pub(crate) fn squeeze<P: KeccakParams, const N: usize>(&mut self, out: &mut [[u8; N]; 4]) {
+        const LANE_BYTES: usize = 8;
+
+        for offset in (0..N).step_by(P::RATE_BYTES) {
+            self.permute();
+            let block_end = N.min(offset + P::RATE_BYTES);
+            for (i, lane) in self.lanes_iter::<P>().enumerate() {
+                let start = offset + i * LANE_BYTES;
+                if start >= block_end {
+                    break;
+                }
+                let end = block_end.min(start + LANE_BYTES);
+                for (out, x) in out.iter_mut().zip(lane.0) {
+                    out[start..end].copy_from_slice(&x.to_le_bytes()[..end - start]);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keccak::fips202::Shake128Params;
+    use crate::keccak::keccak_f1600::Keccak1600_64;
+    use crate::keccak::KeccakOps;
+    use rand::Rng;
+
+    #[test]
+    fn keccak_x4_vs_single() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..100 {
+            let mut singles: [Keccak1600_64; 4] = array_init::array_init(|_| {
+                let mut k = Keccak1600_64::default();
+                rng.fill(&mut k.0[..]);
+                k
+            });
+            let mut x4 = Keccak1600x4::default();
+            for (i, lane) in x4.0.iter_mut().enumerate() {
+                *lane = Lane4([
+                    singles[0].0[i],
+                    singles[1].0[i],
+                    singles[2].0[i],
+                    singles[3].0[i],
+                ]);
+            }
+
+            x4.permute();
+            for single in singles.iter_mut() {
+                KeccakOps::<Shake128Params>::permute(single);
+            }
+            for (i, lane) in x4.0.iter().enumerate() {
+                assert_eq!(
+                    *lane,
+                    Lane4([
+                        singles[0].0[i],
+                        singles[1].0[i],
+                        singles[2].0[i],
+                        singles[3].0[i]
+                    ])
+                );
+            }
+
+            let mut state = x4.0;
+            permute_portable(&mut state);
+            x4.permute();
+            assert_eq!(state, x4.0);
+        }
+    }
+}
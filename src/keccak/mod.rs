@@ -1,15 +1,53 @@
 pub mod fips202;
+pub mod interleaved;
 pub mod keccak_f1600;
+pub mod keccak_x4;
+#[cfg(feature = "digest")]
+mod rustcrypto;
+pub mod sp800_185;
+pub mod turboshake;
 
 use crate::lib::{
-    ops::{BitXorAssign, Index, IndexMut, Range, RangeTo},
+    ops::{BitAnd, BitXor, BitXorAssign, Index, IndexMut, Not, Range, RangeTo},
     slice::{Iter, IterMut},
 };
 use crate::utils::split::Splitter;
 
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
-#[derive(Zeroize)]
+/// Round constants of Keccak-f[1600]
+pub(crate) const RC: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// Keccak state of `NL` lanes of type `T`
+///
+/// Cloning snapshots a partially absorbed state, so a common prefix can be absorbed once and continued with
+/// different data. It is deliberately not `Copy`, and every clone is zeroized on drop.
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
 pub struct Keccak<T: Default + Copy + Zeroize, const NL: usize>(pub [T; NL], pub bool);
 
 impl<T: Default + Copy + Zeroize, const NL: usize> Default for Keccak<T, NL> {
@@ -31,50 +69,88 @@ pub trait KeccakOps<P: KeccakParams>: Zeroize {
     type Params: KeccakParams;
     const LANE_BYTES: usize = P::LANE_BYTES;
 
-    const RC: [u64; 24] = [
-        0x0000000000000001,
-        0x0000000000008082,
-        0x800000000000808a,
-        0x8000000080008000,
-        0x000000000000808b,
-        0x0000000080000001,
-        0x8000000080008081,
-        0x8000000000008009,
-        0x000000000000008a,
-        0x0000000000000088,
-        0x0000000080008009,
-        0x000000008000000a,
-        0x000000008000808b,
-        0x800000000000008b,
-        0x8000000000008089,
-        0x8000000000008003,
-        0x8000000000008002,
-        0x8000000000000080,
-        0x000000000000800a,
-        0x800000008000000a,
-        0x8000000080008081,
-        0x8000000000008080,
-        0x0000000080000001,
-        0x8000000080008008,
-    ];
+    const RC: [u64; 24] = RC;
 
     fn absorb(&mut self, data: &[u8]);
     fn finalize_xor(&mut self);
+
+    /// XOR `data` into the rate part of the state starting at byte position `pos`, applying the permutation
+    /// whenever the rate is full. Returns the position for the next call.
+    /// The state needs to be `reset` before absorbing the first chunk at position 0.
+    fn absorb_at(&mut self, pos: usize, data: &[u8]) -> usize;
+
+    /// Apply domain separation and padding at byte position `pos`, as returned by the last `absorb_at`.
+    /// The state is then ready to be squeezed.
+    #[inline(always)]
+    fn pad_at(&mut self, pos: usize) {
+        self.pad_domain_at(pos, P::DELIM);
+    }
+
+    /// `pad_at` with the domain separation byte `domain` instead of `P::DELIM`, for functions sharing the
+    /// sponge of `P` but not its padding
+    fn pad_domain_at(&mut self, pos: usize, domain: u8);
+
+    /// Squeeze `out.len()` bytes, continuing at byte position `pos` of the current output block and
+    /// applying the permutation whenever the block is exhausted. Returns the position for the next call.
+    /// Use `pos = RATE_BYTES` for the first call after `pad_at`.
+    fn squeeze_at(&mut self, pos: usize, out: &mut [u8]) -> usize;
+
     fn squeeze(&mut self, out: &mut [u8]);
 
+    /// Keccak-p[1600, NUM_ROUNDS], i.e. the last `NUM_ROUNDS` rounds of Keccak-f[1600]
     fn permute(&mut self) {
-        for rc in Self::RC[..{ P::NUM_ROUNDS }].iter() {
+        for round in Self::RC.len() - P::NUM_ROUNDS..Self::RC.len() {
             self.theta();
             self.rho_pi();
             self.chi();
-            self.iota(rc);
+            self.iota(round);
         }
     }
 
     fn theta(&mut self);
     fn rho_pi(&mut self);
     fn chi(&mut self);
-    fn iota(&mut self, rc: &u64);
+    /// XOR the round constant of round `round` (of Keccak-f[1600]) into the first lane
+    fn iota(&mut self, round: usize);
+}
+
+/// A 64-bit lane of Keccak-f[1600], in the representation of a backend
+pub trait KeccakLane:
+    Default
+    + Copy
+    + Zeroize
+    + BitXor<Output = Self>
+    + BitXorAssign
+    + BitAnd<Output = Self>
+    + Not<Output = Self>
+{
+    /// The round constants in this representation
+    const RC: [Self; 24];
+
+    /// Convert from the standard representation, with byte `i` of the lane as byte `i` of `x` in little-endian
+    fn from_u64(x: u64) -> Self;
+    /// Convert to the standard representation
+    fn to_u64(self) -> u64;
+    fn rotate_left(self, n: u32) -> Self;
+}
+
+impl KeccakLane for u64 {
+    const RC: [Self; 24] = RC;
+
+    #[inline(always)]
+    fn from_u64(x: u64) -> Self {
+        x
+    }
+
+    #[inline(always)]
+    fn to_u64(self) -> u64 {
+        self
+    }
+
+    #[inline(always)]
+    fn rotate_left(self, n: u32) -> Self {
+        u64::rotate_left(self, n)
+    }
 }
 
 pub trait KeccakState<const NUM_LANES: usize = 25>: Zeroize {
@@ -0,0 +1,262 @@
+//! Implementations of the [`digest`] traits for the incremental sponges of [`super::fips202`]
+//!
+//! The [`Absorber`]s of the SHA-3 and Keccak digests implement [`digest::Digest`], the SHAKE absorbers
+//! [`ExtendableOutput`] with [`XofReader`] as reader, so they can be used wherever the `sha3` crate is. The
+//! block size is the rate, as needed by HMAC.
+
+use super::fips202::*;
+use super::KeccakParams;
+use crate::lib::{fmt, mem};
+
+use digest::consts::{U104, U136, U144, U168, U28, U32, U48, U64, U72};
+use digest::crypto_common::{AlgorithmName, BlockSizeUser};
+use digest::{
+    ExtendableOutput, ExtendableOutputReset, FixedOutput, FixedOutputReset, HashMarker, Output,
+    OutputSizeUser, Reset, Update,
+};
+
+impl<P: KeccakParams, S: SpongeOps<P>> HashMarker for Absorber<P, S> {}
+
+impl<P: KeccakParams, S: SpongeOps<P>> Update for Absorber<P, S> {
+    #[inline]
+    fn update(&mut self, data: &[u8]) {
+        Absorber::update(self, data);
+    }
+}
+
+impl<P: KeccakParams, S: SpongeOps<P>> Reset for Absorber<P, S> {
+    #[inline]
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+macro_rules! impl_block_size_and_name {
+    ($params:ident, $sponge:ident, $block_size:ident, $name:literal) => {
+        impl BlockSizeUser for Absorber<$params, $sponge> {
+            type BlockSize = $block_size;
+        }
+
+        impl AlgorithmName for Absorber<$params, $sponge> {
+            fn write_alg_name(f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str($name)
+            }
+        }
+    };
+}
+
+impl_block_size_and_name!(Sha3_224Params, Sha3_224, U144, "SHA3-224");
+impl_block_size_and_name!(Sha3_256Params, Sha3_256, U136, "SHA3-256");
+impl_block_size_and_name!(Sha3_384Params, Sha3_384, U104, "SHA3-384");
+impl_block_size_and_name!(Sha3_512Params, Sha3_512, U72, "SHA3-512");
+impl_block_size_and_name!(Keccak224Params, Keccak224, U144, "Keccak-224");
+impl_block_size_and_name!(Keccak256Params, Keccak256, U136, "Keccak-256");
+impl_block_size_and_name!(Keccak384Params, Keccak384, U104, "Keccak-384");
+impl_block_size_and_name!(Keccak512Params, Keccak512, U72, "Keccak-512");
+impl_block_size_and_name!(Shake128Params, Shake128, U168, "SHAKE128");
+impl_block_size_and_name!(Shake256Params, Shake256, U136, "SHAKE256");
+
+macro_rules! impl_fixed_output {
+    ($params:ident, $digest:ident, $output_size:ident) => {
+        impl OutputSizeUser for Absorber<$params, $digest> {
+            type OutputSize = $output_size;
+        }
+
+        impl FixedOutput for Absorber<$params, $digest> {
+            #[inline]
+            fn finalize_into(self, out: &mut Output<Self>) {
+                self.finalize_xof().read(out);
+            }
+        }
+
+        impl FixedOutputReset for Absorber<$params, $digest> {
+            #[inline]
+            fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+                mem::take(self).finalize_xof().read(out);
+            }
+        }
+    };
+}
+
+impl_fixed_output!(Sha3_224Params, Sha3_224, U28);
+impl_fixed_output!(Sha3_256Params, Sha3_256, U32);
+impl_fixed_output!(Sha3_384Params, Sha3_384, U48);
+impl_fixed_output!(Sha3_512Params, Sha3_512, U64);
+impl_fixed_output!(Keccak224Params, Keccak224, U28);
+impl_fixed_output!(Keccak256Params, Keccak256, U32);
+impl_fixed_output!(Keccak384Params, Keccak384, U48);
+impl_fixed_output!(Keccak512Params, Keccak512, U64);
+
+macro_rules! impl_extendable_output {
+    ($params:ident, $xof:ident) => {
+        impl ExtendableOutput for Absorber<$params, $xof> {
+            type Reader = XofReader<$params, $xof>;
+
+            #[inline]
+            fn finalize_xof(self) -> Self::Reader {
+                Absorber::finalize_xof(self)
+            }
+        }
+
+        impl ExtendableOutputReset for Absorber<$params, $xof> {
+            #[inline]
+            fn finalize_xof_reset(&mut self) -> Self::Reader {
+                Absorber::finalize_xof(mem::take(self))
+            }
+        }
+    };
+}
+
+impl_extendable_output!(Shake128Params, Shake128);
+impl_extendable_output!(Shake256Params, Shake256);
+
+impl<P: KeccakParams, S: SpongeOps<P>> digest::XofReader for XofReader<P, S> {
+    #[inline]
+    fn read(&mut self, buffer: &mut [u8]) {
+        XofReader::read(self, buffer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use rand::{Rng, RngCore};
+    use std::*;
+
+    /// Hash random data with `D` and `G` through the `digest::Digest` interface
+    fn digest_vs_sha3<D: digest::Digest + FixedOutputReset, G: digest::Digest>() {
+        let mut rng = rand::thread_rng();
+        let mut hasher = D::new();
+
+        for n in (0..400).step_by(13) {
+            let mut data = vec![0u8; n];
+            rng.fill_bytes(&mut data);
+            let split = rng.gen_range(0..=n);
+
+            digest::Digest::update(&mut hasher, &data[..split]);
+            digest::Digest::update(&mut hasher, &data[split..]);
+            let out = hasher.finalize_reset();
+            assert_eq!(out.as_slice(), G::digest(&data).as_slice());
+            assert_eq!(D::digest(&data).as_slice(), out.as_slice());
+        }
+    }
+
+    fn xof_vs_sha3<X, G>()
+    where
+        X: Default + Update + ExtendableOutputReset,
+        G: Default + Update + ExtendableOutput,
+    {
+        use digest::XofReader;
+
+        let mut rng = rand::thread_rng();
+        let mut xof = X::default();
+
+        for n in (0..400).step_by(13) {
+            let mut data = vec![0u8; n];
+            rng.fill_bytes(&mut data);
+
+            xof.update(&data);
+            let mut out = [0u8; 300];
+            let mut reader = xof.finalize_xof_reset();
+            let split = rng.gen_range(0..=out.len());
+            reader.read(&mut out[..split]);
+            reader.read(&mut out[split..]);
+
+            let mut golden = [0u8; 300];
+            G::digest_xof(&data, &mut golden);
+            assert_eq!(out, golden);
+        }
+    }
+
+    #[test]
+    fn digest_traits() {
+        digest_vs_sha3::<Sha3_224Absorber, sha3::Sha3_224>();
+        digest_vs_sha3::<Sha3_256Absorber, sha3::Sha3_256>();
+        digest_vs_sha3::<Sha3_384Absorber, sha3::Sha3_384>();
+        digest_vs_sha3::<Sha3_512Absorber, sha3::Sha3_512>();
+        digest_vs_sha3::<Keccak224Absorber, sha3::Keccak224>();
+        digest_vs_sha3::<Keccak256Absorber, sha3::Keccak256>();
+        digest_vs_sha3::<Keccak384Absorber, sha3::Keccak384>();
+        digest_vs_sha3::<Keccak512Absorber, sha3::Keccak512>();
+    }
+
+    /// HMAC over `D` and `G` with keys shorter than, as long as and longer than the block size
+    fn hmac_vs_sha3<D, G>()
+    where
+        D: digest::Digest + BlockSizeUser + Clone,
+        G: digest::Digest + BlockSizeUser + Clone,
+    {
+        use hmac::{Mac, SimpleHmac};
+
+        let mut rng = rand::thread_rng();
+        let block_size = D::block_size();
+        assert_eq!(block_size, G::block_size());
+
+        for key_len in [
+            0,
+            16,
+            block_size - 1,
+            block_size,
+            block_size + 1,
+            3 * block_size,
+        ] {
+            let mut key = vec![0u8; key_len];
+            rng.fill_bytes(&mut key);
+            let mut data = vec![0u8; rng.gen_range(0..500)];
+            rng.fill_bytes(&mut data);
+
+            let mut mac = <SimpleHmac<D> as Mac>::new_from_slice(&key).unwrap();
+            let mut golden = <SimpleHmac<G> as Mac>::new_from_slice(&key).unwrap();
+            Mac::update(&mut mac, &data);
+            Mac::update(&mut golden, &data);
+            assert_eq!(
+                mac.finalize().into_bytes().as_slice(),
+                golden.finalize().into_bytes().as_slice()
+            );
+        }
+    }
+
+    #[test]
+    fn hmac() {
+        hmac_vs_sha3::<Sha3_224Absorber, sha3::Sha3_224>();
+        hmac_vs_sha3::<Sha3_256Absorber, sha3::Sha3_256>();
+        hmac_vs_sha3::<Sha3_384Absorber, sha3::Sha3_384>();
+        hmac_vs_sha3::<Sha3_512Absorber, sha3::Sha3_512>();
+        hmac_vs_sha3::<Keccak256Absorber, sha3::Keccak256>();
+        hmac_vs_sha3::<Keccak512Absorber, sha3::Keccak512>();
+    }
+
+    struct Name<T>(marker::PhantomData<T>);
+
+    impl<T: AlgorithmName> fmt::Display for Name<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            T::write_alg_name(f)
+        }
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(format!("{:?}", Sha3_256Absorber::new()), "Absorber { .. }");
+        let name = |name: &dyn fmt::Display| format!("{}", name);
+        assert_eq!(
+            name(&Name::<Sha3_256Absorber>(marker::PhantomData)),
+            "SHA3-256"
+        );
+        assert_eq!(
+            name(&Name::<Keccak512Absorber>(marker::PhantomData)),
+            "Keccak-512"
+        );
+        assert_eq!(
+            name(&Name::<Shake128Absorber>(marker::PhantomData)),
+            "SHAKE128"
+        );
+    }
+
+    #[test]
+    fn xof_traits() {
+        xof_vs_sha3::<Shake128Absorber, sha3::Shake128>();
+        xof_vs_sha3::<Shake256Absorber, sha3::Shake256>();
+    }
+}
@@ -0,0 +1,753 @@
+//! SHA-3 derived functions of NIST SP 800-185: cSHAKE, KMAC, TupleHash and ParallelHash
+//!
+//! All of them are built on cSHAKE, which absorbs `bytepad(encode_string(N) || encode_string(S), rate)` for
+//! the function name `N` and customization string `S` before the data and pads with the domain separation
+//! `0x04`. With `N` and `S` both empty, cSHAKE is SHAKE.
+
+use super::fips202::{
+    Absorber, Shake128, Shake128Params, Shake256, Shake256Params, SpongeOps, XofReader,
+};
+use super::KeccakParams;
+use crate::lib::{cmp::min, marker::PhantomData, mem};
+
+pub struct CShake128Params;
+
+impl KeccakParams for CShake128Params {
+    const RATE_LANES: u8 = 21;
+    const DELIM: u8 = 0x04;
+}
+
+pub struct CShake256Params;
+
+impl KeccakParams for CShake256Params {
+    const RATE_LANES: u8 = 17;
+    const DELIM: u8 = 0x04;
+}
+
+pub trait CShakeParams: KeccakParams {
+    /// SHAKE with the same rate, used for empty `N` and `S`
+    type Shake: KeccakParams;
+    /// Sponge holding the state, zeroized on drop
+    type Sponge: SpongeOps<Self::Shake> + Clone;
+    /// Output bytes of the inner hashes of ParallelHash, twice the security strength
+    const CHAINING_BYTES: usize;
+}
+
+impl CShakeParams for CShake128Params {
+    type Shake = Shake128Params;
+    type Sponge = Shake128;
+    const CHAINING_BYTES: usize = 32;
+}
+
+impl CShakeParams for CShake256Params {
+    type Shake = Shake256Params;
+    type Sponge = Shake256;
+    const CHAINING_BYTES: usize = 64;
+}
+
+/// `left_encode(x)`: the byte length `n` of `x`, followed by the `n` big-endian bytes of `x`
+fn left_encode(x: u64, buf: &mut [u8; 9]) -> &[u8] {
+    let n = (8 - x.leading_zeros() as usize / 8).max(1);
+    buf[0] = n as u8;
+    buf[1..=n].copy_from_slice(&x.to_be_bytes()[8 - n..]);
+    &buf[..=n]
+}
+
+/// `right_encode(x)`: the `n` big-endian bytes of `x`, followed by their number `n`
+fn right_encode(x: u64, buf: &mut [u8; 9]) -> &[u8] {
+    let n = (8 - x.leading_zeros() as usize / 8).max(1);
+    buf[..n].copy_from_slice(&x.to_be_bytes()[8 - n..]);
+    buf[n] = n as u8;
+    &buf[..=n]
+}
+
+/// Bit length of `bytes` bytes, as encoded by `left_encode` and `right_encode`
+#[inline(always)]
+fn bit_len(bytes: usize) -> u64 {
+    8 * bytes as u64
+}
+
+/// cSHAKE128 or cSHAKE256 on the [`Absorber`] of SHAKE with the same rate, padded with `P::DELIM` unless
+/// `N` and `S` are empty
+pub struct CShake<P: CShakeParams> {
+    absorber: Absorber<P::Shake, P::Sponge>,
+    shake: bool,
+    params: PhantomData<P>,
+}
+
+/// Output of a finalized [`CShake`], that of SHAKE with the same rate
+pub type CShakeReader<P> = XofReader<<P as CShakeParams>::Shake, <P as CShakeParams>::Sponge>;
+
+impl<P: CShakeParams> Clone for CShake<P> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            absorber: self.absorber.clone(),
+            shake: self.shake,
+            params: PhantomData,
+        }
+    }
+}
+
+pub type CShake128 = CShake<CShake128Params>;
+pub type CShake256 = CShake<CShake256Params>;
+pub type CShake128Reader = CShakeReader<CShake128Params>;
+pub type CShake256Reader = CShakeReader<CShake256Params>;
+
+impl<P: CShakeParams> CShake<P> {
+    /// cSHAKE with function name `function_name` (`N`) and customization string `customization` (`S`)
+    pub fn new(function_name: &[u8], customization: &[u8]) -> Self {
+        let shake = function_name.is_empty() && customization.is_empty();
+        let mut cshake = Self {
+            absorber: Absorber::new(),
+            shake,
+            params: PhantomData,
+        };
+        if !shake {
+            cshake.bytepad(&[function_name, customization]);
+        }
+        cshake
+    }
+
+    /// Snapshot of the data absorbed so far, see [`Absorber::fork`]
+    #[inline]
+    pub fn fork(&self) -> Self {
+        self.clone()
+    }
+
+    #[inline]
+    pub fn update(&mut self, data: &[u8]) {
+        self.absorber.update(data);
+    }
+
+    /// Absorb `encode_string(s)`
+    fn update_encoded(&mut self, s: &[u8]) {
+        self.update(left_encode(bit_len(s.len()), &mut [0; 9]));
+        self.update(s);
+    }
+
+    /// Absorb `bytepad(encode_string(strings[0]) || encode_string(strings[1]) || ..., rate)`
+    fn bytepad(&mut self, strings: &[&[u8]]) {
+        self.update(left_encode(P::RATE_BYTES as u64, &mut [0; 9]));
+        for s in strings {
+            self.update_encoded(s);
+        }
+        self.absorber.zero_pad_block();
+    }
+
+    /// Pad and switch to squeezing
+    pub fn finalize_xof(self) -> CShakeReader<P> {
+        let domain = if self.shake {
+            P::Shake::DELIM
+        } else {
+            P::DELIM
+        };
+        self.absorber.finalize_xof_with_domain(domain)
+    }
+}
+
+/// KMAC128 or KMAC256: cSHAKE with `N = "KMAC"`, keyed by absorbing `bytepad(encode_string(K), rate)`
+pub struct Kmac<P: CShakeParams>(CShake<P>);
+
+impl<P: CShakeParams> Clone for Kmac<P> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+pub type Kmac128 = Kmac<CShake128Params>;
+pub type Kmac256 = Kmac<CShake256Params>;
+
+impl<P: CShakeParams> Kmac<P> {
+    pub fn new(key: &[u8], customization: &[u8]) -> Self {
+        let mut cshake = CShake::new(b"KMAC", customization);
+        cshake.bytepad(&[key]);
+        Self(cshake)
+    }
+
+    /// Snapshot of the keyed state and the data absorbed so far, to MAC several messages with a common
+    /// prefix, or with the same key, absorbing it once
+    #[inline]
+    pub fn fork(&self) -> Self {
+        self.clone()
+    }
+
+    #[inline]
+    pub fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    /// Write the MAC of length `out.len()` to `out`, which the output depends on
+    pub fn finalize(mut self, out: &mut [u8]) {
+        self.0.update(right_encode(bit_len(out.len()), &mut [0; 9]));
+        self.0.finalize_xof().read(out);
+    }
+
+    /// KMACXOF: output of arbitrary length, independent of the length read
+    pub fn finalize_xof(mut self) -> CShakeReader<P> {
+        self.0.update(right_encode(0, &mut [0; 9]));
+        self.0.finalize_xof()
+    }
+}
+
+/// TupleHash128 or TupleHash256: unambiguous hashing of a tuple of byte strings, cSHAKE with
+/// `N = "TupleHash"` of `encode_string(X[0]) || encode_string(X[1]) || ...`
+pub struct TupleHash<P: CShakeParams>(CShake<P>);
+
+impl<P: CShakeParams> Clone for TupleHash<P> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+pub type TupleHash128 = TupleHash<CShake128Params>;
+pub type TupleHash256 = TupleHash<CShake256Params>;
+
+impl<P: CShakeParams> TupleHash<P> {
+    pub fn new(customization: &[u8]) -> Self {
+        Self(CShake::new(b"TupleHash", customization))
+    }
+
+    /// Append `element` to the tuple; each element needs to be passed in a single call
+    #[inline]
+    pub fn update(&mut self, element: &[u8]) {
+        self.0.update_encoded(element);
+    }
+
+    /// Write the hash of length `out.len()` to `out`, which the output depends on
+    pub fn finalize(mut self, out: &mut [u8]) {
+        self.0.update(right_encode(bit_len(out.len()), &mut [0; 9]));
+        self.0.finalize_xof().read(out);
+    }
+
+    /// TupleHashXOF: output of arbitrary length, independent of the length read
+    pub fn finalize_xof(mut self) -> CShakeReader<P> {
+        self.0.update(right_encode(0, &mut [0; 9]));
+        self.0.finalize_xof()
+    }
+}
+
+/// ParallelHash128 or ParallelHash256: the data is split into blocks of `block_size` bytes, each hashed
+/// with SHAKE to `CHAINING_BYTES`, and the concatenated chaining values are hashed with cSHAKE with
+/// `N = "ParallelHash"`
+///
+/// Blocks are hashed sequentially as the data arrives, with a single SHAKE instance for the current block.
+pub struct ParallelHash<P: CShakeParams> {
+    cshake: CShake<P>,
+    block: CShake<P>,
+    block_size: usize,
+    block_pos: usize,
+    blocks: u64,
+}
+
+impl<P: CShakeParams> Clone for ParallelHash<P> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            cshake: self.cshake.clone(),
+            block: self.block.clone(),
+            block_size: self.block_size,
+            block_pos: self.block_pos,
+            blocks: self.blocks,
+        }
+    }
+}
+
+pub type ParallelHash128 = ParallelHash<CShake128Params>;
+pub type ParallelHash256 = ParallelHash<CShake256Params>;
+
+impl<P: CShakeParams> ParallelHash<P> {
+    /// Panics if `block_size == 0`
+    pub fn new(block_size: usize, customization: &[u8]) -> Self {
+        assert!(block_size > 0);
+        let mut cshake = CShake::new(b"ParallelHash", customization);
+        cshake.update(left_encode(block_size as u64, &mut [0; 9]));
+        Self {
+            cshake,
+            block: CShake::new(b"", b""),
+            block_size,
+            block_pos: 0,
+            blocks: 0,
+        }
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let n = min(self.block_size - self.block_pos, data.len());
+            let (head, rest) = data.split_at(n);
+            self.block.update(head);
+            self.block_pos += n;
+            data = rest;
+            if self.block_pos == self.block_size {
+                self.finish_block();
+            }
+        }
+    }
+
+    /// Absorb the chaining value of the current block
+    fn finish_block(&mut self) {
+        let block = mem::replace(&mut self.block, CShake::new(b"", b""));
+        let mut chaining_value = [0u8; 64];
+        let chaining_value = &mut chaining_value[..P::CHAINING_BYTES];
+        block.finalize_xof().read(chaining_value);
+        self.cshake.update(chaining_value);
+        self.block_pos = 0;
+        self.blocks += 1;
+    }
+
+    /// Absorb the last partial block and `right_encode(n)` for the number of blocks `n`
+    fn finish_blocks(&mut self) {
+        if self.block_pos > 0 {
+            self.finish_block();
+        }
+        let blocks = self.blocks;
+        self.cshake.update(right_encode(blocks, &mut [0; 9]));
+    }
+
+    /// Write the hash of length `out.len()` to `out`, which the output depends on
+    pub fn finalize(mut self, out: &mut [u8]) {
+        self.finish_blocks();
+        self.cshake
+            .update(right_encode(bit_len(out.len()), &mut [0; 9]));
+        self.cshake.finalize_xof().read(out);
+    }
+
+    /// ParallelHashXOF: output of arbitrary length, independent of the length read
+    pub fn finalize_xof(mut self) -> CShakeReader<P> {
+        self.finish_blocks();
+        self.cshake.update(right_encode(0, &mut [0; 9]));
+        self.cshake.finalize_xof()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use hex_literal::hex;
+    use rand::{Rng, RngCore};
+    use std::vec::Vec;
+    use std::*;
+
+    const EMAIL: &[u8] = b"Email Signature";
+    const TAGGED: &[u8] = b"My Tagged Application";
+    const TUPLE: &[u8] = b"My Tuple App";
+    const PARALLEL: &[u8] = b"Parallel Data";
+
+    fn range(n: u8) -> Vec<u8> {
+        (0..n).collect()
+    }
+
+    fn cshake<P: CShakeParams>(n: &[u8], s: &[u8], data: &[u8], out: &mut [u8]) {
+        let mut cshake = CShake::<P>::new(n, s);
+        cshake.update(data);
+        cshake.finalize_xof().read(out);
+    }
+
+    #[test]
+    fn encodings() {
+        let mut buf = [0; 9];
+        assert_eq!(left_encode(0, &mut buf), [1, 0]);
+        assert_eq!(left_encode(168, &mut buf), [1, 168]);
+        assert_eq!(left_encode(256, &mut buf), [2, 1, 0]);
+        assert_eq!(
+            left_encode(u64::MAX, &mut buf),
+            [8, 255, 255, 255, 255, 255, 255, 255, 255]
+        );
+        assert_eq!(right_encode(0, &mut buf), [0, 1]);
+        assert_eq!(right_encode(256, &mut buf), [1, 0, 2]);
+    }
+
+    #[test]
+    fn cshake_nist() {
+        let mut out = [0u8; 32];
+        cshake::<CShake128Params>(b"", EMAIL, &range(4), &mut out);
+        assert_eq!(
+            out,
+            hex!("c1c36925b6409a04f1b504fcbca9d82b4017277cb5ed2b2065fc1d3814d5aaf5")
+        );
+        cshake::<CShake128Params>(b"", EMAIL, &range(200), &mut out);
+        assert_eq!(
+            out,
+            hex!("c5221d50e4f822d96a2e8881a961420f294b7b24fe3d2094baed2c6524cc166b")
+        );
+
+        let mut out = [0u8; 64];
+        cshake::<CShake256Params>(b"", EMAIL, &range(4), &mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "d008828e2b80ac9d2218ffee1d070c48b8e4c87bff32c9699d5b6896eee0edd1"
+                "64020e2be0560858d9c00c037e34a96937c561a74c412bb4c746469527281c8c"
+            )
+        );
+        cshake::<CShake256Params>(b"", EMAIL, &range(200), &mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "07dc27b11e51fbac75bc7b3c1d983e8b4b85fb1defaf218912ac864302730917"
+                "27f42b17ed1df63e8ec118f04b23633c1dfb1574c8fb55cb45da8e25afb092bb"
+            )
+        );
+    }
+
+    #[test]
+    fn cshake_rand() {
+        use sha3::digest::{ExtendableOutput, Update, XofReader};
+
+        let mut rng = rand::thread_rng();
+        const MAX_LEN: usize = if cfg!(miri) { 10 } else { 400 };
+
+        for n in (0..MAX_LEN).step_by(7) {
+            let mut data = vec![0u8; n];
+            rng.fill_bytes(&mut data);
+            let name = &data[..rng.gen_range(0..=n.min(50))];
+            let custom = &data[..rng.gen_range(0..=n.min(200))];
+
+            let mut out = [0u8; 300];
+            let mut golden = [0u8; 300];
+
+            let mut cshake128 = CShake128::new(name, custom);
+            for chunk in data.chunks(rng.gen_range(1..=n.max(1))) {
+                cshake128.update(chunk);
+            }
+            let mut reader = cshake128.finalize_xof();
+            let split = rng.gen_range(0..=out.len());
+            reader.read(&mut out[..split]);
+            reader.read(&mut out[split..]);
+
+            let mut another = sha3::CShake128::from_core(
+                sha3::CShake128Core::new_with_function_name(name, custom),
+            );
+            another.update(&data);
+            another.finalize_xof().read(&mut golden);
+            assert_eq!(out, golden);
+
+            cshake::<CShake256Params>(name, custom, &data, &mut out);
+            let mut another = sha3::CShake256::from_core(
+                sha3::CShake256Core::new_with_function_name(name, custom),
+            );
+            another.update(&data);
+            another.finalize_xof().read(&mut golden);
+            assert_eq!(out, golden);
+        }
+    }
+
+    #[test]
+    fn kmac_nist() {
+        let key = (0x40..0x60).collect::<Vec<u8>>();
+        let kmac = |s: &[u8], data: &[u8]| {
+            let mut kmac = Kmac128::new(&key, s);
+            kmac.update(data);
+            kmac
+        };
+        let kmac256 = |s: &[u8], data: &[u8]| {
+            let mut kmac = Kmac256::new(&key, s);
+            kmac.update(data);
+            kmac
+        };
+
+        let mut out = [0u8; 32];
+        kmac(b"", &range(4)).finalize(&mut out);
+        assert_eq!(
+            out,
+            hex!("e5780b0d3ea6f7d3a429c5706aa43a00fadbd7d49628839e3187243f456ee14e")
+        );
+        kmac(TAGGED, &range(4)).finalize(&mut out);
+        assert_eq!(
+            out,
+            hex!("3b1fba963cd8b0b59e8c1a6d71888b7143651af8ba0a7070c0979e2811324aa5")
+        );
+        kmac(TAGGED, &range(200)).finalize(&mut out);
+        assert_eq!(
+            out,
+            hex!("1f5b4e6cca02209e0dcb5ca635b89a15e271ecc760071dfd805faa38f9729230")
+        );
+        kmac(b"", &range(4)).finalize_xof().read(&mut out);
+        assert_eq!(
+            out,
+            hex!("cd83740bbd92ccc8cf032b1481a0f4460e7ca9dd12b08a0c4031178bacd6ec35")
+        );
+        kmac(TAGGED, &range(4)).finalize_xof().read(&mut out);
+        assert_eq!(
+            out,
+            hex!("31a44527b4ed9f5c6101d11de6d26f0620aa5c341def41299657fe9df1a3b16c")
+        );
+        kmac(TAGGED, &range(200)).finalize_xof().read(&mut out);
+        assert_eq!(
+            out,
+            hex!("47026c7cd793084aa0283c253ef658490c0db61438b8326fe9bddf281b83ae0f")
+        );
+
+        let mut out = [0u8; 64];
+        kmac256(TAGGED, &range(4)).finalize(&mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "20c570c31346f703c9ac36c61c03cb64c3970d0cfc787e9b79599d273a68d2f7"
+                "f69d4cc3de9d104a351689f27cf6f5951f0103f33f4f24871024d9c27773a8dd"
+            )
+        );
+        kmac256(b"", &range(200)).finalize(&mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "75358cf39e41494e949707927cee0af20a3ff553904c86b08f21cc414bcfd691"
+                "589d27cf5e15369cbbff8b9a4c2eb17800855d0235ff635da82533ec6b759b69"
+            )
+        );
+        kmac256(TAGGED, &range(200)).finalize(&mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "b58618f71f92e1d56c1b8c55ddd7cd188b97b4ca4d99831eb2699a837da2e4d9"
+                "70fbacfde50033aea585f1a2708510c32d07880801bd182898fe476876fc8965"
+            )
+        );
+        kmac256(TAGGED, &range(4)).finalize_xof().read(&mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "1755133f1534752aad0748f2c706fb5c784512cab835cd15676b16c0c6647fa9"
+                "6faa7af634a0bf8ff6df39374fa00fad9a39e322a7c92065a64eb1fb0801eb2b"
+            )
+        );
+        kmac256(b"", &range(200)).finalize_xof().read(&mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "ff7b171f1e8a2b24683eed37830ee797538ba8dc563f6da1e667391a75edc02c"
+                "a633079f81ce12a25f45615ec89972031d18337331d24ceb8f8ca8e6a19fd98b"
+            )
+        );
+        kmac256(TAGGED, &range(200)).finalize_xof().read(&mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "d5be731c954ed7732846bb59dbe3a8e30f83e77a4bff4459f2f1c2b4ecebb8ce"
+                "67ba01c62e8ab8578d2d499bd1bb276768781190020a306a97de281dcc30305d"
+            )
+        );
+    }
+
+    #[test]
+    fn tuple_hash_nist() {
+        let elements = [
+            &hex!("000102")[..],
+            &hex!("101112131415")[..],
+            &hex!("202122232425262728")[..],
+        ];
+        let tuple_hash = |s: &[u8], n: usize| {
+            let mut h = TupleHash128::new(s);
+            elements[..n].iter().for_each(|e| h.update(e));
+            h
+        };
+        let tuple_hash256 = |s: &[u8], n: usize| {
+            let mut h = TupleHash256::new(s);
+            elements[..n].iter().for_each(|e| h.update(e));
+            h
+        };
+
+        let mut out = [0u8; 32];
+        tuple_hash(b"", 2).finalize(&mut out);
+        assert_eq!(
+            out,
+            hex!("c5d8786c1afb9b82111ab34b65b2c0048fa64e6d48e263264ce1707d3ffc8ed1")
+        );
+        tuple_hash(TUPLE, 2).finalize(&mut out);
+        assert_eq!(
+            out,
+            hex!("75cdb20ff4db1154e841d758e24160c54bae86eb8c13e7f5f40eb35588e96dfb")
+        );
+        tuple_hash(TUPLE, 3).finalize(&mut out);
+        assert_eq!(
+            out,
+            hex!("e60f202c89a2631eda8d4c588ca5fd07f39e5151998deccf973adb3804bb6e84")
+        );
+        tuple_hash(b"", 2).finalize_xof().read(&mut out);
+        assert_eq!(
+            out,
+            hex!("2f103cd7c32320353495c68de1a8129245c6325f6f2a3d608d92179c96e68488")
+        );
+        tuple_hash(TUPLE, 2).finalize_xof().read(&mut out);
+        assert_eq!(
+            out,
+            hex!("3fc8ad69453128292859a18b6c67d7ad85f01b32815e22ce839c49ec374e9b9a")
+        );
+        tuple_hash(TUPLE, 3).finalize_xof().read(&mut out);
+        assert_eq!(
+            out,
+            hex!("900fe16cad098d28e74d632ed852f99daab7f7df4d99e775657885b4bf76d6f8")
+        );
+
+        let mut out = [0u8; 64];
+        tuple_hash256(b"", 2).finalize(&mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "cfb7058caca5e668f81a12a20a2195ce97a925f1dba3e7449a56f82201ec6073"
+                "11ac2696b1ab5ea2352df1423bde7bd4bb78c9aed1a853c78672f9eb23bbe194"
+            )
+        );
+        tuple_hash256(TUPLE, 2).finalize(&mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "147c2191d5ed7efd98dbd96d7ab5a11692576f5fe2a5065f3e33de6bba9f3aa1"
+                "c4e9a068a289c61c95aab30aee1e410b0b607de3620e24a4e3bf9852a1d4367e"
+            )
+        );
+        tuple_hash256(TUPLE, 3).finalize(&mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "45000be63f9b6bfd89f54717670f69a9bc763591a4f05c50d68891a744bcc6e7"
+                "d6d5b5e82c018da999ed35b0bb49c9678e526abd8e85c13ed254021db9e790ce"
+            )
+        );
+        tuple_hash256(b"", 2).finalize_xof().read(&mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "03ded4610ed6450a1e3f8bc44951d14fbc384ab0efe57b000df6b6df5aae7cd5"
+                "68e77377daf13f37ec75cf5fc598b6841d51dd207c991cd45d210ba60ac52eb9"
+            )
+        );
+        tuple_hash256(TUPLE, 2).finalize_xof().read(&mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "6483cb3c9952eb20e830af4785851fc597ee3bf93bb7602c0ef6a65d741aeca7"
+                "e63c3b128981aa05c6d27438c79d2754bb1b7191f125d6620fca12ce658b2442"
+            )
+        );
+        tuple_hash256(TUPLE, 3).finalize_xof().read(&mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "0c59b11464f2336c34663ed51b2b950bec743610856f36c28d1d088d8a244628"
+                "4dd09830a6a178dc752376199fae935d86cfdee5913d4922dfd369b66a53c897"
+            )
+        );
+    }
+
+    #[test]
+    fn parallel_hash_nist() {
+        let x1 = hex!("000102030405060710111213141516172021222324252627");
+        let x3 = hex!(
+            "000102030405060708090a0b101112131415161718191a1b202122232425262728292a2b"
+            "303132333435363738393a3b404142434445464748494a4b505152535455565758595a5b"
+        );
+        let mut rng = rand::thread_rng();
+        let mut parallel_hash = |block_size: usize, s: &[u8], data: &[u8]| {
+            let mut h = ParallelHash128::new(block_size, s);
+            let split = rng.gen_range(0..=data.len());
+            h.update(&data[..split]);
+            h.update(&data[split..]);
+            h
+        };
+
+        let mut out = [0u8; 32];
+        parallel_hash(8, b"", &x1).finalize(&mut out);
+        assert_eq!(
+            out,
+            hex!("ba8dc1d1d979331d3f813603c67f72609ab5e44b94a0b8f9af46514454a2b4f5")
+        );
+        parallel_hash(8, PARALLEL, &x1).finalize(&mut out);
+        assert_eq!(
+            out,
+            hex!("fc484dcb3f84dceedc353438151bee58157d6efed0445a81f165e495795b7206")
+        );
+        parallel_hash(12, PARALLEL, &x3).finalize(&mut out);
+        assert_eq!(
+            out,
+            hex!("f7fd5312896c6685c828af7e2adb97e393e7f8d54e3c2ea4b95e5aca3796e8fc")
+        );
+        parallel_hash(8, b"", &x1).finalize_xof().read(&mut out);
+        assert_eq!(
+            out,
+            hex!("fe47d661e49ffe5b7d999922c062356750caf552985b8e8ce6667f2727c3c8d3")
+        );
+        parallel_hash(8, PARALLEL, &x1)
+            .finalize_xof()
+            .read(&mut out);
+        assert_eq!(
+            out,
+            hex!("ea2a793140820f7a128b8eb70a9439f93257c6e6e79b4a540d291d6dae7098d7")
+        );
+        parallel_hash(12, PARALLEL, &x3)
+            .finalize_xof()
+            .read(&mut out);
+        assert_eq!(
+            out,
+            hex!("0127ad9772ab904691987fcc4a24888f341fa0db2145e872d4efd255376602f0")
+        );
+
+        let mut out = [0u8; 64];
+        let mut h = ParallelHash256::new(8, b"");
+        h.update(&x1);
+        h.finalize(&mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "bc1ef124da34495e948ead207dd9842235da432d2bbc54b4c110e64c45110553"
+                "1b7f2a3e0ce055c02805e7c2de1fb746af97a1dd01f43b824e31b87612410429"
+            )
+        );
+        let mut h = ParallelHash256::new(8, b"");
+        h.update(&x1);
+        h.finalize_xof().read(&mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "c10a052722614684144d28474850b410757e3cba87651ba167a5cbddff7f4666"
+                "75fbf84bcae7378ac444be681d729499afca667fb879348bfdda427863c82f1c"
+            )
+        );
+    }
+
+    #[test]
+    fn fork() {
+        let mut rng = rand::thread_rng();
+        let mut data = vec![0u8; 500];
+        rng.fill_bytes(&mut data);
+
+        for split in [0, 1, 167, 168, 169, 400] {
+            let (prefix, rest) = data.split_at(split);
+
+            let mut kmac = Kmac256::new(b"key", TAGGED);
+            kmac.update(prefix);
+            let mut other = kmac.fork();
+            kmac.update(rest);
+            other.update(b"other");
+
+            let mut expected = Kmac256::new(b"key", TAGGED);
+            expected.update(&data);
+            let (mut out, mut golden) = ([0u8; 64], [0u8; 64]);
+            kmac.finalize(&mut out);
+            expected.finalize(&mut golden);
+            assert_eq!(out, golden);
+
+            let mut expected = Kmac256::new(b"key", TAGGED);
+            expected.update(prefix);
+            expected.update(b"other");
+            other.finalize(&mut out);
+            expected.finalize(&mut golden);
+            assert_eq!(out, golden);
+
+            let mut cshake128 = CShake128::new(b"", EMAIL);
+            cshake128.update(prefix);
+            let mut forked = cshake128.fork();
+            cshake128.update(rest);
+            forked.update(rest);
+            cshake128.finalize_xof().read(&mut out);
+            cshake::<CShake128Params>(b"", EMAIL, &data, &mut golden);
+            assert_eq!(out, golden);
+            forked.finalize_xof().read(&mut out);
+            assert_eq!(out, golden);
+        }
+    }
+}
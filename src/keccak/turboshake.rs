@@ -0,0 +1,823 @@
+//! TurboSHAKE and the KangarooTwelve tree hash of RFC 9861, on Keccak-p[1600, 12]
+//!
+//! TurboSHAKE is SHAKE with 12 instead of 24 rounds and a domain separation byte `D` in `0x01..=0x7f`.
+//! KT128 and KT256 split the input into chunks of 8192 bytes, hash all chunks but the first to chaining
+//! values with TurboSHAKE and absorb them into the final node after the first chunk.
+
+use super::fips202::{Absorber, HasKeccak, SpongeOps, XofReader};
+use super::{keccak_f1600::Keccak1600, KeccakParams};
+use crate::lib::{cmp::min, mem};
+
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub struct TurboShake128Params;
+
+impl KeccakParams for TurboShake128Params {
+    const RATE_LANES: u8 = 21;
+    const DELIM: u8 = 0x1f;
+    const NUM_ROUNDS: usize = 12;
+}
+
+pub struct TurboShake256Params;
+
+impl KeccakParams for TurboShake256Params {
+    const RATE_LANES: u8 = 17;
+    const DELIM: u8 = 0x1f;
+    const NUM_ROUNDS: usize = 12;
+}
+
+pub trait TurboShakeParams: KeccakParams + Sized {
+    /// Sponge holding the state, zeroized on drop
+    type Sponge: SpongeOps<Self> + Clone;
+    /// Output bytes of the leaves of KangarooTwelve, twice the security strength
+    const CHAINING_BYTES: usize;
+}
+
+#[derive(Clone, Default, Zeroize, ZeroizeOnDrop)]
+pub struct TurboShake128Sponge(Keccak1600);
+
+#[derive(Clone, Default, Zeroize, ZeroizeOnDrop)]
+pub struct TurboShake256Sponge(Keccak1600);
+
+macro_rules! turboshake_sponge {
+    ($sponge:ident, $params:ident, $chaining_bytes:literal) => {
+        impl TurboShakeParams for $params {
+            type Sponge = $sponge;
+            const CHAINING_BYTES: usize = $chaining_bytes;
+        }
+
+        impl SpongeOps<$params> for $sponge {}
+
+        impl HasKeccak<$params> for $sponge {
+            type Keccak = Keccak1600;
+            #[inline(always)]
+            fn keccak(&mut self) -> &mut Self::Keccak {
+                &mut self.0
+            }
+        }
+    };
+}
+
+turboshake_sponge!(TurboShake128Sponge, TurboShake128Params, 32);
+turboshake_sponge!(TurboShake256Sponge, TurboShake256Params, 64);
+
+/// TurboSHAKE128 or TurboSHAKE256 on an [`Absorber`] with Keccak-p[1600, 12], padded with `domain`
+pub struct TurboShake<P: TurboShakeParams> {
+    absorber: Absorber<P, P::Sponge>,
+    domain: u8,
+}
+
+impl<P: TurboShakeParams> Clone for TurboShake<P> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            absorber: self.absorber.clone(),
+            domain: self.domain,
+        }
+    }
+}
+
+/// Output of a finalized [`TurboShake`] or [`KangarooTwelve`]
+pub type TurboShakeReader<P> = XofReader<P, <P as TurboShakeParams>::Sponge>;
+
+pub type TurboShake128 = TurboShake<TurboShake128Params>;
+pub type TurboShake256 = TurboShake<TurboShake256Params>;
+pub type TurboShake128Reader = TurboShakeReader<TurboShake128Params>;
+pub type TurboShake256Reader = TurboShakeReader<TurboShake256Params>;
+
+impl<P: TurboShakeParams> Default for TurboShake<P> {
+    /// The default domain separation byte `0x1f`
+    #[inline]
+    fn default() -> Self {
+        Self::new(P::DELIM)
+    }
+}
+
+impl<P: TurboShakeParams> TurboShake<P> {
+    /// Panics if `domain` is not in `0x01..=0x7f`
+    pub fn new(domain: u8) -> Self {
+        assert!((0x01..=0x7f).contains(&domain));
+        Self {
+            absorber: Absorber::new(),
+            domain,
+        }
+    }
+
+    /// Snapshot of the data absorbed so far, see [`Absorber::fork`]
+    #[inline]
+    pub fn fork(&self) -> Self {
+        self.clone()
+    }
+
+    #[inline]
+    pub fn update(&mut self, data: &[u8]) {
+        self.absorber.update(data);
+    }
+
+    /// Pad with the domain separation byte and switch to squeezing
+    pub fn finalize_xof(self) -> TurboShakeReader<P> {
+        self.absorber.finalize_xof_with_domain(self.domain)
+    }
+}
+
+const CHUNK_BYTES: usize = 8192;
+
+/// Domain separation bytes of the nodes
+const SINGLE_NODE: u8 = 0x07;
+const LEAF_NODE: u8 = 0x0b;
+const FINAL_NODE: u8 = 0x06;
+
+/// `length_encode(x)`: the big-endian bytes of `x` without leading zeros, followed by their number
+fn length_encode(x: u64, buf: &mut [u8; 9]) -> &[u8] {
+    let n = 8 - x.leading_zeros() as usize / 8;
+    buf[..n].copy_from_slice(&x.to_be_bytes()[8 - n..]);
+    buf[n] = n as u8;
+    &buf[..=n]
+}
+
+/// KT128 or KT256 of the message and the customization string `customization`
+///
+/// Each leaf is finalized as soon as the following chunk starts, so only the final node and one leaf are held
+/// in memory at any time.
+pub struct KangarooTwelve<'a, P: TurboShakeParams> {
+    final_node: TurboShake<P>,
+    leaf: TurboShake<P>,
+    customization: &'a [u8],
+    /// Bytes absorbed into the current chunk
+    chunk_pos: usize,
+    /// Completed chunks, including the first one absorbed into `final_node`
+    chunks: u64,
+}
+
+pub type Kt128<'a> = KangarooTwelve<'a, TurboShake128Params>;
+pub type Kt256<'a> = KangarooTwelve<'a, TurboShake256Params>;
+
+impl<'a, P: TurboShakeParams> Clone for KangarooTwelve<'a, P> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            final_node: self.final_node.clone(),
+            leaf: self.leaf.clone(),
+            customization: self.customization,
+            chunk_pos: self.chunk_pos,
+            chunks: self.chunks,
+        }
+    }
+}
+
+impl<'a, P: TurboShakeParams> KangarooTwelve<'a, P> {
+    pub fn new(customization: &'a [u8]) -> Self {
+        Self {
+            final_node: TurboShake::new(SINGLE_NODE),
+            leaf: TurboShake::new(LEAF_NODE),
+            customization,
+            chunk_pos: 0,
+            chunks: 0,
+        }
+    }
+
+    /// Absorb the next part of the message
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            // a full chunk is only completed once more data follows, as a message of exactly
+            // `CHUNK_BYTES` is hashed by a single node
+            if self.chunk_pos == CHUNK_BYTES {
+                self.finish_chunk();
+            }
+            let n = min(CHUNK_BYTES - self.chunk_pos, data.len());
+            let (head, rest) = data.split_at(n);
+            if self.chunks == 0 {
+                self.final_node.update(head);
+            } else {
+                self.leaf.update(head);
+            }
+            self.chunk_pos += n;
+            data = rest;
+        }
+    }
+
+    /// Complete the first chunk with `0x03 || 0x00^7`, or absorb the chaining value of the current leaf
+    fn finish_chunk(&mut self) {
+        if self.chunks == 0 {
+            self.final_node.update(&[0x03, 0, 0, 0, 0, 0, 0, 0]);
+        } else {
+            let leaf = mem::replace(&mut self.leaf, TurboShake::new(LEAF_NODE));
+            let mut chaining_value = [0u8; 64];
+            let chaining_value = &mut chaining_value[..P::CHAINING_BYTES];
+            leaf.finalize_xof().read(chaining_value);
+            self.final_node.update(chaining_value);
+        }
+        self.chunk_pos = 0;
+        self.chunks += 1;
+    }
+
+    /// Absorb `customization || length_encode(|customization|)` and switch to squeezing
+    pub fn finalize_xof(mut self) -> TurboShakeReader<P> {
+        let customization = self.customization;
+        self.update(customization);
+        self.update(length_encode(customization.len() as u64, &mut [0; 9]));
+
+        if self.chunks > 0 {
+            self.finish_chunk();
+            let leaves = self.chunks - 1;
+            self.final_node.update(length_encode(leaves, &mut [0; 9]));
+            self.final_node.update(&[0xff, 0xff]);
+            self.final_node.domain = FINAL_NODE;
+        }
+        self.final_node.finalize_xof()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use hex_literal::hex;
+    use rand::{Rng, RngCore};
+    use std::vec::Vec;
+    use std::*;
+
+    /// `ptn(n)`: the bytes `0x00, 0x01, ..., 0xfa` repeated, truncated to `n` bytes
+    fn ptn(n: usize) -> Vec<u8> {
+        (0..n).map(|i| (i % 251) as u8).collect()
+    }
+
+    fn turboshake<P: TurboShakeParams>(message: &[u8], domain: u8, out: &mut [u8]) {
+        let mut turboshake = TurboShake::<P>::new(domain);
+        turboshake.update(message);
+        turboshake.finalize_xof().read(out);
+    }
+
+    fn kt<P: TurboShakeParams>(message: &[u8], customization: &[u8], out: &mut [u8]) {
+        let mut kt = KangarooTwelve::<P>::new(customization);
+        kt.update(message);
+        kt.finalize_xof().read(out);
+    }
+
+    /// The last `out.len()` bytes of the first `10000 + out.len()` output bytes of the empty message
+    fn long_output<P: TurboShakeParams>(mut reader: TurboShakeReader<P>, out: &mut [u8]) {
+        let mut skip = [0u8; 10000];
+        reader.read(&mut skip);
+        reader.read(out);
+    }
+
+    #[test]
+    fn length_encoding() {
+        let mut buf = [0; 9];
+        assert_eq!(length_encode(0, &mut buf), [0]);
+        assert_eq!(length_encode(12, &mut buf), [12, 1]);
+        assert_eq!(length_encode(65538, &mut buf), [1, 0, 2, 3]);
+    }
+
+    #[test]
+    fn kt128_rfc() {
+        let mut out = [0u8; 32];
+        let vectors = [
+            (
+                0,
+                hex!("1ac2d450fc3b4205d19da7bfca1b37513c0803577ac7167f06fe2ce1f0ef39e5"),
+            ),
+            (
+                1,
+                hex!("2bda92450e8b147f8a7cb629e784a058efca7cf7d8218e02d345dfaa65244a1f"),
+            ),
+            (
+                17,
+                hex!("6bf75fa2239198db4772e36478f8e19b0f371205f6a9a93a273f51df37122888"),
+            ),
+            (
+                17 * 17,
+                hex!("0c315ebcdedbf61426de7dcf8fb725d1e74675d7f5327a5067f367b108ecb67c"),
+            ),
+            (
+                17 * 17 * 17,
+                hex!("cb552e2ec77d9910701d578b457ddf772c12e322e4ee7fe417f92c758f0d59d0"),
+            ),
+            (
+                17 * 17 * 17 * 17,
+                hex!("8701045e22205345ff4dda05555cbb5c3af1a771c2b89baef37db43d9998b9fe"),
+            ),
+            (
+                17 * 17 * 17 * 17 * 17,
+                hex!("844d610933b1b9963cbdeb5ae3b6b05cc7cbd67ceedf883eb678a0a8e0371682"),
+            ),
+            (
+                17 * 17 * 17 * 17 * 17 * 17,
+                hex!("3c390782a8a4e89fa6367f72feaaf13255c8d95878481d3cd8ce85f58e880af8"),
+            ),
+        ];
+        for (n, expected) in vectors {
+            kt::<TurboShake128Params>(&ptn(n), &[], &mut out);
+            assert_eq!(out, expected);
+        }
+
+        let vectors = [
+            (
+                0,
+                1,
+                hex!("fab658db63e94a246188bf7af69a133045f46ee984c56e3c3328caaf1aa1a583"),
+            ),
+            (
+                1,
+                41,
+                hex!("d848c5068ced736f4462159b9867fd4c20b808acc3d5bc48e0b06ba0a3762ec4"),
+            ),
+            (
+                3,
+                41 * 41,
+                hex!("c389e5009ae57120854c2e8c64670ac01358cf4c1baf89447a724234dc7ced74"),
+            ),
+            (
+                7,
+                41 * 41 * 41,
+                hex!("75d2f86a2e644566726b4fbcfc5657b9dbcf070c7b0dca06450ab291d7443bcf"),
+            ),
+        ];
+        for (n, c, expected) in vectors {
+            kt::<TurboShake128Params>(&vec![0xff; n], &ptn(c), &mut out);
+            assert_eq!(out, expected);
+        }
+
+        let vectors = [
+            (
+                8191,
+                0,
+                hex!("1b577636f723643e990cc7d6a659837436fd6a103626600eb8301cd1dbe553d6"),
+            ),
+            (
+                8192,
+                0,
+                hex!("48f256f6772f9edfb6a8b661ec92dc93b95ebd05a08a17b39ae3490870c926c3"),
+            ),
+            (
+                8192,
+                8189,
+                hex!("3ed12f70fb05ddb58689510ab3e4d23c6c6033849aa01e1d8c220a297fedcd0b"),
+            ),
+            (
+                8192,
+                8190,
+                hex!("6a7c1b6a5cd0d8c9ca943a4a216cc64604559a2ea45f78570a15253d67ba00ae"),
+            ),
+        ];
+        for (m, c, expected) in vectors {
+            kt::<TurboShake128Params>(&ptn(m), &ptn(c), &mut out);
+            assert_eq!(out, expected);
+        }
+
+        long_output(Kt128::new(&[]).finalize_xof(), &mut out);
+        assert_eq!(
+            out,
+            hex!("e8dc563642f7228c84684c898405d3a834799158c079b12880277a1d28e2ff6d")
+        );
+    }
+
+    #[test]
+    fn kt256_rfc() {
+        let mut out = [0u8; 64];
+        let vectors = [
+            (
+                0,
+                hex!(
+                    "b23d2e9cea9f4904e02bec06817fc10ce38ce8e93ef4c89e6537076af8646404"
+                    "e3e8b68107b8833a5d30490aa33482353fd4adc7148ecb782855003aaebde4a9"
+                ),
+            ),
+            (
+                1,
+                hex!(
+                    "0d005a194085360217128cf17f91e1f71314efa5564539d444912e3437efa17f"
+                    "82db6f6ffe76e781eaa068bce01f2bbf81eacb983d7230f2fb02834a21b1ddd0"
+                ),
+            ),
+            (
+                17,
+                hex!(
+                    "1ba3c02b1fc514474f06c8979978a9056c8483f4a1b63d0dccefe3a28a2f323e"
+                    "1cdcca40ebf006ac76ef0397152346837b1277d3e7faa9c9653b19075098527b"
+                ),
+            ),
+            (
+                17 * 17,
+                hex!(
+                    "de8ccbc63e0f133ebb4416814d4c66f691bbf8b6a61ec0a7700f836b086cb029"
+                    "d54f12ac7159472c72db118c35b4e6aa213c6562caaa9dcc518959e69b10f3ba"
+                ),
+            ),
+            (
+                17 * 17 * 17,
+                hex!(
+                    "647efb49fe9d717500171b41e7f11bd491544443209997ce1c2530d15eb1ffbb"
+                    "598935ef954528ffc152b1e4d731ee2683680674365cd191d562bae753b84aa5"
+                ),
+            ),
+            (
+                17 * 17 * 17 * 17,
+                hex!(
+                    "b06275d284cd1cf205bcbe57dccd3ec1ff6686e3ed15776383e1f2fa3c6ac8f0"
+                    "8bf8a162829db1a44b2a43ff83dd89c3cf1ceb61ede659766d5ccf817a62ba8d"
+                ),
+            ),
+            (
+                17 * 17 * 17 * 17 * 17,
+                hex!(
+                    "9473831d76a4c7bf77ace45b59f1458b1673d64bcd877a7c66b2664aa6dd149e"
+                    "60eab71b5c2bab858c074ded81ddce2b4022b5215935c0d4d19bf511aeeb0772"
+                ),
+            ),
+            (
+                17 * 17 * 17 * 17 * 17 * 17,
+                hex!(
+                    "0652b740d78c5e1f7c8dcc1777097382768b7ff38f9a7a20f29f413bb1b3045b"
+                    "31a5578f568f911e09cf44746da84224a5266e96a4a535e871324e4f9c7004da"
+                ),
+            ),
+        ];
+        for (n, expected) in vectors {
+            kt::<TurboShake256Params>(&ptn(n), &[], &mut out);
+            assert_eq!(out, expected);
+        }
+
+        let vectors = [
+            (
+                0,
+                1,
+                hex!(
+                    "9280f5cc39b54a5a594ec63de0bb99371e4609d44bf845c2f5b8c316d72b1598"
+                    "11f748f23e3fabbe5c3226ec96c62186df2d33e9df74c5069ceecbb4dd10eff6"
+                ),
+            ),
+            (
+                1,
+                41,
+                hex!(
+                    "47ef96dd616f200937aa7847e34ec2feae8087e3761dc0f8c1a154f51dc9ccf8"
+                    "45d7adbce57ff64b639722c6a1672e3bf5372d87e00aff89be97240756998853"
+                ),
+            ),
+            (
+                3,
+                41 * 41,
+                hex!(
+                    "3b48667a5051c5966c53c5d42b95de451e05584e7806e2fb765eda959074172c"
+                    "b438a9e91dde337c98e9c41bed94c4e0aef431d0b64ef2324f7932caa6f54969"
+                ),
+            ),
+            (
+                7,
+                41 * 41 * 41,
+                hex!(
+                    "e0911cc00025e1540831e266d94add9b98712142b80d2629e643aac4efaf5a3a"
+                    "30a88cbf4ac2a91a2432743054fbcc9897670e86ba8cec2fc2ace9c966369724"
+                ),
+            ),
+        ];
+        for (n, c, expected) in vectors {
+            kt::<TurboShake256Params>(&vec![0xff; n], &ptn(c), &mut out);
+            assert_eq!(out, expected);
+        }
+
+        let vectors = [
+            (
+                8191,
+                0,
+                hex!(
+                    "3081434d93a4108d8d8a3305b89682cebedc7ca4ea8a3ce869fbb73cbe4a58ee"
+                    "f6f24de38ffc170514c70e7ab2d01f03812616e863d769afb3753193ba045b20"
+                ),
+            ),
+            (
+                8192,
+                0,
+                hex!(
+                    "c6ee8e2ad3200c018ac87aaa031cdac22121b412d07dc6e0dccbb53423747e9a"
+                    "1c18834d99df596cf0cf4b8dfafb7bf02d139d0c9035725adc1a01b7230a41fa"
+                ),
+            ),
+            (
+                8192,
+                8189,
+                hex!(
+                    "74e47879f10a9c5d11bd2da7e194fe57e86378bf3c3f7448eff3c576a0f18c5c"
+                    "aae0999979512090a7f348af4260d4de3c37f1ecaf8d2c2c96c1d16c64b12496"
+                ),
+            ),
+            (
+                8192,
+                8190,
+                hex!(
+                    "f4b5908b929ffe01e0f79ec2f21243d41a396b2e7303a6af1d6399cd6c7a0a2d"
+                    "d7c4f607e8277f9c9b1cb4ab9ddc59d4b92d1fc7558441f1832c3279a4241b8b"
+                ),
+            ),
+        ];
+        for (m, c, expected) in vectors {
+            kt::<TurboShake256Params>(&ptn(m), &ptn(c), &mut out);
+            assert_eq!(out, expected);
+        }
+
+        long_output(Kt256::new(&[]).finalize_xof(), &mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "ad4a1d718cf950506709a4c33396139b4449041fc79a05d68da35f1e453522e0"
+                "56c64fe94958e7085f2964888259b9932752f3ccd855288efee5fcbb8b563069"
+            )
+        );
+    }
+
+    #[test]
+    fn turboshake128_rfc() {
+        let mut out = [0u8; 32];
+        turboshake::<TurboShake128Params>(&[], 0x1f, &mut out);
+        assert_eq!(
+            out,
+            hex!("1e415f1c5983aff2169217277d17bb538cd945a397ddec541f1ce41af2c1b74c")
+        );
+        let mut out64 = [0u8; 64];
+        turboshake::<TurboShake128Params>(&[], 0x1f, &mut out64);
+        assert_eq!(
+            out64,
+            hex!(
+                "1e415f1c5983aff2169217277d17bb538cd945a397ddec541f1ce41af2c1b74c"
+                "3e8ccae2a4dae56c84a04c2385c03c15e8193bdf58737363321691c05462c8df"
+            )
+        );
+        long_output(TurboShake128::default().finalize_xof(), &mut out);
+        assert_eq!(
+            out,
+            hex!("a3b9b0385900ce761f22aed548e754da10a5242d62e8c658e3f3a923a7555607")
+        );
+
+        let vectors = [
+            (
+                1,
+                hex!("55cedd6f60af7bb29a4042ae832ef3f58db7299f893ebb9247247d856958daa9"),
+            ),
+            (
+                17,
+                hex!("9c97d036a3bac819db70ede0ca554ec6e4c2a1a4ffbfd9ec269ca6a111161233"),
+            ),
+            (
+                17 * 17,
+                hex!("96c77c279e0126f7fc07c9b07f5cdae1e0be60bdbe10620040e75d7223a624d2"),
+            ),
+            (
+                17 * 17 * 17,
+                hex!("d4976eb56bcf118520582b709f73e1d6853e001fdaf80e1b13e0d0599d5fb372"),
+            ),
+            (
+                17 * 17 * 17 * 17,
+                hex!("da67c7039e98bf530cf7a37830c6664e14cbab7f540f58403b1b82951318ee5c"),
+            ),
+            (
+                17 * 17 * 17 * 17 * 17,
+                hex!("b97a906fbf83ef7c812517abf3b2d0aea0c4f60318ce11cf103925127f59eecd"),
+            ),
+            (
+                17 * 17 * 17 * 17 * 17 * 17,
+                hex!("35cd494adeded2f25239af09a7b8ef0c4d1ca4fe2d1ac370fa63216fe7b4c2b1"),
+            ),
+        ];
+        for (n, expected) in vectors {
+            turboshake::<TurboShake128Params>(&ptn(n), 0x1f, &mut out);
+            assert_eq!(out, expected);
+        }
+
+        let vectors = [
+            (
+                3,
+                0x01,
+                hex!("bf323f940494e88ee1c540fe660be8a0c93f43d15ec006998462fa994eed5dab"),
+            ),
+            (
+                1,
+                0x06,
+                hex!("8ec9c66465ed0d4a6c35d13506718d687a25cb05c74cca1e42501abd83874a67"),
+            ),
+            (
+                3,
+                0x07,
+                hex!("b658576001cad9b1e5f399a9f77723bba05458042d68206f7252682dba3663ed"),
+            ),
+            (
+                7,
+                0x0b,
+                hex!("8deeaa1aec47ccee569f659c21dfa8e112db3cee37b18178b2acd805b799cc37"),
+            ),
+            (
+                1,
+                0x30,
+                hex!("553122e2135e363c3292bed2c6421fa232bab03daa07c7d6636603286506325b"),
+            ),
+            (
+                3,
+                0x7f,
+                hex!("16274cc656d44cefd422395d0f9053bda6d28e122aba15c765e5ad0e6eaf26f9"),
+            ),
+        ];
+        for (n, domain, expected) in vectors {
+            turboshake::<TurboShake128Params>(&vec![0xff; n], domain, &mut out);
+            assert_eq!(out, expected);
+        }
+    }
+
+    #[test]
+    fn turboshake256_rfc() {
+        let mut out = [0u8; 64];
+        turboshake::<TurboShake256Params>(&[], 0x1f, &mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "367a329dafea871c7802ec67f905ae13c57695dc2c6663c61035f59a18f8e7db"
+                "11edc0e12e91ea60eb6b32df06dd7f002fbafabb6e13ec1cc20d995547600db0"
+            )
+        );
+        long_output(TurboShake256::default().finalize_xof(), &mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "abefa11630c661269249742685ec082f207265dccf2f43534e9c61ba0c9d1d75"
+                "34ced68cfc7bb16b5db206e4583b904ccd2f0608616a58104d6f127c7bceed93"
+            )
+        );
+
+        let vectors = [
+            (
+                1,
+                hex!(
+                    "3e1712f928f8eaf1054632b2aa0a246ed8b0c378728f60bc970410155c28820e"
+                    "90cc90d8a3006aa2372c5c5ea176b0682bf22bae7467ac94f74d43d39b0482e2"
+                ),
+            ),
+            (
+                17,
+                hex!(
+                    "b3bab0300e6a191fbe6137939835923578794ea54843f5011090fa2f3780a9e5"
+                    "cb22c59d78b40a0fbff9e672c0fbe0970bd2c845091c6044d687054da5d8e9c7"
+                ),
+            ),
+            (
+                17 * 17,
+                hex!(
+                    "66b810db8e90780424c0847372fdc95710882fde31c6df75beb9d4cd9305cfca"
+                    "e35e7b83e8b7e6eb4b78605880116316fe2c078a09b94ad7b8213c0a738b65c0"
+                ),
+            ),
+            (
+                17 * 17 * 17,
+                hex!(
+                    "c74ebc919a5b3b0dd1228185ba02d29ef442d69d3d4276a93efe0bf9a16a7dc0"
+                    "cd4eabadab8cd7a5edd96695f5d360abe09e2c6511a3ec397da3b76b9e1674fb"
+                ),
+            ),
+            (
+                17 * 17 * 17 * 17,
+                hex!(
+                    "02cc3a8897e6f4f6ccb6fd46631b1f5207b66c6de9c7b55b2d1a23134a170afd"
+                    "ac234eaba9a77cff88c1f020b73724618c5687b362c430b248cd38647f848a1d"
+                ),
+            ),
+            (
+                17 * 17 * 17 * 17 * 17,
+                hex!(
+                    "add53b06543e584b5823f626996aee50fe45ed15f20243a7165485acb4aa76b4"
+                    "ffda75cedf6d8cdc95c332bd56f4b986b58bb17d1778bfc1b1a97545cdf4ec9f"
+                ),
+            ),
+            (
+                17 * 17 * 17 * 17 * 17 * 17,
+                hex!(
+                    "9e11bc59c24e73993c1484ec66358ef71db74aefd84e123f7800ba9c4853e02c"
+                    "fe701d9e6bb765a304f0dc34a4ee3ba82c410f0da70e86bfbd90ea877c2d6104"
+                ),
+            ),
+        ];
+        for (n, expected) in vectors {
+            turboshake::<TurboShake256Params>(&ptn(n), 0x1f, &mut out);
+            assert_eq!(out, expected);
+        }
+
+        let vectors = [
+            (
+                3,
+                0x01,
+                hex!(
+                    "d21c6fbbf587fa2282f29aea620175fb0257413af78a0b1b2a87419ce031d933"
+                    "ae7a4d383327a8a17641a34f8a1d1003ad7da6b72dba84bb62fef28f62f12424"
+                ),
+            ),
+            (
+                1,
+                0x06,
+                hex!(
+                    "738d7b4e37d18b7f22ad1b5313e357e3dd7d07056a26a303c433fa3533455280"
+                    "f4f5a7d4f700efb437fe6d281405e07be32a0a972e22e63adc1b090daefe004b"
+                ),
+            ),
+            (
+                3,
+                0x07,
+                hex!(
+                    "18b3b5b7061c2e67c1753a00e6ad7ed7ba1c906cf93efb7092eaf27fbeebb755"
+                    "ae6e292493c110e48d260028492b8e09b5500612b8f2578985ded5357d00ec67"
+                ),
+            ),
+            (
+                7,
+                0x0b,
+                hex!(
+                    "bb36764951ec97e9d85f7ee9a67a7718fc005cf42556be79ce12c0bde50e5736"
+                    "d6632b0d0dfb202d1bbb8ffe3dd74cb00834fa756cb03471bab13a1e2c16b3c0"
+                ),
+            ),
+            (
+                1,
+                0x30,
+                hex!(
+                    "f3fe12873d34bcbb2e608779d6b70e7f86bec7e90bf113cbd4fdd0c4e2f4625e"
+                    "148dd7ee1a52776cf77f240514d9ccfc3b5ddab8ee255e39ee389072962c111a"
+                ),
+            ),
+            (
+                3,
+                0x7f,
+                hex!(
+                    "abe569c1f77ec340f02705e7d37c9ab7e155516e4a6a150021d70b6fac0bb40c"
+                    "069f9a9828a0d575cd99f9bae435ab1acf7ed9110ba97ce0388d074bac768776"
+                ),
+            ),
+        ];
+        for (n, domain, expected) in vectors {
+            turboshake::<TurboShake256Params>(&vec![0xff; n], domain, &mut out);
+            assert_eq!(out, expected);
+        }
+    }
+
+    #[test]
+    fn kt_update_read() {
+        let mut rng = rand::thread_rng();
+
+        for n in [
+            0,
+            1,
+            CHUNK_BYTES - 1,
+            CHUNK_BYTES,
+            CHUNK_BYTES + 1,
+            3 * CHUNK_BYTES + 5,
+        ] {
+            let mut message = vec![0u8; n];
+            rng.fill_bytes(&mut message);
+            let customization = ptn(rng.gen_range(0..300));
+
+            let mut expected = [0u8; 200];
+            let mut kt = Kt256::new(&customization);
+            kt.update(&message);
+            kt.finalize_xof().read(&mut expected);
+
+            let mut out = [0u8; 200];
+            let mut kt = Kt256::new(&customization);
+            let mut rest = &message[..];
+            while !rest.is_empty() {
+                let (chunk, tail) = rest.split_at(rng.gen_range(0..=rest.len().min(5000)));
+                kt.update(chunk);
+                rest = tail;
+            }
+            let mut reader = kt.finalize_xof();
+            let split = rng.gen_range(0..=out.len());
+            reader.read(&mut out[..split]);
+            reader.read(&mut out[split..]);
+            assert_eq!(out, expected);
+        }
+    }
+
+    #[test]
+    fn fork() {
+        let message = ptn(3 * CHUNK_BYTES + 5);
+        let mut expected = [0u8; 64];
+        turboshake::<TurboShake128Params>(&message, 0x0b, &mut expected);
+
+        for split in [0, 1, 167, 168, 169, CHUNK_BYTES] {
+            let mut out = [0u8; 64];
+            let mut turboshake = TurboShake128::new(0x0b);
+            turboshake.update(&message[..split]);
+            let mut forked = turboshake.fork();
+            turboshake.update(b"other");
+            forked.update(&message[split..]);
+            forked.finalize_xof().read(&mut out);
+            assert_eq!(out, expected);
+        }
+
+        let mut expected = [0u8; 64];
+        kt::<TurboShake256Params>(&message, b"custom", &mut expected);
+        let mut out = [0u8; 64];
+        let mut kt = Kt256::new(b"custom");
+        kt.update(&message[..CHUNK_BYTES + 1]);
+        let mut cloned = kt.clone();
+        kt.update(b"other");
+        cloned.update(&message[CHUNK_BYTES + 1..]);
+        cloned.finalize_xof().read(&mut out);
+        assert_eq!(out, expected);
+    }
+}
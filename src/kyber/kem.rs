@@ -11,6 +11,20 @@ pub struct SecretKey<const K: usize> {
     pub z: [u8; KYBER_SSBYTES],
 }
 
+impl<const K: usize> SecretKey<K> {
+    /// `H(pk)` only depends on `pk`, it is computed once here rather than in every decapsulation
+    pub fn new(cpa_sk: CPASecretKey<K>, pk: PublicKey<K>, z: [u8; KYBER_SSBYTES]) -> Self {
+        let mut h_pk = [0u8; Sha3_256::DIGEST_BYTES];
+        pk.hash_h(&mut h_pk);
+        Self {
+            cpa_sk,
+            pk,
+            h_pk,
+            z,
+        }
+    }
+}
+
 trait Kem {}
 
 // pub fn encapsulate<R: RngCore, const K: usize, const KYBER_CIPHERTEXTBYTES: usize>(
@@ -1,5 +1,5 @@
 use super::ciphertext::{Ciphertext, CompressCiphertext};
-use crate::keccak::fips202::{Digest, Sha3_512};
+use crate::keccak::fips202::{Digest, Sha3_256Absorber, Sha3_512};
 use crate::kyber::{Prf, MSG_BYTES, NOISE_SEED_BYTES};
 use crate::poly::kyber::{KyberPoly, POLYBYTES};
 use crate::poly::{Polynomial, SizedPolynomial, UNIFORM_SEED_BYTES};
@@ -24,6 +24,18 @@ impl<const K: usize> Default for PublicKey<K> {
     }
 }
 
+impl<const K: usize> PublicKey<K> {
+    /// `H(pk)`, SHA3-256 of the serialized key `bytes || seed`, absorbed in place without serializing it
+    pub fn hash_h(&self, out: &mut [u8; KYBER_SYMBYTES]) {
+        let mut absorber = Sha3_256Absorber::new();
+        for poly_bytes in &self.bytes {
+            absorber.update(poly_bytes);
+        }
+        absorber.update(&self.seed);
+        absorber.finalize(out);
+    }
+}
+
 pub struct CPASecretKey<const K: usize>([[u8; POLYBYTES]; K]);
 
 impl<const K: usize> CPASecretKey<K> {
@@ -92,36 +104,28 @@ impl<const K: usize> PublicKeyScheme for KyberPke<K> {
 
         pk.seed = *public_seed; // copy_from_slice(public_seed) ???
 
-        let mut prf = Prf::default();
-
         let mut a_i = KyberPolyVec::<K>::default();
 
         let mut skpv = KyberPolyVec::<K>::default();
-        skpv.getnoise_eta1(&mut prf, noise_seed, 0);
+        skpv.getnoise_eta1(noise_seed, 0);
         skpv.ntt_and_reduce();
         skpv.serialize(sk.bytes_mut());
 
-        // if we can do getnoise_eta1 and/or ntt in parallel (for K polys at once), then this would be faster:
-        // let mut e = KyberPolyVec::<K>::default();
-        // e.getnoise_eta1(&mut prf, noise_seed, K as u8);
-        // e.ntt(); //  C ref: does an extra e.reduce() (which is not needed)
-
-        // otherwise Maybe this is ok (eliminates K-1 poly allocations)
-        let mut e_i = KyberPoly::default();
+        // the K noise polynomials are sampled four at a time
+        let mut e = KyberPolyVec::<K>::default();
+        e.getnoise_eta1(noise_seed, K as u8);
+        e.ntt(); //  C ref: does an extra e.reduce() (which is not needed)
 
         let mut pkpv = KyberPolyVec::<K>::default();
 
-        for (i, pk_poly) in pkpv.as_mut().iter_mut().enumerate() {
+        for (i, (pk_poly, e_i)) in pkpv.as_mut().iter_mut().zip(e.as_ref()).enumerate() {
             a_i.uniform_xof::<false>(public_seed, i as u8);
 
             pk_poly.vector_mul_acc(&a_i, &skpv);
             // C ref does an extra  pk_poly.reduce() which is not needed
 
-            e_i.getnoise_eta1::<K>(&mut prf, noise_seed, (K + i) as u8);
-            e_i.ntt();
-
             pk_poly.scale_mont();
-            (*pk_poly) += &e_i;
+            (*pk_poly) += e_i;
         }
 
         pkpv.reduce();
@@ -148,7 +152,7 @@ where
         let mut prf = Prf::default();
 
         let mut sp = KyberPolyVec::<K>::default();
-        sp.getnoise_eta1(&mut prf, coins, 0);
+        sp.getnoise_eta1(coins, 0);
         sp.ntt_and_reduce();
 
         let mut b = KyberPolyVec::<K>::default();
@@ -160,7 +164,7 @@ where
         b.inv_ntt_tomont();
 
         let mut ep = KyberPolyVec::<K>::default();
-        ep.getnoise_eta2(&mut prf, coins, K as u8);
+        ep.getnoise_eta2(coins, K as u8);
         b += &ep;
         b.reduce();
         ct.compress_polyvec(&b);
@@ -239,6 +243,7 @@ mod tests {
     use crate::utils::flatten::FlattenSlice;
 
     use super::*;
+    use crate::keccak::fips202::Sha3_256;
 
     fn test_keypair_vs_ref<const K: usize>() {
         let mut rng = rand::thread_rng();
@@ -272,6 +277,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn hash_h() {
+        let mut rng = rand::thread_rng();
+        let mut entropy = [0u8; KYBER_SYMBYTES];
+        rng.fill(entropy.as_mut());
+        let mut sk = CPASecretKey::<3>::default();
+        let mut pk = PublicKey::<3>::default();
+        keypair(&entropy, &mut sk, &mut pk);
+
+        let mut serialized = pk.bytes.as_slice().flatten_slice().to_vec();
+        serialized.extend_from_slice(&pk.seed);
+        let mut expected = [0u8; KYBER_SYMBYTES];
+        let mut h_pk = [0u8; KYBER_SYMBYTES];
+        Sha3_256::default().digest(&serialized, &mut expected);
+        pk.hash_h(&mut h_pk);
+        assert_eq!(h_pk, expected);
+    }
+
     #[test]
     #[cfg(not(miri))]
     fn keypair_vs_ref_2() {
@@ -67,8 +67,13 @@ mod polyvec;
 #[cfg(feature = "pub_internals")]
 pub mod polyvec;
 
+pub mod aes;
+pub mod composite;
 pub mod dilithium;
+pub mod drbg;
+pub mod ed25519;
 pub mod keccak;
 pub mod kyber;
+pub mod sha2;
 
 pub use poly::{Polynomial, SizedPolynomial};
@@ -1,14 +1,33 @@
 use crate::{
+    aes::Aes256Ctr,
+    dilithium::{Prf, CRH_BYTES, PRF_BLOCK_BYTES, SEED_BYTES},
     field::{
+        self,
         dilithium::{fqmul, DilithiumFq, DILITHIUM_Q, MONT},
         Field,
     },
-    keccak::{fips202::Shake128Params, KeccakParams},
+    keccak::{
+        fips202::{CrystalsPrf, Shake128Params, SpongeOps},
+        KeccakParams,
+    },
     poly::{Poly, Polynomial, SizedPolynomial},
+    utils::{
+        flatten::FlattenSliceMut,
+        split::{ArraySplitter, Splitter},
+    },
 };
 
 pub(crate) const DILITHIUM_N: usize = 256;
 
+/// Number of dropped bits from `t`
+pub const D: usize = field::dilithium::D as usize;
+
+/// Bits per coefficient of a packed `t1` polynomial
+pub const T1_BITS: usize = 23 - D;
+
+/// Bits per coefficient of a packed `t0` polynomial
+pub const T0_BITS: usize = D;
+
 const ROOT_OF_UNITY: i32 = 1753; // 2Nth (512-th) root of 1 mod Q
 
 pub type DilithiumPoly = Poly<DilithiumFq, DILITHIUM_N>;
@@ -116,9 +135,384 @@ impl DilithiumPoly {
     }
 }
 
+/// Packs 8 coefficients of `D` bits each into `D` bytes (little-endian bit order)
+#[inline(always)]
+fn pack_8<const D: usize>(t: [u32; 8], r: &mut [u8; D]) {
+    let mut acc = 0u64;
+    let mut bits = 0;
+    let mut idx = 0;
+    for x in t {
+        debug_assert!(x >> D == 0);
+        acc |= (x as u64) << bits;
+        bits += D;
+        while bits >= 8 {
+            r[idx] = acc as u8;
+            acc >>= 8;
+            bits -= 8;
+            idx += 1;
+        }
+    }
+    debug_assert_eq!(idx, D);
+}
+
+/// Unpacks `D` bytes into 8 coefficients of `D` bits each (little-endian bit order)
+#[inline(always)]
+fn unpack_8<const D: usize>(a: &[u8; D]) -> [u32; 8] {
+    let mask = (1u64 << D) - 1;
+    let mut t = [0u32; 8];
+    let mut acc = 0u64;
+    let mut bits = 0;
+    let mut idx = 0;
+    for x in t.iter_mut() {
+        while bits < D {
+            acc |= (a[idx] as u64) << bits;
+            bits += 8;
+            idx += 1;
+        }
+        *x = (acc & mask) as u32;
+        acc >>= D;
+        bits -= D;
+    }
+    t
+}
+
+/// Bit-packing of polynomials
+///
+/// Packed polynomials are represented as `[[u8; B]; 32]` where `B` is the number of bits per
+/// coefficient, i.e., each `[u8; B]` chunk holds exactly 8 coefficients.
+impl DilithiumPoly {
+    #[inline(always)]
+    fn pack_bits<const B: usize>(&self, r: &mut [[u8; B]; 32], f: impl Fn(i32) -> u32) {
+        for (coeffs, bytes) in self.0.as_array_chunks::<8>().zip(r.iter_mut()) {
+            pack_8(coeffs.map(|c| f(c.0)), bytes);
+        }
+    }
+
+    #[inline(always)]
+    fn unpack_bits<const B: usize>(&mut self, a: &[[u8; B]; 32], f: impl Fn(u32) -> i32) {
+        for (coeffs, bytes) in self.0.as_array_chunks_mut::<8>().zip(a.iter()) {
+            for (c, t) in coeffs.iter_mut().zip(unpack_8(bytes)) {
+                c.0 = f(t);
+            }
+        }
+    }
+
+    /// Bit-pack polynomial `t1` with coefficients in `[0, 1023]`
+    #[inline]
+    pub fn pack_t1(&self, r: &mut [[u8; T1_BITS]; 32]) {
+        self.pack_bits(r, |c| c as u32);
+    }
+
+    /// Unpack polynomial `t1` with 10-bit coefficients
+    #[inline]
+    pub fn unpack_t1(&mut self, a: &[[u8; T1_BITS]; 32]) {
+        self.unpack_bits(a, |t| t as i32);
+    }
+
+    /// Bit-pack polynomial `t0` with coefficients in `]-2^{D-1}, 2^{D-1}]`
+    #[inline]
+    pub fn pack_t0(&self, r: &mut [[u8; T0_BITS]; 32]) {
+        const HALF: i32 = 1 << (D - 1);
+        self.pack_bits(r, |c| (HALF - c) as u32);
+    }
+
+    /// Unpack polynomial `t0` with coefficients in `]-2^{D-1}, 2^{D-1}]`
+    #[inline]
+    pub fn unpack_t0(&mut self, a: &[[u8; T0_BITS]; 32]) {
+        const HALF: i32 = 1 << (D - 1);
+        self.unpack_bits(a, |t| HALF - t as i32);
+    }
+
+    /// Bit-pack polynomial with coefficients in `[-ETA, ETA]`
+    /// `B` is the number of bits per coefficient: 3 for `ETA = 2` and 4 for `ETA = 4`
+    #[inline]
+    pub fn pack_eta<const B: usize>(&self, r: &mut [[u8; B]; 32]) {
+        debug_assert!(B == 3 || B == 4);
+        let eta = 1 << (B - 2);
+        self.pack_bits(r, |c| (eta - c) as u32);
+    }
+
+    /// Unpack polynomial with coefficients in `[-ETA, ETA]`
+    /// `B` is the number of bits per coefficient: 3 for `ETA = 2` and 4 for `ETA = 4`
+    #[inline]
+    pub fn unpack_eta<const B: usize>(&mut self, a: &[[u8; B]; 32]) {
+        debug_assert!(B == 3 || B == 4);
+        let eta = 1 << (B - 2);
+        self.unpack_bits(a, |t| eta - t as i32);
+    }
+
+    /// Bit-pack polynomial `z` with coefficients in `[-(GAMMA1 - 1), GAMMA1]`
+    /// `B` is the number of bits per coefficient: 18 for `GAMMA1 = 2^17` and 20 for `GAMMA1 = 2^19`
+    #[inline]
+    pub fn pack_z<const B: usize>(&self, r: &mut [[u8; B]; 32]) {
+        debug_assert!(B == 18 || B == 20);
+        let gamma1 = 1 << (B - 1);
+        self.pack_bits(r, |c| (gamma1 - c) as u32);
+    }
+
+    /// Unpack polynomial `z` with coefficients in `[-(GAMMA1 - 1), GAMMA1]`
+    /// `B` is the number of bits per coefficient: 18 for `GAMMA1 = 2^17` and 20 for `GAMMA1 = 2^19`
+    #[inline]
+    pub fn unpack_z<const B: usize>(&mut self, a: &[[u8; B]; 32]) {
+        debug_assert!(B == 18 || B == 20);
+        let gamma1 = 1 << (B - 1);
+        self.unpack_bits(a, |t| gamma1 - t as i32);
+    }
+
+    /// Bit-pack polynomial `w1` with coefficients in `[0, (Q-1)/(2*GAMMA2) - 1]`
+    /// `B` is the number of bits per coefficient: 6 for `GAMMA2 = (Q-1)/88` and 4 for `GAMMA2 = (Q-1)/32`
+    #[inline]
+    pub fn pack_w1<const B: usize>(&self, r: &mut [[u8; B]; 32]) {
+        debug_assert!(B == 4 || B == 6);
+        self.pack_bits(r, |c| c as u32);
+    }
+
+    /// Write the positions of the 1 coefficients of hint polynomial into the front of `idx`
+    /// Returns the number of written positions. Panics if `idx` is too short.
+    #[inline]
+    pub fn pack_hint(&self, idx: &mut [u8]) -> usize {
+        let mut k = 0;
+        for (j, c) in self.0.iter().enumerate() {
+            if c.0 != 0 {
+                idx[k] = j as u8;
+                k += 1;
+            }
+        }
+        k
+    }
+
+    /// Decode hint polynomial with 1 coefficients at positions `idx`
+    /// Returns `false` if the positions are not strictly increasing.
+    #[inline]
+    pub fn unpack_hint(&mut self, idx: &[u8]) -> bool {
+        *self = Self::default();
+        for (j, &i) in idx.iter().enumerate() {
+            // Coefficients are ordered for strong unforgeability
+            if j > 0 && i <= idx[j - 1] {
+                return false;
+            }
+            self[i as usize].0 = 1;
+        }
+        true
+    }
+}
+
+impl DilithiumPoly {
+    /// Reduce all coefficients to representatives in `[-6283009, 6283007]`
+    #[inline]
+    pub fn reduce32(&mut self) {
+        for c in self.0.iter_mut() {
+            *c = c.reduce32();
+        }
+    }
+
+    /// Add Q to all negative coefficients
+    #[inline]
+    pub fn caddq(&mut self) {
+        for c in self.0.iter_mut() {
+            *c = c.caddq();
+        }
+    }
+
+    /// Multiply all coefficients by `2^D` without modular reduction
+    #[inline]
+    pub fn shiftl(&mut self) {
+        for c in self.0.iter_mut() {
+            c.0 <<= D;
+        }
+    }
+
+    /// Returns `true` if the infinity norm of the polynomial is not strictly smaller than `bound`
+    /// Assumes coefficients are reduced by `reduce32`.
+    ///
+    /// Branch-free over the coefficients: only the verdict for the whole polynomial is revealed, neither
+    /// which coefficient violates the bound nor the sign of its centralized representative.
+    pub fn chknorm(&self, bound: i32) -> bool {
+        if bound > (DILITHIUM_Q - 1) / 8 {
+            return true;
+        }
+        let violation = self.0.iter().fold(0, |acc, c| {
+            // absolute value
+            let t = c.0 >> 31;
+            let t = c.0 - (t & (2 * c.0));
+            // negative iff t >= bound
+            acc | (bound - 1 - t)
+        });
+        violation < 0
+    }
+}
+
+/// Rounding of polynomials with coefficients in standard representation
+impl DilithiumPoly {
+    /// Split `self` into high bits (written back to `self`) and low bits `a0` using `Power2Round`
+    pub fn power2round(&mut self, a0: &mut Self) {
+        for (a, a0) in self.0.iter_mut().zip(a0.0.iter_mut()) {
+            (*a, *a0) = a.power2round();
+        }
+    }
+
+    /// Split `self` into high bits (written back to `self`) and low bits `a0` using `Decompose`
+    pub fn decompose<const GAMMA2: i32>(&mut self, a0: &mut Self) {
+        for (a, a0) in self.0.iter_mut().zip(a0.0.iter_mut()) {
+            (*a, *a0) = a.decompose::<GAMMA2>();
+        }
+    }
+
+    /// Compute hint polynomial from low bits `a0` and high bits `a1`
+    /// Returns the number of 1 coefficients
+    pub fn make_hint<const GAMMA2: i32>(&mut self, a0: &Self, a1: &Self) -> usize {
+        let mut s = 0;
+        for ((h, a0), a1) in self.0.iter_mut().zip(a0.0.iter()).zip(a1.0.iter()) {
+            *h = a0.make_hint::<GAMMA2>(*a1);
+            s += h.0 as usize;
+        }
+        s
+    }
+
+    /// Replace `self` with its high bits corrected according to the hint polynomial `h`
+    pub fn use_hint<const GAMMA2: i32>(&mut self, h: &Self) {
+        for (a, h) in self.0.iter_mut().zip(h.0.iter()) {
+            *a = a.use_hint::<GAMMA2>(*h);
+        }
+    }
+}
+
+/// AES-256 key of the AES variants derived from a `CRH_BYTES` seed: its first 32 bytes, as in the reference
+/// implementation
+#[inline(always)]
+fn aes_key(seed: &[u8; CRH_BYTES]) -> &[u8; SEED_BYTES] {
+    let (key, _): (_, &[u8; CRH_BYTES - SEED_BYTES]) = seed.dissect_ref();
+    key
+}
+
+/// Sampling of short, masking, and challenge polynomials
+impl DilithiumPoly {
+    /// Rejection sampling of coefficients in `[-ETA, ETA]` from the nibbles of `buf`, starting from coefficient `ctr`
+    /// Returns the number of sampled coefficients
+    fn rej_eta<const ETA: usize>(&mut self, mut ctr: usize, buf: &[u8; PRF_BLOCK_BYTES]) -> usize {
+        debug_assert!(ETA == 2 || ETA == 4);
+
+        for &b in buf.iter() {
+            for t in [b & 0x0F, b >> 4] {
+                if ctr >= Self::N {
+                    return ctr;
+                }
+                let t = t as i32;
+                if ETA == 2 && t < 15 {
+                    // t mod 5
+                    self[ctr].0 = 2 - (t - ((205 * t) >> 10) * 5);
+                    ctr += 1;
+                } else if ETA == 4 && t < 9 {
+                    self[ctr].0 = 4 - t;
+                    ctr += 1;
+                }
+            }
+        }
+        ctr
+    }
+
+    /// Rejection sampling of all coefficients in `[-ETA, ETA]` from the byte stream produced by `squeeze`
+    #[inline(always)]
+    fn sample_eta<const ETA: usize>(&mut self, mut squeeze: impl FnMut(&mut [u8])) {
+        let mut buf = [0u8; PRF_BLOCK_BYTES];
+        let mut ctr = 0;
+        while ctr < Self::N {
+            squeeze(&mut buf);
+            ctr = self.rej_eta::<ETA>(ctr, &buf);
+        }
+    }
+
+    /// Sample polynomial with uniformly random coefficients in `[-ETA, ETA]` by rejection sampling on the
+    /// output stream of SHAKE256(seed || nonce)
+    pub fn uniform_eta<const ETA: usize>(
+        &mut self,
+        prf: &mut Prf,
+        seed: &[u8; CRH_BYTES],
+        nonce: u16,
+    ) {
+        prf.absorb_prf_u16(seed, nonce);
+        self.sample_eta::<ETA>(|buf| prf.squeeze(buf));
+    }
+
+    /// Sample polynomial with uniformly random coefficients in `[-ETA, ETA]` by rejection sampling on the
+    /// output stream of AES-256-CTR keyed with the first 32 bytes of `seed`, for the AES variants
+    pub fn uniform_eta_aes<const ETA: usize>(&mut self, seed: &[u8; CRH_BYTES], nonce: u16) {
+        let mut aes = Aes256Ctr::new(aes_key(seed), nonce as u64);
+        self.sample_eta::<ETA>(|buf| aes.squeeze(buf));
+    }
+
+    /// Sample polynomial with uniformly random coefficients in `[-(GAMMA1 - 1), GAMMA1]` by unpacking the
+    /// output stream of SHAKE256(seed || nonce)
+    /// `Z_BITS` is the number of bits per coefficient: 18 for `GAMMA1 = 2^17` and 20 for `GAMMA1 = 2^19`
+    pub fn uniform_gamma1<const Z_BITS: usize>(
+        &mut self,
+        prf: &mut Prf,
+        seed: &[u8; CRH_BYTES],
+        nonce: u16,
+    ) {
+        let mut buf = [[0u8; Z_BITS]; 32];
+        prf.absorb_prf_u16(seed, nonce);
+        prf.squeeze(buf.flatten_slice_mut());
+        self.unpack_z(&buf);
+    }
+
+    /// Sample polynomial with uniformly random coefficients in `[-(GAMMA1 - 1), GAMMA1]` by unpacking the
+    /// output stream of AES-256-CTR keyed with the first 32 bytes of `seed`, for the AES variants
+    pub fn uniform_gamma1_aes<const Z_BITS: usize>(&mut self, seed: &[u8; CRH_BYTES], nonce: u16) {
+        let mut buf = [[0u8; Z_BITS]; 32];
+        Aes256Ctr::new(aes_key(seed), nonce as u64).squeeze(buf.flatten_slice_mut());
+        self.unpack_z(&buf);
+    }
+
+    /// Sample polynomial with uniformly random coefficients in `[0, Q)` by rejection sampling on the output
+    /// stream of AES-256-CTR keyed with `seed`; the matrix `A` of the AES variants
+    pub fn uniform_aes(&mut self, seed: &[u8; SEED_BYTES], nonce: u16) {
+        let mut aes = Aes256Ctr::new(seed, nonce as u64);
+        let mut buf = [0u8; Shake128Params::RATE_BYTES];
+        let mut ctr = 0;
+        while ctr < Self::N {
+            aes.squeeze(&mut buf);
+            ctr = self.rej_uniform(ctr, &buf);
+        }
+    }
+
+    /// SampleInBall: generate the challenge polynomial with exactly `TAU` nonzero coefficients in `{-1, 1}`
+    /// from the output stream of SHAKE256(seed)
+    pub fn challenge<const TAU: usize>(&mut self, prf: &mut Prf, seed: &[u8; SEED_BYTES]) {
+        let mut buf = [0u8; PRF_BLOCK_BYTES];
+        prf.absorb(seed);
+        prf.squeeze(&mut buf);
+
+        let mut signs = 0u64;
+        for (i, &b) in buf[..8].iter().enumerate() {
+            signs |= (b as u64) << (8 * i);
+        }
+        let mut pos = 8;
+
+        *self = Self::default();
+        for i in Self::N - TAU..Self::N {
+            let b = loop {
+                if pos >= PRF_BLOCK_BYTES {
+                    prf.squeeze(&mut buf);
+                    pos = 0;
+                }
+                let b = buf[pos] as usize;
+                pos += 1;
+                if b <= i {
+                    break b;
+                }
+            };
+            self[i] = self[b];
+            self[b].0 = 1 - 2 * (signs & 1) as i32;
+            signs >>= 1;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::flatten::{FlattenSlice, FlattenSliceMut};
     use crystals_cref::dilithium as cref;
 
     #[test]
@@ -218,4 +612,242 @@ mod tests {
             );
         }
     }
+
+    fn random_poly(lo: i32, hi: i32) -> DilithiumPoly {
+        let mut rng = rand::thread_rng();
+        let mut poly = DilithiumPoly::default();
+        for c in poly.as_mut() {
+            c.0 = rng.gen_range(lo..=hi);
+        }
+        poly
+    }
+
+    const PACK_TESTS: usize = if cfg!(miri) { 10 } else { 1_000 };
+
+    #[test]
+    fn pack_t1_roundtrip() {
+        for _ in 0..PACK_TESTS {
+            let poly = random_poly(0, (1 << T1_BITS) - 1);
+            let mut packed = [[0u8; T1_BITS]; 32];
+            poly.pack_t1(&mut packed);
+            let mut unpacked = DilithiumPoly::default();
+            unpacked.unpack_t1(&packed);
+            assert_eq!(poly, unpacked);
+        }
+    }
+
+    #[test]
+    fn pack_t0_roundtrip() {
+        for _ in 0..PACK_TESTS {
+            let poly = random_poly(-(1 << (D - 1)) + 1, 1 << (D - 1));
+            let mut packed = [[0u8; T0_BITS]; 32];
+            poly.pack_t0(&mut packed);
+            let mut unpacked = DilithiumPoly::default();
+            unpacked.unpack_t0(&packed);
+            assert_eq!(poly, unpacked);
+        }
+    }
+
+    fn pack_eta_roundtrip<const B: usize>() {
+        let eta = 1 << (B - 2);
+        for _ in 0..PACK_TESTS {
+            let poly = random_poly(-eta, eta);
+            let mut packed = [[0u8; B]; 32];
+            poly.pack_eta(&mut packed);
+            let mut unpacked = DilithiumPoly::default();
+            unpacked.unpack_eta(&packed);
+            assert_eq!(poly, unpacked);
+        }
+    }
+
+    fn pack_z_roundtrip<const B: usize>() {
+        let gamma1 = 1 << (B - 1);
+        for _ in 0..PACK_TESTS {
+            let poly = random_poly(-gamma1 + 1, gamma1);
+            let mut packed = [[0u8; B]; 32];
+            poly.pack_z(&mut packed);
+            let mut unpacked = DilithiumPoly::default();
+            unpacked.unpack_z(&packed);
+            assert_eq!(poly, unpacked);
+        }
+    }
+
+    #[test]
+    fn pack_eta_z_roundtrip() {
+        pack_eta_roundtrip::<3>();
+        pack_eta_roundtrip::<4>();
+        pack_z_roundtrip::<18>();
+        pack_z_roundtrip::<20>();
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn pack_t1_t0_vs_ref() {
+        for _ in 0..PACK_TESTS {
+            let mut poly = random_poly(0, (1 << T1_BITS) - 1);
+            let mut packed = [[0u8; T1_BITS]; 32];
+            let mut packed_ref = [0u8; T1_BITS * 32];
+            poly.pack_t1(&mut packed);
+            cref::polyt1_pack(&mut packed_ref, &poly.into_array());
+            assert_eq!(packed.flatten_slice(), packed_ref);
+
+            let mut unpacked_ref = [0i32; DilithiumPoly::N];
+            cref::polyt1_unpack(&mut unpacked_ref, &packed_ref);
+            poly.unpack_t1(&packed);
+            assert_eq!(poly.into_array(), unpacked_ref);
+
+            let mut poly = random_poly(-(1 << (D - 1)) + 1, 1 << (D - 1));
+            let mut packed = [[0u8; T0_BITS]; 32];
+            let mut packed_ref = [0u8; T0_BITS * 32];
+            poly.pack_t0(&mut packed);
+            cref::polyt0_pack(&mut packed_ref, &poly.into_array());
+            assert_eq!(packed.flatten_slice(), packed_ref);
+
+            cref::polyt0_unpack(&mut unpacked_ref, &packed_ref);
+            poly.unpack_t0(&packed);
+            assert_eq!(poly.into_array(), unpacked_ref);
+        }
+    }
+
+    /// `K` selects the reference parameter set matching the bit-widths
+    fn pack_vs_ref<
+        const K: usize,
+        const ETA_BITS: usize,
+        const Z_BITS: usize,
+        const W1_BITS: usize,
+    >() {
+        let eta = 1 << (ETA_BITS - 2);
+        let gamma1 = 1 << (Z_BITS - 1);
+        let mut unpacked_ref = [0i32; DilithiumPoly::N];
+        for _ in 0..PACK_TESTS {
+            let mut poly = random_poly(-eta, eta);
+            let mut packed = [[0u8; ETA_BITS]; 32];
+            let mut packed_ref = [[0u8; ETA_BITS]; 32];
+            poly.pack_eta(&mut packed);
+            cref::polyeta_pack::<K>(packed_ref.flatten_slice_mut(), &poly.into_array());
+            assert_eq!(packed, packed_ref);
+            cref::polyeta_unpack::<K>(&mut unpacked_ref, packed_ref.flatten_slice());
+            poly.unpack_eta(&packed);
+            assert_eq!(poly.into_array(), unpacked_ref);
+
+            let mut poly = random_poly(-gamma1 + 1, gamma1);
+            let mut packed = [[0u8; Z_BITS]; 32];
+            let mut packed_ref = [[0u8; Z_BITS]; 32];
+            poly.pack_z(&mut packed);
+            cref::polyz_pack::<K>(packed_ref.flatten_slice_mut(), &poly.into_array());
+            assert_eq!(packed, packed_ref);
+            cref::polyz_unpack::<K>(&mut unpacked_ref, packed_ref.flatten_slice());
+            poly.unpack_z(&packed);
+            assert_eq!(poly.into_array(), unpacked_ref);
+
+            let poly = random_poly(0, (1 << W1_BITS) - 1);
+            let mut packed = [[0u8; W1_BITS]; 32];
+            let mut packed_ref = [[0u8; W1_BITS]; 32];
+            poly.pack_w1(&mut packed);
+            cref::polyw1_pack::<K>(packed_ref.flatten_slice_mut(), &poly.into_array());
+            assert_eq!(packed, packed_ref);
+        }
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn pack_eta_z_w1_vs_ref() {
+        pack_vs_ref::<4, 3, 18, 6>();
+        pack_vs_ref::<6, 4, 20, 4>();
+        pack_vs_ref::<8, 3, 20, 4>();
+    }
+
+    #[test]
+    fn sample_bounds() {
+        let mut rng = rand::thread_rng();
+        let mut prf = Prf::default();
+        let mut seed = [0u8; CRH_BYTES];
+        let mut poly = DilithiumPoly::default();
+
+        for nonce in 0..if cfg!(miri) { 2 } else { 200 } {
+            rng.fill(&mut seed[..]);
+
+            poly.uniform_eta::<2>(&mut prf, &seed, nonce);
+            assert!(poly.as_ref().iter().all(|c| (-2..=2).contains(&c.0)));
+            poly.uniform_eta::<4>(&mut prf, &seed, nonce);
+            assert!(poly.as_ref().iter().all(|c| (-4..=4).contains(&c.0)));
+
+            poly.uniform_gamma1::<18>(&mut prf, &seed, nonce);
+            assert!(poly
+                .as_ref()
+                .iter()
+                .all(|c| (-(1 << 17) + 1..=1 << 17).contains(&c.0)));
+            poly.uniform_gamma1::<20>(&mut prf, &seed, nonce);
+            assert!(poly
+                .as_ref()
+                .iter()
+                .all(|c| (-(1 << 19) + 1..=1 << 19).contains(&c.0)));
+
+            let c_seed: [u8; SEED_BYTES] = rng.gen();
+            poly.challenge::<39>(&mut prf, &c_seed);
+            assert!(poly.as_ref().iter().all(|c| (-1..=1).contains(&c.0)));
+            assert_eq!(poly.as_ref().iter().filter(|c| c.0 != 0).count(), 39);
+        }
+    }
+
+    #[test]
+    fn chknorm_bounds() {
+        let mut rng = rand::thread_rng();
+        for bound in [1, 2, (1 << 17) - 78, (1 << 19) - 196, (DILITHIUM_Q - 1) / 8] {
+            for _ in 0..100 {
+                let mut poly = random_poly(-bound + 1, bound - 1);
+                assert!(!poly.chknorm(bound));
+
+                let i = rng.gen_range(0..DilithiumPoly::N);
+                for (c, violates) in [
+                    (bound - 1, false),
+                    (-bound + 1, false),
+                    (bound, true),
+                    (-bound, true),
+                    (bound + 1, true),
+                ] {
+                    poly[i].0 = c;
+                    assert_eq!(poly.chknorm(bound), violates, "bound={bound} c={c}");
+                }
+            }
+        }
+
+        // bounds beyond (Q - 1) / 8 are always violated
+        assert!(DilithiumPoly::default().chknorm((DILITHIUM_Q - 1) / 8 + 1));
+    }
+
+    /// `K` selects the reference parameter set matching `ETA`, `Z_BITS`, and `TAU`
+    fn sample_vs_ref<const K: usize, const ETA: usize, const Z_BITS: usize, const TAU: usize>() {
+        let mut rng = rand::thread_rng();
+        let mut prf = Prf::default();
+        let mut seed = [0u8; CRH_BYTES];
+        let mut poly = DilithiumPoly::default();
+        let mut poly_ref = [0i32; DilithiumPoly::N];
+
+        for _ in 0..PACK_TESTS {
+            rng.fill(&mut seed[..]);
+            let nonce: u16 = rng.gen();
+
+            poly.uniform_eta::<ETA>(&mut prf, &seed, nonce);
+            cref::poly_uniform_eta::<K>(&mut poly_ref, &seed, nonce);
+            assert_eq!(poly.into_array(), poly_ref);
+
+            poly.uniform_gamma1::<Z_BITS>(&mut prf, &seed, nonce);
+            cref::poly_uniform_gamma1::<K>(&mut poly_ref, &seed, nonce);
+            assert_eq!(poly.into_array(), poly_ref);
+
+            let c_seed: [u8; SEED_BYTES] = rng.gen();
+            poly.challenge::<TAU>(&mut prf, &c_seed);
+            cref::poly_challenge::<K>(&mut poly_ref, &c_seed);
+            assert_eq!(poly.into_array(), poly_ref);
+        }
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn sample_eta_gamma1_challenge_vs_ref() {
+        sample_vs_ref::<4, 2, 18, 39>();
+        sample_vs_ref::<6, 4, 20, 49>();
+        sample_vs_ref::<8, 2, 20, 60>();
+    }
 }
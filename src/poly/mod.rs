@@ -2,13 +2,15 @@ pub mod dilithium;
 pub mod kyber;
 
 use crate::field::Field;
-use crate::keccak::fips202::{CrystalsXof, Shake128, Shake128Params, SpongeOps};
+use crate::keccak::fips202::{CrystalsXof, Shake128, Shake128Params, Shake128x4, SpongeOps};
 use crate::keccak::KeccakParams;
 use crate::lib::fmt::Debug;
 use crate::lib::ops::{AddAssign, Index, IndexMut, SubAssign};
 use crate::lib::slice::{Iter, IterMut};
 use crate::polyvec::PolyVec;
 
+use zeroize::Zeroize;
+
 // TODO use Parameters
 pub const UNIFORM_SEED_BYTES: usize = 32;
 
@@ -191,9 +193,55 @@ pub trait SizedPolynomial<const N: usize>:
         }
         debug_assert_eq!(ctr, Self::NUM_SCALARS);
     }
+
+    /// Four-way `uniform`, sampling `polys[k]` with the nonces `nonces[k]`
+    #[inline]
+    fn uniform_x4(
+        mut polys: [&mut Self; 4],
+        seed: &[u8; UNIFORM_SEED_BYTES],
+        nonces: [(u8, u8); 4],
+    ) {
+        let mut shake128 = Shake128x4::default();
+        shake128.absorb_xof_with_nonces(seed, nonces);
+        let mut xof_out = [[0u8; Shake128Params::RATE_BYTES]; 4];
+
+        let mut ctr = [0; 4];
+        while ctr.iter().any(|&c| c < Self::NUM_SCALARS) {
+            shake128.squeeze(&mut xof_out);
+            for ((poly, ctr), xof_out) in polys.iter_mut().zip(ctr.iter_mut()).zip(xof_out.iter()) {
+                if *ctr < Self::NUM_SCALARS {
+                    *ctr = poly.rej_uniform(*ctr, xof_out);
+                }
+            }
+        }
+    }
+}
+
+/// Sample each polynomial of `polys` with `uniform` and the nonces it is paired with, four at a time
+pub(crate) fn uniform_many<'a, P, const N: usize>(
+    seed: &[u8; UNIFORM_SEED_BYTES],
+    polys: impl Iterator<Item = (&'a mut P, (u8, u8))>,
+) where
+    P: SizedPolynomial<N> + 'a,
+{
+    let mut polys = polys.fuse();
+    loop {
+        match (polys.next(), polys.next(), polys.next(), polys.next()) {
+            (Some((p0, n0)), Some((p1, n1)), Some((p2, n2)), Some((p3, n3))) => {
+                P::uniform_x4([p0, p1, p2, p3], seed, [n0, n1, n2, n3]);
+            }
+            (p0, p1, p2, _) => {
+                for (poly, (i, j)) in [p0, p1, p2].into_iter().flatten() {
+                    poly.uniform(seed, i, j);
+                }
+                return;
+            }
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Zeroize)]
+#[zeroize(bound = "T: Zeroize")]
 pub struct Poly<T: Field, const N: usize>([T; N]);
 
 impl<T: Field, const N: usize> Default for Poly<T, N> {
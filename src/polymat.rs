@@ -1,11 +1,15 @@
 use crate::lib::ops::{Index, IndexMut};
 use crate::{
+    poly::dilithium::DilithiumPoly,
     poly::kyber::KyberPoly,
-    poly::{SizedPolynomial, UNIFORM_SEED_BYTES},
+    poly::{uniform_many, SizedPolynomial, UNIFORM_SEED_BYTES},
     polyvec::*,
 };
 
-#[derive(Debug)]
+use zeroize::Zeroize;
+
+#[derive(Debug, Zeroize)]
+#[zeroize(bound = "P: Zeroize")]
 pub struct PolyMat<P, const N: usize, const K: usize, const L: usize>([PolyVec<P, N, L>; K])
 where
     P: SizedPolynomial<N>;
@@ -68,15 +72,61 @@ impl<P, const N: usize, const K: usize, const L: usize> PolyMat<P, N, K, L>
 where
     P: SizedPolynomial<N>,
 {
-    //     #[inline]
-    //     pub fn mult_vec(&self, polyvec: &PolyVec<T, N, K>) -> PolyVec<T, N, K> {
-    //         let mut r = PolyVec::default();
+    /// Matrix-vector product `r = A * v` in NTT domain
+    /// The coefficients of `r` are not reduced.
+    #[inline]
+    pub fn mult_vec(&self, v: &PolyVec<P, N, L>, r: &mut PolyVec<P, N, K>) {
+        for (row, r) in self.as_ref().iter().zip(r.as_mut().iter_mut()) {
+            *r = P::default();
+            r.vector_mul_acc(row, v);
+        }
+    }
+
+    /// Transposed matrix-vector product `r = A^T * v` in NTT domain
+    /// The coefficients of `r` are not reduced.
+    #[inline]
+    pub fn mult_vec_transposed(&self, v: &PolyVec<P, N, K>, r: &mut PolyVec<P, N, L>) {
+        *r = PolyVec::default();
+        for (row, v) in self.as_ref().iter().zip(v.as_ref().iter()) {
+            for (a, r) in row.as_ref().iter().zip(r.as_mut().iter_mut()) {
+                a.pointwise_acc(v, r);
+            }
+        }
+    }
 
-    //         for i in 0..K {
-    //             self[i].basemul_acc(&polyvec, &mut r[i]);
-    //         }
-    //         r
-    //     }
+    /// Computes `r = A * v` where `A = gen_matrix::<TRANSPOSED>(seed)`, expanding one row of `A` at a time
+    /// The coefficients of `r` are not reduced.
+    #[inline]
+    pub fn expand_mult_vec<const TRANSPOSED: bool>(
+        seed: &[u8; UNIFORM_SEED_BYTES],
+        v: &PolyVec<P, N, L>,
+        r: &mut PolyVec<P, N, K>,
+    ) {
+        let mut row = PolyVec::<P, N, L>::default();
+        for (i, r) in r.as_mut().iter_mut().enumerate() {
+            row.uniform_xof::<TRANSPOSED>(seed, i as u8);
+            *r = P::default();
+            r.vector_mul_acc(&row, v);
+        }
+    }
+
+    /// Computes `r = A^T * v` where `A = gen_matrix::<TRANSPOSED>(seed)`, expanding one row of `A` at a time
+    /// The coefficients of `r` are not reduced.
+    #[inline]
+    pub fn expand_mult_vec_transposed<const TRANSPOSED: bool>(
+        seed: &[u8; UNIFORM_SEED_BYTES],
+        v: &PolyVec<P, N, K>,
+        r: &mut PolyVec<P, N, L>,
+    ) {
+        let mut row = PolyVec::<P, N, L>::default();
+        *r = PolyVec::default();
+        for (i, v) in v.as_ref().iter().enumerate() {
+            row.uniform_xof::<TRANSPOSED>(seed, i as u8);
+            for (a, r) in row.as_ref().iter().zip(r.as_mut().iter_mut()) {
+                a.pointwise_acc(v, r);
+            }
+        }
+    }
 
     /// Expand seed to A matrix (or A^T if TRANSPOSED is true)
     /// For Kyber:
@@ -102,14 +152,37 @@ where
 
     #[inline]
     pub fn gen_matrix_into<const TRANSPOSED: bool>(&mut self, seed: &[u8; UNIFORM_SEED_BYTES]) {
-        for (i, vec) in self.as_mut().iter_mut().enumerate() {
-            vec.uniform_xof::<TRANSPOSED>(seed, i as u8);
-        }
+        let polys = self.as_mut().iter_mut().enumerate().flat_map(|(i, vec)| {
+            vec.as_mut().iter_mut().enumerate().map(move |(j, poly)| {
+                let nonces = if TRANSPOSED {
+                    (i as u8, j as u8)
+                } else {
+                    (j as u8, i as u8)
+                };
+                (poly, nonces)
+            })
+        });
+        uniform_many(seed, polys);
     }
 }
 
 pub type KyberMatrix<const K: usize> = PolyMat<KyberPoly, { KyberPoly::N }, K, K>;
 
+pub type DilithiumMatrix<const K: usize, const L: usize> =
+    PolyMat<DilithiumPoly, { DilithiumPoly::N }, K, L>;
+
+impl<const K: usize, const L: usize> DilithiumMatrix<K, L> {
+    /// Expand seed to the matrix `A` of the Dilithium-AES variants
+    #[inline]
+    pub fn gen_a_aes(seed: &[u8; UNIFORM_SEED_BYTES]) -> Self {
+        let mut a = Self::default();
+        for (i, vec) in a.as_mut().iter_mut().enumerate() {
+            vec.uniform_aes(seed, i as u8);
+        }
+        a
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use rand::Rng;
@@ -159,4 +232,170 @@ mod tests {
             }
         }
     }
+
+    /// Four-way sampling of the matrix gives the same result as sampling each polynomial on its own
+    fn gen_matrix_x4_vs_single<P, const N: usize, const K: usize, const L: usize>()
+    where
+        P: SizedPolynomial<N> + PartialEq + core::fmt::Debug,
+    {
+        let mut rng = rand::thread_rng();
+        let mut seed = [0u8; 32];
+
+        for _ in 0..MULT_TESTS {
+            rng.fill(&mut seed);
+            let a = PolyMat::<P, N, K, L>::gen_matrix::<false>(&seed);
+            let at = PolyMat::<P, N, K, L>::gen_matrix::<true>(&seed);
+            for i in 0..K {
+                let mut row = PolyVec::<P, N, L>::default();
+                row.uniform_xof::<false>(&seed, i as u8);
+                assert_eq!(row.as_ref(), a[i].as_ref());
+                for j in 0..L {
+                    let mut poly = P::default();
+                    poly.uniform(&seed, j as u8, i as u8);
+                    assert_eq!(poly, a[i][j]);
+                    poly.uniform(&seed, i as u8, j as u8);
+                    assert_eq!(poly, at[i][j]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn gen_matrix_x4() {
+        gen_matrix_x4_vs_single::<KyberPoly, { KyberPoly::N }, 2, 2>();
+        gen_matrix_x4_vs_single::<KyberPoly, { KyberPoly::N }, 3, 3>();
+        gen_matrix_x4_vs_single::<KyberPoly, { KyberPoly::N }, 4, 4>();
+        gen_matrix_x4_vs_single::<DilithiumPoly, { DilithiumPoly::N }, 4, 4>();
+        gen_matrix_x4_vs_single::<DilithiumPoly, { DilithiumPoly::N }, 6, 5>();
+        gen_matrix_x4_vs_single::<DilithiumPoly, { DilithiumPoly::N }, 8, 7>();
+    }
+
+    const MULT_TESTS: usize = if cfg!(miri) { 1 } else { 20 };
+
+    fn dilithium_streaming_x<const K: usize, const L: usize>() {
+        let mut rng = rand::thread_rng();
+        let mut seed = [0u8; 32];
+
+        for _ in 0..MULT_TESTS {
+            rng.fill(&mut seed);
+            let v = DilithiumPolyVec::<L>::new_random(&mut rng);
+            let w = DilithiumPolyVec::<K>::new_random(&mut rng);
+
+            let a = DilithiumMatrix::<K, L>::gen_a(&seed);
+
+            let mut r = DilithiumPolyVec::<K>::default();
+            let mut r_streamed = DilithiumPolyVec::<K>::new_random(&mut rng);
+            a.mult_vec(&v, &mut r);
+            DilithiumMatrix::<K, L>::expand_mult_vec::<false>(&seed, &v, &mut r_streamed);
+            assert_eq!(r.as_ref(), r_streamed.as_ref());
+
+            let mut rt = DilithiumPolyVec::<L>::default();
+            let mut rt_streamed = DilithiumPolyVec::<L>::new_random(&mut rng);
+            a.mult_vec_transposed(&w, &mut rt);
+            DilithiumMatrix::<K, L>::expand_mult_vec_transposed::<false>(
+                &seed,
+                &w,
+                &mut rt_streamed,
+            );
+            assert_eq!(rt.as_ref(), rt_streamed.as_ref());
+
+            // A^T * w == (A^T) * w with explicitly transposed matrix
+            let mut at = DilithiumMatrix::<L, K>::default();
+            for i in 0..K {
+                for j in 0..L {
+                    at[j][i] = a[i][j];
+                }
+            }
+            at.mult_vec(&w, &mut rt_streamed);
+            assert_eq!(rt.as_ref(), rt_streamed.as_ref());
+        }
+    }
+
+    #[test]
+    fn dilithium_mult_vec_streaming() {
+        dilithium_streaming_x::<4, 4>();
+        dilithium_streaming_x::<6, 5>();
+        dilithium_streaming_x::<8, 7>();
+    }
+
+    fn kyber_streaming_x<const K: usize, const TRANSPOSED: bool>() {
+        let mut rng = rand::thread_rng();
+        let mut seed = [0u8; 32];
+
+        for _ in 0..MULT_TESTS {
+            rng.fill(&mut seed);
+            let v = KyberPolyVec::<K>::new_random(&mut rng);
+
+            let a = KyberMatrix::<K>::gen_matrix::<TRANSPOSED>(&seed);
+
+            let mut r = KyberPolyVec::<K>::default();
+            let mut r_streamed = KyberPolyVec::<K>::default();
+            a.mult_vec(&v, &mut r);
+            KyberMatrix::<K>::expand_mult_vec::<TRANSPOSED>(&seed, &v, &mut r_streamed);
+            assert_eq!(r.as_ref(), r_streamed.as_ref());
+
+            // same result as `basemul_acc` (up to reduction)
+            r.reduce();
+            for i in 0..K {
+                let mut t = KyberPoly::default();
+                a[i].basemul_acc(&v, &mut t);
+                assert_eq!(r[i], t);
+            }
+
+            // A^T * v with A = gen_matrix::<TRANSPOSED> is the product with gen_matrix::<!TRANSPOSED>
+            a.mult_vec_transposed(&v, &mut r);
+            KyberMatrix::<K>::expand_mult_vec_transposed::<TRANSPOSED>(&seed, &v, &mut r_streamed);
+            assert_eq!(r.as_ref(), r_streamed.as_ref());
+            let at = if TRANSPOSED {
+                KyberMatrix::<K>::gen_a(&seed)
+            } else {
+                KyberMatrix::<K>::gen_at(&seed)
+            };
+            at.mult_vec(&v, &mut r_streamed);
+            assert_eq!(r.as_ref(), r_streamed.as_ref());
+        }
+    }
+
+    #[test]
+    fn kyber_mult_vec_streaming() {
+        kyber_streaming_x::<2, false>();
+        kyber_streaming_x::<3, true>();
+        kyber_streaming_x::<4, false>();
+    }
+
+    fn dilithium_mult_vec_x<const K: usize, const L: usize>() {
+        use crystals_cref::dilithium as cref;
+
+        let mut rng = rand::thread_rng();
+        let mut seed = [0u8; 32];
+        let mut a_ref = [[[0i32; DilithiumPoly::N]; L]; K];
+        let mut r_ref = [[0i32; DilithiumPoly::N]; K];
+
+        for _ in 0..MULT_TESTS {
+            rng.fill(&mut seed);
+            let v = DilithiumPolyVec::<L>::new_random(&mut rng);
+            let v_ref = v.as_ref().map(|p| p.into_array());
+
+            let a = DilithiumMatrix::<K, L>::gen_a(&seed);
+            cref::polyvec_matrix_expand::<K, L>(&mut a_ref, &seed);
+            for i in 0..K {
+                for j in 0..L {
+                    assert_eq!(a[i][j].into_array(), a_ref[i][j], "i={} j={}", i, j);
+                }
+            }
+
+            let mut r = DilithiumPolyVec::<K>::default();
+            a.mult_vec(&v, &mut r);
+            cref::polyvec_matrix_pointwise_montgomery::<K, L>(&mut r_ref, &a_ref, &v_ref);
+            assert_eq!(r.as_ref().map(|p| p.into_array()), r_ref);
+        }
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn dilithium_mult_vec_vs_ref() {
+        dilithium_mult_vec_x::<4, 4>();
+        dilithium_mult_vec_x::<6, 5>();
+        dilithium_mult_vec_x::<8, 7>();
+    }
 }
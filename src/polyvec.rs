@@ -1,12 +1,14 @@
 use crate::{
-    keccak::fips202::{CrystalsPrf, CrystalsXof, SpongeOps},
-    kyber::{Prf, Xof, NOISE_SEED_BYTES, XOF_BLOCK_BYTES},
-    lib::ops::{AddAssign, Index, IndexMut},
-    poly::dilithium::DilithiumPoly,
+    dilithium::{Prf as DilithiumPrf, CRH_BYTES},
+    keccak::fips202::Shake256x4,
+    kyber::NOISE_SEED_BYTES,
+    lib::ops::{AddAssign, Index, IndexMut, SubAssign},
+    poly::dilithium::{DilithiumPoly, T0_BITS, T1_BITS},
     poly::kyber::{KyberPoly, KYBER_N, POLYBYTES},
-    poly::{Polynomial, SizedPolynomial, UNIFORM_SEED_BYTES},
+    poly::{uniform_many, Polynomial, SizedPolynomial, UNIFORM_SEED_BYTES},
 };
 use rand::{CryptoRng, RngCore};
+use zeroize::Zeroize;
 
 pub trait PolynomialVector: Default + Sized + Index<usize> + IndexMut<usize> {
     type Poly: Polynomial;
@@ -22,7 +24,8 @@ pub trait PolynomialVector: Default + Sized + Index<usize> + IndexMut<usize> {
     fn basemul_acc(&self, other: &Self, result: &mut <Self as PolynomialVector>::Poly);
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Zeroize)]
+#[zeroize(bound = "P: Zeroize")]
 pub struct PolyVec<P, const N: usize, const K: usize>([P; K])
 where
     P: Polynomial;
@@ -113,26 +116,15 @@ where
 
     #[inline]
     fn uniform_xof<const TRANSPOSED: bool>(&mut self, seed: &[u8; UNIFORM_SEED_BYTES], i: u8) {
-        let mut shake128 = Xof::default();
-        let mut xof_out = [0u8; XOF_BLOCK_BYTES];
-
-        for (j, poly) in self.as_mut().iter_mut().enumerate() {
-            let (i, j) = if TRANSPOSED {
+        let polys = self.as_mut().iter_mut().enumerate().map(|(j, poly)| {
+            let nonces = if TRANSPOSED {
                 (i, j as u8)
             } else {
                 (j as u8, i)
             };
-
-            shake128.absorb_xof_with_nonces(seed, i, j);
-
-            let mut ctr = 0;
-            while ctr < Self::Poly::NUM_SCALARS {
-                shake128.squeeze(&mut xof_out);
-                ctr = poly.rej_uniform(ctr, &xof_out);
-            }
-
-            debug_assert_eq!(ctr, Self::Poly::NUM_SCALARS);
-        }
+            (poly, nonces)
+        });
+        uniform_many(seed, polys);
     }
 
     fn basemul_acc(&self, other: &Self, result: &mut <Self as PolynomialVector>::Poly) {
@@ -181,6 +173,17 @@ where
     }
 }
 
+impl<P, const N: usize, const K: usize> SubAssign<&Self> for PolyVec<P, N, K>
+where
+    P: Polynomial,
+{
+    fn sub_assign(&mut self, rhs: &Self) {
+        for i in 0..K {
+            self[i] -= &rhs[i];
+        }
+    }
+}
+
 impl<const N: usize, const K: usize> PolyVec<KyberPoly, N, K> {
     #[inline(always)]
     pub fn new_deserialize(bytes: &[[u8; POLYBYTES]; K]) -> Self {
@@ -215,31 +218,41 @@ impl<const K: usize> KyberPolyVec<K> {
         pv
     }
 
+    /// Sample the i-th polynomial with `cbd` from `PRF(seed, nonce + i)`, four polynomials at a time
+    #[inline(always)]
+    fn getnoise_x4<const BUF_BYTES: usize>(
+        &mut self,
+        seed: &[u8; NOISE_SEED_BYTES],
+        nonce: u8,
+        cbd: impl Fn(&mut KyberPoly, &[u8; BUF_BYTES]),
+    ) {
+        let mut prf = Shake256x4::default();
+        let mut buf = [[0u8; BUF_BYTES]; 4];
+        for (k, polys) in self.as_mut().chunks_mut(4).enumerate() {
+            let nonce = nonce.wrapping_add(4 * k as u8);
+            prf.absorb_prf(seed, [0, 1, 2, 3].map(|i| nonce.wrapping_add(i)));
+            prf.squeeze(&mut buf);
+            for (poly, buf) in polys.iter_mut().zip(buf.iter()) {
+                cbd(poly, buf);
+            }
+        }
+        buf.zeroize();
+    }
+
     #[inline]
-    pub fn getnoise_eta1(&mut self, prf: &mut Prf, seed: &[u8; NOISE_SEED_BYTES], nonce: u8) {
+    pub fn getnoise_eta1(&mut self, seed: &[u8; NOISE_SEED_BYTES], nonce: u8) {
         if K == 2 {
             const ETA1: usize = 3;
-            let mut buf = [0u8; ETA1 * KYBER_N / 4];
-            for (i, poly) in self.as_mut().iter_mut().enumerate() {
-                prf.absorb_prf(seed, i as u8 + nonce);
-                prf.squeeze(&mut buf);
-                poly.cbd3(&buf);
-            }
+            self.getnoise_x4::<{ ETA1 * KYBER_N / 4 }>(seed, nonce, KyberPoly::cbd3);
         } else {
-            self.getnoise_eta2(prf, seed, nonce);
+            self.getnoise_eta2(seed, nonce);
         }
     }
 
     #[inline]
-    pub fn getnoise_eta2(&mut self, prf: &mut Prf, seed: &[u8; NOISE_SEED_BYTES], nonce: u8) {
+    pub fn getnoise_eta2(&mut self, seed: &[u8; NOISE_SEED_BYTES], nonce: u8) {
         const ETA2: usize = 2;
-        prf.absorb_prf(seed, nonce);
-        let mut buf = [0u8; ETA2 * KYBER_N / 4];
-        for (i, poly) in self.as_mut().iter_mut().enumerate() {
-            prf.absorb_prf(seed, i as u8 + nonce);
-            prf.squeeze(&mut buf);
-            poly.cbd2(&buf);
-        }
+        self.getnoise_x4::<{ ETA2 * KYBER_N / 4 }>(seed, nonce, KyberPoly::cbd2);
     }
 
     #[inline] // more possibilities for code with constant d to be optimized?
@@ -267,4 +280,327 @@ impl<const K: usize> DilithiumPolyVec<K> {
         }
         pv
     }
+
+    /// Reduce all coefficients to representatives in `[-6283009, 6283007]`
+    pub fn reduce32(&mut self) {
+        for poly in self.0.iter_mut() {
+            poly.reduce32();
+        }
+    }
+
+    /// Add Q to all negative coefficients
+    pub fn caddq(&mut self) {
+        for poly in self.0.iter_mut() {
+            poly.caddq();
+        }
+    }
+
+    /// Multiply all coefficients by `2^D` without modular reduction
+    pub fn shiftl(&mut self) {
+        for poly in self.0.iter_mut() {
+            poly.shiftl();
+        }
+    }
+
+    /// Pointwise (Montgomery) multiplication of each polynomial with polynomial `c` in NTT domain
+    pub fn pointwise_poly(&self, c: &DilithiumPoly, r: &mut Self) {
+        for (poly, r) in self.0.iter().zip(r.0.iter_mut()) {
+            c.pointwise(poly, r);
+        }
+    }
+
+    /// Returns `true` if the infinity norm of any polynomial is not strictly smaller than `bound`
+    /// Exits early only at polynomial granularity, see [`DilithiumPoly::chknorm`].
+    pub fn chknorm(&self, bound: i32) -> bool {
+        self.0.iter().any(|poly| poly.chknorm(bound))
+    }
+
+    /// Split each polynomial into high bits (written back to `self`) and low bits `v0` using `Power2Round`
+    pub fn power2round(&mut self, v0: &mut Self) {
+        for (v, v0) in self.0.iter_mut().zip(v0.0.iter_mut()) {
+            v.power2round(v0);
+        }
+    }
+
+    /// Split each polynomial into high bits (written back to `self`) and low bits `v0` using `Decompose`
+    pub fn decompose<const GAMMA2: i32>(&mut self, v0: &mut Self) {
+        for (v, v0) in self.0.iter_mut().zip(v0.0.iter_mut()) {
+            v.decompose::<GAMMA2>(v0);
+        }
+    }
+
+    /// Compute hint vector from low bits `v0` and high bits `v1`
+    /// Returns the number of 1 coefficients
+    pub fn make_hint<const GAMMA2: i32>(&mut self, v0: &Self, v1: &Self) -> usize {
+        self.0
+            .iter_mut()
+            .zip(v0.0.iter())
+            .zip(v1.0.iter())
+            .map(|((h, v0), v1)| h.make_hint::<GAMMA2>(v0, v1))
+            .sum()
+    }
+
+    /// Replace each polynomial with its high bits corrected according to the hint vector `h`
+    pub fn use_hint<const GAMMA2: i32>(&mut self, h: &Self) {
+        for (v, h) in self.0.iter_mut().zip(h.0.iter()) {
+            v.use_hint::<GAMMA2>(h);
+        }
+    }
+
+    /// Sample vector of polynomials with coefficients in `[-ETA, ETA]`, the i-th polynomial using `nonce + i`
+    pub fn uniform_eta<const ETA: usize>(
+        &mut self,
+        prf: &mut DilithiumPrf,
+        seed: &[u8; CRH_BYTES],
+        nonce: u16,
+    ) {
+        for (i, poly) in self.0.iter_mut().enumerate() {
+            poly.uniform_eta::<ETA>(prf, seed, nonce.wrapping_add(i as u16));
+        }
+    }
+
+    /// Sample vector of polynomials with coefficients in `[-(GAMMA1 - 1), GAMMA1]`, the i-th polynomial using
+    /// `K * nonce + i`
+    pub fn uniform_gamma1<const Z_BITS: usize>(
+        &mut self,
+        prf: &mut DilithiumPrf,
+        seed: &[u8; CRH_BYTES],
+        nonce: u16,
+    ) {
+        for (i, poly) in self.0.iter_mut().enumerate() {
+            poly.uniform_gamma1::<Z_BITS>(
+                prf,
+                seed,
+                (K as u16).wrapping_mul(nonce).wrapping_add(i as u16),
+            );
+        }
+    }
+
+    /// AES variant of `uniform_eta`
+    pub fn uniform_eta_aes<const ETA: usize>(&mut self, seed: &[u8; CRH_BYTES], nonce: u16) {
+        for (i, poly) in self.0.iter_mut().enumerate() {
+            poly.uniform_eta_aes::<ETA>(seed, nonce.wrapping_add(i as u16));
+        }
+    }
+
+    /// AES variant of `uniform_gamma1`
+    pub fn uniform_gamma1_aes<const Z_BITS: usize>(&mut self, seed: &[u8; CRH_BYTES], nonce: u16) {
+        for (i, poly) in self.0.iter_mut().enumerate() {
+            poly.uniform_gamma1_aes::<Z_BITS>(
+                seed,
+                (K as u16).wrapping_mul(nonce).wrapping_add(i as u16),
+            );
+        }
+    }
+
+    /// Expand row `i` of the matrix `A` of the AES variants, the j-th polynomial using the nonce `256 * i + j`
+    pub fn uniform_aes(&mut self, seed: &[u8; UNIFORM_SEED_BYTES], i: u8) {
+        for (j, poly) in self.0.iter_mut().enumerate() {
+            poly.uniform_aes(seed, u16::from_le_bytes([j as u8, i]));
+        }
+    }
+
+    pub fn pack_t1(&self, r: &mut [[[u8; T1_BITS]; 32]; K]) {
+        for (poly, r) in self.0.iter().zip(r.iter_mut()) {
+            poly.pack_t1(r);
+        }
+    }
+
+    pub fn unpack_t1(&mut self, a: &[[[u8; T1_BITS]; 32]; K]) {
+        for (poly, a) in self.0.iter_mut().zip(a.iter()) {
+            poly.unpack_t1(a);
+        }
+    }
+
+    pub fn pack_t0(&self, r: &mut [[[u8; T0_BITS]; 32]; K]) {
+        for (poly, r) in self.0.iter().zip(r.iter_mut()) {
+            poly.pack_t0(r);
+        }
+    }
+
+    pub fn unpack_t0(&mut self, a: &[[[u8; T0_BITS]; 32]; K]) {
+        for (poly, a) in self.0.iter_mut().zip(a.iter()) {
+            poly.unpack_t0(a);
+        }
+    }
+
+    pub fn pack_eta<const B: usize>(&self, r: &mut [[[u8; B]; 32]; K]) {
+        for (poly, r) in self.0.iter().zip(r.iter_mut()) {
+            poly.pack_eta(r);
+        }
+    }
+
+    pub fn unpack_eta<const B: usize>(&mut self, a: &[[[u8; B]; 32]; K]) {
+        for (poly, a) in self.0.iter_mut().zip(a.iter()) {
+            poly.unpack_eta(a);
+        }
+    }
+
+    pub fn pack_z<const B: usize>(&self, r: &mut [[[u8; B]; 32]; K]) {
+        for (poly, r) in self.0.iter().zip(r.iter_mut()) {
+            poly.pack_z(r);
+        }
+    }
+
+    pub fn unpack_z<const B: usize>(&mut self, a: &[[[u8; B]; 32]; K]) {
+        for (poly, a) in self.0.iter_mut().zip(a.iter()) {
+            poly.unpack_z(a);
+        }
+    }
+
+    pub fn pack_w1<const B: usize>(&self, r: &mut [[[u8; B]; 32]; K]) {
+        for (poly, r) in self.0.iter().zip(r.iter_mut()) {
+            poly.pack_w1(r);
+        }
+    }
+
+    /// Encode hint vector with 0/1 coefficients as a list of at most `OMEGA` coefficient indices
+    /// (`idx`) followed by the cumulative number of indices after each polynomial (`cnt`).
+    /// Panics if the total weight of the hint exceeds `OMEGA`.
+    pub fn pack_hint<const OMEGA: usize>(&self, idx: &mut [u8; OMEGA], cnt: &mut [u8; K]) {
+        idx.fill(0);
+        let mut k = 0;
+        for (poly, cnt) in self.0.iter().zip(cnt.iter_mut()) {
+            k += poly.pack_hint(&mut idx[k..]);
+            *cnt = k as u8;
+        }
+    }
+
+    /// Decode hint vector packed by `pack_hint`.
+    /// Returns `false` if the encoding is malformed, i.e., counts exceeding `OMEGA` or decreasing,
+    /// indices not strictly increasing within a polynomial, or unused index bytes not being zero.
+    pub fn unpack_hint<const OMEGA: usize>(&mut self, idx: &[u8; OMEGA], cnt: &[u8; K]) -> bool {
+        let mut k = 0;
+        for (poly, &cnt) in self.0.iter_mut().zip(cnt.iter()) {
+            let cnt = cnt as usize;
+            if cnt < k || cnt > OMEGA || !poly.unpack_hint(&idx[k..cnt]) {
+                return false;
+            }
+            k = cnt;
+        }
+        // Extra indices are zero for strong unforgeability
+        idx[k..].iter().all(|&b| b == 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    const OMEGA: usize = 80;
+
+    fn random_hint<const K: usize>(weight: usize) -> DilithiumPolyVec<K> {
+        let mut rng = rand::thread_rng();
+        let mut h = DilithiumPolyVec::<K>::default();
+        for _ in 0..weight {
+            h[rng.gen_range(0..K)][rng.gen_range(0..DilithiumPoly::N)].0 = 1;
+        }
+        h
+    }
+
+    fn kyber_getnoise_x4<const K: usize>() {
+        let mut rng = rand::thread_rng();
+        let mut prf = crate::kyber::Prf::default();
+        let mut seed = [0u8; NOISE_SEED_BYTES];
+        let mut v = KyberPolyVec::<K>::default();
+        let mut poly = KyberPoly::default();
+
+        for _ in 0..20 {
+            rng.fill(&mut seed);
+            let nonce = rng.gen();
+            v.getnoise_eta1(&seed, nonce);
+            for i in 0..K {
+                poly.getnoise_eta1::<K>(&mut prf, &seed, nonce.wrapping_add(i as u8));
+                assert_eq!(poly, v[i]);
+            }
+            v.getnoise_eta2(&seed, nonce);
+            for i in 0..K {
+                poly.getnoise_eta2(&mut prf, &seed, nonce.wrapping_add(i as u8));
+                assert_eq!(poly, v[i]);
+            }
+        }
+    }
+
+    #[test]
+    fn kyber_getnoise_x4_vs_single() {
+        kyber_getnoise_x4::<2>();
+        kyber_getnoise_x4::<3>();
+        kyber_getnoise_x4::<4>();
+    }
+
+    #[test]
+    fn dilithium_chknorm_bounds() {
+        let mut rng = rand::thread_rng();
+        let bound = (1 << 17) - 78;
+        for _ in 0..100 {
+            let mut v = DilithiumPolyVec::<4>::default();
+            for poly in v.0.iter_mut() {
+                for c in poly.as_mut() {
+                    c.0 = rng.gen_range(-bound + 1..bound);
+                }
+            }
+            assert!(!v.chknorm(bound));
+
+            let (i, j) = (rng.gen_range(0..4), rng.gen_range(0..DilithiumPoly::N));
+            v[i][j].0 = if rng.gen() { bound } else { -bound };
+            assert!(v.chknorm(bound));
+            v[i][j].0 = bound - 1;
+            assert!(!v.chknorm(bound));
+        }
+    }
+
+    #[test]
+    fn dilithium_hint_roundtrip() {
+        for _ in 0..1_000 {
+            let h = random_hint::<4>(rand::thread_rng().gen_range(0..=OMEGA));
+            let mut idx = [0u8; OMEGA];
+            let mut cnt = [0u8; 4];
+            h.pack_hint(&mut idx, &mut cnt);
+            let mut h2 = DilithiumPolyVec::<4>::new_random(&mut rand::thread_rng());
+            assert!(h2.unpack_hint(&idx, &cnt));
+            assert_eq!(h.0, h2.0);
+        }
+    }
+
+    #[test]
+    fn dilithium_hint_malformed() {
+        let mut h = DilithiumPolyVec::<4>::default();
+        h[0][3].0 = 1;
+        h[0][7].0 = 1;
+        h[2][1].0 = 1;
+        let mut idx = [0u8; OMEGA];
+        let mut cnt = [0u8; 4];
+        h.pack_hint(&mut idx, &mut cnt);
+        assert_eq!(cnt, [2, 2, 3, 3]);
+        assert_eq!(idx[..3], [3, 7, 1]);
+
+        let mut h2 = DilithiumPolyVec::<4>::default();
+        assert!(h2.unpack_hint(&idx, &cnt));
+
+        // decreasing counts
+        let mut bad_cnt = cnt;
+        bad_cnt[1] = 1;
+        assert!(!h2.unpack_hint(&idx, &bad_cnt));
+
+        // count beyond OMEGA
+        let mut bad_cnt = cnt;
+        bad_cnt[3] = OMEGA as u8 + 1;
+        assert!(!h2.unpack_hint(&idx, &bad_cnt));
+
+        // unordered indices
+        let mut bad_idx = idx;
+        bad_idx.swap(0, 1);
+        assert!(!h2.unpack_hint(&bad_idx, &cnt));
+
+        // repeated index
+        let mut bad_idx = idx;
+        bad_idx[1] = bad_idx[0];
+        assert!(!h2.unpack_hint(&bad_idx, &cnt));
+
+        // non-zero padding
+        let mut bad_idx = idx;
+        bad_idx[OMEGA - 1] = 1;
+        assert!(!h2.unpack_hint(&bad_idx, &cnt));
+    }
 }
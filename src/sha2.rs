@@ -0,0 +1,271 @@
+//! SHA-512 (FIPS 180-4), as used by Ed25519 and the pre-hash of the composite signatures
+
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub const SHA512_BLOCK_BYTES: usize = 128;
+pub const SHA512_DIGEST_BYTES: usize = 64;
+
+#[rustfmt::skip]
+const K: [u64; 80] = [
+    0x428a2f98d728ae22,
+    0x7137449123ef65cd,
+    0xb5c0fbcfec4d3b2f,
+    0xe9b5dba58189dbbc,
+    0x3956c25bf348b538,
+    0x59f111f1b605d019,
+    0x923f82a4af194f9b,
+    0xab1c5ed5da6d8118,
+    0xd807aa98a3030242,
+    0x12835b0145706fbe,
+    0x243185be4ee4b28c,
+    0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f,
+    0x80deb1fe3b1696b1,
+    0x9bdc06a725c71235,
+    0xc19bf174cf692694,
+    0xe49b69c19ef14ad2,
+    0xefbe4786384f25e3,
+    0x0fc19dc68b8cd5b5,
+    0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275,
+    0x4a7484aa6ea6e483,
+    0x5cb0a9dcbd41fbd4,
+    0x76f988da831153b5,
+    0x983e5152ee66dfab,
+    0xa831c66d2db43210,
+    0xb00327c898fb213f,
+    0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2,
+    0xd5a79147930aa725,
+    0x06ca6351e003826f,
+    0x142929670a0e6e70,
+    0x27b70a8546d22ffc,
+    0x2e1b21385c26c926,
+    0x4d2c6dfc5ac42aed,
+    0x53380d139d95b3df,
+    0x650a73548baf63de,
+    0x766a0abb3c77b2a8,
+    0x81c2c92e47edaee6,
+    0x92722c851482353b,
+    0xa2bfe8a14cf10364,
+    0xa81a664bbc423001,
+    0xc24b8b70d0f89791,
+    0xc76c51a30654be30,
+    0xd192e819d6ef5218,
+    0xd69906245565a910,
+    0xf40e35855771202a,
+    0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8,
+    0x1e376c085141ab53,
+    0x2748774cdf8eeb99,
+    0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63,
+    0x4ed8aa4ae3418acb,
+    0x5b9cca4f7763e373,
+    0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc,
+    0x78a5636f43172f60,
+    0x84c87814a1f0ab72,
+    0x8cc702081a6439ec,
+    0x90befffa23631e28,
+    0xa4506cebde82bde9,
+    0xbef9a3f7b2c67915,
+    0xc67178f2e372532b,
+    0xca273eceea26619c,
+    0xd186b8c721c0c207,
+    0xeada7dd6cde0eb1e,
+    0xf57d4f7fee6ed178,
+    0x06f067aa72176fba,
+    0x0a637dc5a2c898a6,
+    0x113f9804bef90dae,
+    0x1b710b35131c471b,
+    0x28db77f523047d84,
+    0x32caab7b40c72493,
+    0x3c9ebe0a15c9bebc,
+    0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6,
+    0x597f299cfc657e2a,
+    0x5fcb6fab3ad6faec,
+    0x6c44198c4a475817,
+];
+
+#[rustfmt::skip]
+const IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct Sha512 {
+    state: [u64; 8],
+    buf: [u8; SHA512_BLOCK_BYTES],
+    pos: usize,
+    /// total number of absorbed bytes
+    len: u128,
+}
+
+impl Default for Sha512 {
+    fn default() -> Self {
+        Self {
+            state: IV,
+            buf: [0; SHA512_BLOCK_BYTES],
+            pos: 0,
+            len: 0,
+        }
+    }
+}
+
+impl Sha512 {
+    #[inline]
+    fn compress(state: &mut [u64; 8], block: &[u8; SHA512_BLOCK_BYTES]) {
+        let mut w = [0u64; 80];
+        for (w, chunk) in w.iter_mut().zip(block.chunks_exact(8)) {
+            *w = u64::from_be_bytes(chunk.try_into().unwrap());
+        }
+        for t in 16..80 {
+            let s0 = w[t - 15].rotate_right(1) ^ w[t - 15].rotate_right(8) ^ (w[t - 15] >> 7);
+            let s1 = w[t - 2].rotate_right(19) ^ w[t - 2].rotate_right(61) ^ (w[t - 2] >> 6);
+            w[t] = w[t - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[t - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+        for (&k, &w) in K.iter().zip(w.iter()) {
+            let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(k)
+                .wrapping_add(w);
+            let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *s = s.wrapping_add(v);
+        }
+        w.zeroize();
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.len += data.len() as u128;
+        let mut data = data;
+        while !data.is_empty() {
+            let n = data.len().min(SHA512_BLOCK_BYTES - self.pos);
+            let (chunk, rest) = data.split_at(n);
+            self.buf[self.pos..self.pos + n].copy_from_slice(chunk);
+            self.pos += n;
+            data = rest;
+            if self.pos == SHA512_BLOCK_BYTES {
+                Self::compress(&mut self.state, &self.buf);
+                self.pos = 0;
+            }
+        }
+    }
+
+    /// Write the digest to `out` and reset to the initial state
+    pub fn finalize(&mut self, out: &mut [u8; SHA512_DIGEST_BYTES]) {
+        let bit_len = self.len << 3;
+        self.buf[self.pos] = 0x80;
+        self.buf[self.pos + 1..].fill(0);
+        if self.pos + 1 > SHA512_BLOCK_BYTES - 16 {
+            Self::compress(&mut self.state, &self.buf);
+            self.buf.fill(0);
+        }
+        self.buf[SHA512_BLOCK_BYTES - 16..].copy_from_slice(&bit_len.to_be_bytes());
+        Self::compress(&mut self.state, &self.buf);
+
+        for (chunk, s) in out.chunks_exact_mut(8).zip(self.state.iter()) {
+            chunk.copy_from_slice(&s.to_be_bytes());
+        }
+        *self = Self::default();
+    }
+
+    /// Digest of the concatenation of all `parts`
+    pub fn digest_parts(parts: &[&[u8]], out: &mut [u8; SHA512_DIGEST_BYTES]) {
+        let mut sha = Self::default();
+        for part in parts {
+            sha.update(part);
+        }
+        sha.finalize(out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn sha512_fips180() {
+        let mut digest = [0u8; SHA512_DIGEST_BYTES];
+
+        Sha512::digest_parts(&[], &mut digest);
+        assert_eq!(
+            digest,
+            hex!(
+                "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce"
+                "47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e"
+            )
+        );
+
+        Sha512::digest_parts(&[b"abc"], &mut digest);
+        assert_eq!(
+            digest,
+            hex!(
+                "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a"
+                "2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+            )
+        );
+
+        // two-block message, the length field doesn't fit in the first padded block
+        Sha512::digest_parts(
+            &[
+                b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmno",
+                b"ijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu",
+            ],
+            &mut digest,
+        );
+        assert_eq!(
+            digest,
+            hex!(
+                "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018"
+                "501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909"
+            )
+        );
+    }
+
+    #[test]
+    fn sha512_million_a() {
+        let mut sha = Sha512::default();
+        let mut digest = [0u8; SHA512_DIGEST_BYTES];
+        for _ in 0..1000 {
+            sha.update(&[b'a'; 1000]);
+        }
+        sha.finalize(&mut digest);
+        assert_eq!(
+            digest,
+            hex!(
+                "e718483d0ce769644e2e42c7bc15b4638e1f98b13b2044285632a803afa973eb"
+                "de0ff244877ea60a4cb0432ce577c31beb009c5c2c49aa2e4eadb217ad8cc09b"
+            )
+        );
+    }
+}
@@ -0,0 +1,157 @@
+#![cfg(test)]
+
+use crystals_cref::{dilithium as cref, randombytes};
+use krystals::dilithium::*;
+use rand::{Rng, RngCore};
+
+const NUM_TESTS: usize = 100;
+
+/// Verification verdict of the Rust implementation for an encoded signature
+fn verify_bytes<
+    const K: usize,
+    const L: usize,
+    const ETA_BITS: usize,
+    const Z_BITS: usize,
+    const W1_BITS: usize,
+    const OMEGA: usize,
+    const AES: bool,
+>(
+    msg: &[u8],
+    sig_bytes: &[u8],
+    pk: &PublicKey<K, AES>,
+) -> bool {
+    Signature::<K, L, Z_BITS, OMEGA, AES>::from_bytes(sig_bytes).map_or(false, |sig| {
+        Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::verify(msg, &sig, pk).is_ok()
+    })
+}
+
+/// Compare keypairs, signatures and verification verdicts with the C reference implementation, built with
+/// `DILITHIUM_USE_AES` for the AES variants
+fn test_vs_ref<
+    const K: usize,
+    const L: usize,
+    const ETA_BITS: usize,
+    const Z_BITS: usize,
+    const W1_BITS: usize,
+    const OMEGA: usize,
+    const AES: bool,
+>() {
+    let mut rng = rand::thread_rng();
+
+    let mut sk = SecretKey::<K, L, ETA_BITS, AES>::default();
+    let mut pk = PublicKey::<K, AES>::default();
+    let mut sig = Signature::<K, L, Z_BITS, OMEGA, AES>::default();
+
+    let mut pk_bytes = vec![0u8; PublicKey::<K, AES>::BYTES];
+    let mut sk_bytes = vec![0u8; SecretKey::<K, L, ETA_BITS, AES>::BYTES];
+    let mut sig_bytes = vec![0u8; Signature::<K, L, Z_BITS, OMEGA, AES>::BYTES];
+
+    let mut pk_ref = vec![0u8; PublicKey::<K, AES>::BYTES];
+    let mut sk_ref = vec![0u8; SecretKey::<K, L, ETA_BITS, AES>::BYTES];
+    let mut sig_ref = vec![0u8; Signature::<K, L, Z_BITS, OMEGA, AES>::BYTES];
+
+    for test in 0..NUM_TESTS {
+        let seed: [u8; SEED_BYTES] = rng.gen();
+        randombytes::randombytes_push_bytes(&seed);
+        cref::crypto_sign_keypair::<K, AES>(&mut pk_ref, &mut sk_ref);
+
+        Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::keypair(&seed, &mut sk, &mut pk);
+        pk.write_bytes(&mut pk_bytes);
+        sk.write_bytes(&mut sk_bytes);
+        assert_eq!(
+            pk_bytes, pk_ref,
+            "PK failed K={K} AES={AES} test#={test} seed={seed:?}"
+        );
+        assert_eq!(
+            sk_bytes, sk_ref,
+            "SK failed K={K} AES={AES} test#={test} seed={seed:?}"
+        );
+
+        let mut msg = vec![0u8; rng.gen_range(0..256)];
+        rng.fill_bytes(&mut msg);
+
+        Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::sign(&msg, &sk, &mut sig);
+        sig.write_bytes(&mut sig_bytes);
+        let siglen = cref::crypto_sign_signature::<K, AES>(&mut sig_ref, &msg, &sk_ref);
+        assert_eq!(siglen, sig_bytes.len());
+        assert_eq!(
+            sig_bytes, sig_ref,
+            "Signature failed K={K} AES={AES} test#={test}"
+        );
+
+        let mut sm = vec![0u8; sig_bytes.len() + msg.len()];
+        let smlen = cref::crypto_sign::<K, AES>(&mut sm, &msg, &sk_ref);
+        assert_eq!(smlen, sm.len());
+        assert_eq!(sm[..sig_bytes.len()], sig_bytes);
+        assert_eq!(sm[sig_bytes.len()..], msg);
+
+        let mut m = vec![0u8; sm.len()];
+        assert_eq!(
+            cref::crypto_sign_open::<K, AES>(&mut m, &sm, &pk_ref),
+            Some(msg.len())
+        );
+        assert_eq!(m[..msg.len()], msg);
+
+        // verdicts on valid, tampered and mismatched inputs must agree
+        let pk_dec = PublicKey::<K, AES>::from_bytes(&pk_ref).expect("Public key decoding");
+        assert_eq!(pk_dec, pk);
+        let mut tampered_sig = sig_bytes.clone();
+        tampered_sig[rng.gen_range(0..sig_bytes.len())] ^= 1 << rng.gen_range(0..8);
+        let mut tampered_msg = msg.clone();
+        tampered_msg.push(rng.gen());
+        for (msg, sig_bytes, valid) in [
+            (&msg, &sig_bytes, true),
+            (&msg, &tampered_sig, false),
+            (&tampered_msg, &sig_bytes, false),
+        ] {
+            let verdict = verify_bytes::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>(
+                msg, sig_bytes, &pk_dec,
+            );
+            let verdict_ref = cref::crypto_sign_verify::<K, AES>(sig_bytes, msg, &pk_ref);
+            assert_eq!(
+                verdict, verdict_ref,
+                "Verdict mismatch K={K} AES={AES} test#={test}"
+            );
+            assert_eq!(
+                verdict, valid,
+                "Unexpected verdict K={K} AES={AES} test#={test}"
+            );
+        }
+    }
+}
+
+#[test]
+#[cfg(not(miri))]
+fn dilithium2_vs_ref() {
+    test_vs_ref::<4, 4, 3, 18, 6, 80, false>();
+}
+
+#[test]
+#[cfg(not(miri))]
+fn dilithium3_vs_ref() {
+    test_vs_ref::<6, 5, 4, 20, 4, 55, false>();
+}
+
+#[test]
+#[cfg(not(miri))]
+fn dilithium5_vs_ref() {
+    test_vs_ref::<8, 7, 3, 20, 4, 75, false>();
+}
+
+#[test]
+#[cfg(not(miri))]
+fn dilithium2aes_vs_ref() {
+    test_vs_ref::<4, 4, 3, 18, 6, 80, true>();
+}
+
+#[test]
+#[cfg(not(miri))]
+fn dilithium3aes_vs_ref() {
+    test_vs_ref::<6, 5, 4, 20, 4, 55, true>();
+}
+
+#[test]
+#[cfg(not(miri))]
+fn dilithium5aes_vs_ref() {
+    test_vs_ref::<8, 7, 3, 20, 4, 75, true>();
+}
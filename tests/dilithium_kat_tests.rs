@@ -0,0 +1,322 @@
+#![cfg(test)]
+#![allow(dead_code)]
+
+mod kat;
+
+use kat::*;
+use krystals::dilithium::*;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+fn get_rsp_iter(name: &str) -> SignKatFile {
+    let kat_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("KATs")
+        .join(format!("{}.rsp", name));
+    println!("kat path: {:?}", kat_path);
+    SignKatFile::new(&kat_path).expect("Unable to load KAT file")
+}
+
+/// Run the tests of `PQCgenKAT_sign`: each key pair is generated from the first `SEED_BYTES` drawn from the
+/// CTR_DRBG seeded with the test seed, and the message is signed deterministically
+fn generate_kats<
+    const K: usize,
+    const L: usize,
+    const ETA_BITS: usize,
+    const Z_BITS: usize,
+    const W1_BITS: usize,
+    const OMEGA: usize,
+    const AES: bool,
+>() -> Vec<SignKAT> {
+    let mut sk = SecretKey::<K, L, ETA_BITS, AES>::default();
+    let mut pk = PublicKey::<K, AES>::default();
+    let mut sig = Signature::<K, L, Z_BITS, OMEGA, AES>::default();
+
+    let mut kats = SignKAT::seeds();
+    for kat in kats.iter_mut() {
+        let mut seed = [0u8; SEED_BYTES];
+        kat_rng(&kat.seed).fill_bytes(&mut seed);
+        Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::keypair(&seed, &mut sk, &mut pk);
+        Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::sign(&kat.msg, &sk, &mut sig);
+
+        kat.pk = vec![0u8; PublicKey::<K, AES>::BYTES];
+        pk.write_bytes(&mut kat.pk);
+        kat.sk = vec![0u8; SecretKey::<K, L, ETA_BITS, AES>::BYTES];
+        sk.write_bytes(&mut kat.sk);
+        kat.sm = vec![0u8; Signature::<K, L, Z_BITS, OMEGA, AES>::BYTES];
+        sig.write_bytes(&mut kat.sm);
+        kat.sm.extend_from_slice(&kat.msg);
+
+        assert_eq!(
+            Dilithium::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>::verify(&kat.msg, &sig, &pk),
+            Ok(()),
+            "Verification failure (count = {})",
+            kat.count
+        );
+    }
+    kats
+}
+
+/// The `.rsp` file of the generated tests, as written by `PQCgenKAT_sign`
+fn rsp<
+    const K: usize,
+    const L: usize,
+    const ETA_BITS: usize,
+    const Z_BITS: usize,
+    const W1_BITS: usize,
+    const OMEGA: usize,
+    const AES: bool,
+>(
+    algorithm: &str,
+) -> Vec<u8> {
+    let kats = generate_kats::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>();
+    let mut rsp = Vec::new();
+    write_rsp(&mut rsp, algorithm, &kats, SignKAT::write).unwrap();
+    rsp
+}
+
+/// SHA-256 digest of the generated `.rsp` file
+fn rsp_digest<
+    const K: usize,
+    const L: usize,
+    const ETA_BITS: usize,
+    const Z_BITS: usize,
+    const W1_BITS: usize,
+    const OMEGA: usize,
+    const AES: bool,
+>(
+    algorithm: &str,
+) -> String {
+    let rsp = rsp::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>(algorithm);
+    Sha256::digest(&rsp)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Compare the generated `.rsp` file with the digest of the reference output in `tests/KATs/SHA256SUMS`,
+/// written by `make -C tests/KATs SHA256SUMS`
+fn test_dilithium_kat_digest<
+    const K: usize,
+    const L: usize,
+    const ETA_BITS: usize,
+    const Z_BITS: usize,
+    const W1_BITS: usize,
+    const OMEGA: usize,
+    const AES: bool,
+>(
+    name: &str,
+    algorithm: &str,
+) {
+    let sums_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("KATs")
+        .join("SHA256SUMS");
+    let sums = std::fs::read_to_string(sums_path).expect("Unable to load SHA256SUMS");
+    assert_eq!(
+        rsp_digest::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>(algorithm),
+        expected_sha256(&sums, &format!("{}.rsp", name))
+    );
+}
+
+/// Compare the generated `.rsp` file with the digest of a previous output of this crate in
+/// `tests/KATs/regression.sha256`
+///
+/// This only detects changes of the output, it is not a check against the reference implementation.
+fn test_dilithium_rsp_regression<
+    const K: usize,
+    const L: usize,
+    const ETA_BITS: usize,
+    const Z_BITS: usize,
+    const W1_BITS: usize,
+    const OMEGA: usize,
+    const AES: bool,
+>(
+    name: &str,
+    algorithm: &str,
+) {
+    assert_eq!(
+        rsp_digest::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>(algorithm),
+        expected_sha256(
+            include_str!("KATs/regression.sha256"),
+            &format!("{}.rsp", name)
+        )
+    );
+}
+
+/// Compare the generated tests with the `.rsp` file `name` of the reference implementation, entry by entry
+fn test_dilithium_kat<
+    const K: usize,
+    const L: usize,
+    const ETA_BITS: usize,
+    const Z_BITS: usize,
+    const W1_BITS: usize,
+    const OMEGA: usize,
+    const AES: bool,
+>(
+    name: &str,
+) {
+    let kats = generate_kats::<K, L, ETA_BITS, Z_BITS, W1_BITS, OMEGA, AES>();
+    let mut num_kats = 0;
+    for (known, kat) in get_rsp_iter(name).zip(kats.iter()) {
+        assert_eq!(
+            known.seed, kat.seed,
+            "Seed mismatch (count = {})",
+            known.count
+        );
+        assert_eq!(
+            known.msg, kat.msg,
+            "Message mismatch (count = {})",
+            known.count
+        );
+        assert_eq!(
+            known.pk, kat.pk,
+            "Public key mismatch (count = {})",
+            known.count
+        );
+        assert_eq!(
+            known.sk, kat.sk,
+            "Secret key mismatch (count = {})",
+            known.count
+        );
+        assert_eq!(
+            known.sm, kat.sm,
+            "Signature mismatch (count = {})",
+            known.count
+        );
+        num_kats += 1;
+    }
+    assert_eq!(num_kats, KAT_COUNT, "Incomplete KAT file");
+}
+
+#[test]
+fn dilithium2_rsp_regression() {
+    test_dilithium_rsp_regression::<4, 4, 3, 18, 6, 80, false>("dilithium2", "Dilithium2");
+}
+
+#[test]
+fn dilithium3_rsp_regression() {
+    test_dilithium_rsp_regression::<6, 5, 4, 20, 4, 55, false>("dilithium3", "Dilithium3");
+}
+
+#[test]
+fn dilithium5_rsp_regression() {
+    test_dilithium_rsp_regression::<8, 7, 3, 20, 4, 75, false>("dilithium5", "Dilithium5");
+}
+
+#[test]
+fn dilithium2aes_rsp_regression() {
+    test_dilithium_rsp_regression::<4, 4, 3, 18, 6, 80, true>("dilithium2aes", "Dilithium2-AES");
+}
+
+#[test]
+fn dilithium3aes_rsp_regression() {
+    test_dilithium_rsp_regression::<6, 5, 4, 20, 4, 55, true>("dilithium3aes", "Dilithium3-AES");
+}
+
+#[test]
+fn dilithium5aes_rsp_regression() {
+    test_dilithium_rsp_regression::<8, 7, 3, 20, 4, 75, true>("dilithium5aes", "Dilithium5-AES");
+}
+
+#[test]
+#[ignore = "requires tests/KATs/SHA256SUMS, generated with `make -C tests/KATs SHA256SUMS`"]
+fn dilithium2_kat_digest() {
+    test_dilithium_kat_digest::<4, 4, 3, 18, 6, 80, false>("dilithium2", "Dilithium2");
+}
+
+#[test]
+#[ignore = "requires tests/KATs/SHA256SUMS, generated with `make -C tests/KATs SHA256SUMS`"]
+fn dilithium3_kat_digest() {
+    test_dilithium_kat_digest::<6, 5, 4, 20, 4, 55, false>("dilithium3", "Dilithium3");
+}
+
+#[test]
+#[ignore = "requires tests/KATs/SHA256SUMS, generated with `make -C tests/KATs SHA256SUMS`"]
+fn dilithium5_kat_digest() {
+    test_dilithium_kat_digest::<8, 7, 3, 20, 4, 75, false>("dilithium5", "Dilithium5");
+}
+
+#[test]
+#[ignore = "requires tests/KATs/SHA256SUMS, generated with `make -C tests/KATs SHA256SUMS`"]
+fn dilithium2aes_kat_digest() {
+    test_dilithium_kat_digest::<4, 4, 3, 18, 6, 80, true>("dilithium2aes", "Dilithium2-AES");
+}
+
+#[test]
+#[ignore = "requires tests/KATs/SHA256SUMS, generated with `make -C tests/KATs SHA256SUMS`"]
+fn dilithium3aes_kat_digest() {
+    test_dilithium_kat_digest::<6, 5, 4, 20, 4, 55, true>("dilithium3aes", "Dilithium3-AES");
+}
+
+#[test]
+#[ignore = "requires tests/KATs/SHA256SUMS, generated with `make -C tests/KATs SHA256SUMS`"]
+fn dilithium5aes_kat_digest() {
+    test_dilithium_kat_digest::<8, 7, 3, 20, 4, 75, true>("dilithium5aes", "Dilithium5-AES");
+}
+
+#[test]
+#[ignore = "requires tests/KATs/dilithium2.rsp, generated with `make -C tests/KATs`"]
+fn dilithium2_kat() {
+    test_dilithium_kat::<4, 4, 3, 18, 6, 80, false>("dilithium2");
+}
+
+#[test]
+#[ignore = "requires tests/KATs/dilithium3.rsp, generated with `make -C tests/KATs`"]
+fn dilithium3_kat() {
+    test_dilithium_kat::<6, 5, 4, 20, 4, 55, false>("dilithium3");
+}
+
+#[test]
+#[ignore = "requires tests/KATs/dilithium5.rsp, generated with `make -C tests/KATs`"]
+fn dilithium5_kat() {
+    test_dilithium_kat::<8, 7, 3, 20, 4, 75, false>("dilithium5");
+}
+
+#[test]
+#[ignore = "requires tests/KATs/dilithium2aes.rsp, generated with `make -C tests/KATs`"]
+fn dilithium2aes_kat() {
+    test_dilithium_kat::<4, 4, 3, 18, 6, 80, true>("dilithium2aes");
+}
+
+#[test]
+#[ignore = "requires tests/KATs/dilithium3aes.rsp, generated with `make -C tests/KATs`"]
+fn dilithium3aes_kat() {
+    test_dilithium_kat::<6, 5, 4, 20, 4, 55, true>("dilithium3aes");
+}
+
+#[test]
+#[ignore = "requires tests/KATs/dilithium5aes.rsp, generated with `make -C tests/KATs`"]
+fn dilithium5aes_kat() {
+    test_dilithium_kat::<8, 7, 3, 20, 4, 75, true>("dilithium5aes");
+}
+
+/// Write the generated `.rsp` files to `target/KATs`, e.g. to diff them with those of `make -C tests/KATs`
+#[test]
+#[ignore = "writes target/KATs/dilithium*.rsp"]
+fn write_dilithium_kats() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("target")
+        .join("KATs");
+    std::fs::create_dir_all(&dir).unwrap();
+    for (name, rsp) in [
+        ("dilithium2", rsp::<4, 4, 3, 18, 6, 80, false>("Dilithium2")),
+        ("dilithium3", rsp::<6, 5, 4, 20, 4, 55, false>("Dilithium3")),
+        ("dilithium5", rsp::<8, 7, 3, 20, 4, 75, false>("Dilithium5")),
+        (
+            "dilithium2aes",
+            rsp::<4, 4, 3, 18, 6, 80, true>("Dilithium2-AES"),
+        ),
+        (
+            "dilithium3aes",
+            rsp::<6, 5, 4, 20, 4, 55, true>("Dilithium3-AES"),
+        ),
+        (
+            "dilithium5aes",
+            rsp::<8, 7, 3, 20, 4, 75, true>("Dilithium5-AES"),
+        ),
+    ] {
+        std::fs::write(dir.join(format!("{}.rsp", name)), rsp).unwrap();
+    }
+}
@@ -1,5 +1,7 @@
+use krystals::drbg::{Aes256CtrDrbg, CTR_DRBG_SEED_BYTES};
+use rand::RngCore;
 use std::fs::File;
-use std::io::{self, BufRead, BufReader, Lines};
+use std::io::{self, BufRead, BufReader, Lines, Write};
 use std::path::Path;
 
 fn decode_hex(s: &str) -> Vec<u8> {
@@ -9,79 +11,212 @@ fn decode_hex(s: &str) -> Vec<u8> {
         .collect::<Vec<u8>>()
 }
 
-#[derive(Debug)]
-pub struct KAT {
-    pub keygen_rand0: Vec<u8>,
-    pub keygen_rand1: Vec<u8>,
-    pub encap_rand: Vec<u8>,
+/// Upper case hex, as written by `fprintBstr` of the NIST KAT generators
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+/// Number of tests in the `.rsp` files of the NIST KAT generators
+pub const KAT_COUNT: usize = 100;
+
+/// Test seeds of `PQCgenKAT_sign` and `PQCgenKAT_kem`: a CTR_DRBG instantiated with the bytes `0..48` draws
+/// the 48-byte seed of each test, followed by its `33 * (count + 1)` byte message in the case of signatures.
+/// A test runs the scheme with the CTR_DRBG reseeded with its seed as `randombytes`, see [`kat_rng`].
+fn kat_seeds(count: usize, with_msg: bool) -> Vec<(Vec<u8>, Vec<u8>)> {
+    let mut entropy_input = [0u8; CTR_DRBG_SEED_BYTES];
+    for (i, e) in entropy_input.iter_mut().enumerate() {
+        *e = i as u8;
+    }
+    let mut drbg = Aes256CtrDrbg::new(&entropy_input);
+    (0..count)
+        .map(|i| {
+            let mut seed = vec![0u8; CTR_DRBG_SEED_BYTES];
+            drbg.fill_bytes(&mut seed);
+            let mut msg = vec![0u8; if with_msg { 33 * (i + 1) } else { 0 }];
+            drbg.fill_bytes(&mut msg);
+            (seed, msg)
+        })
+        .collect()
+}
+
+/// `randombytes` of a test with seed `seed`
+pub fn kat_rng(seed: &[u8]) -> Aes256CtrDrbg {
+    Aes256CtrDrbg::new(seed.try_into().expect("KAT seed length"))
+}
+
+/// Next block of `key = value` lines, skipping leading comments and empty lines
+fn next_entry(lines: &mut Lines<BufReader<File>>) -> Option<Vec<(String, String)>> {
+    let mut entry = Vec::new();
+    for line in lines {
+        let line = line.expect("Reading KAT file");
+        match line.trim().split_once(" = ") {
+            Some((key, value)) => entry.push((key.to_string(), value.to_string())),
+            None if !entry.is_empty() => break,
+            None => continue,
+        }
+    }
+    (!entry.is_empty()).then(|| entry)
+}
+
+/// Known answer test of a signature scheme, as in the `.rsp` files of the NIST `PQCgenKAT_sign` tool
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SignKAT {
+    pub count: usize,
+    pub seed: Vec<u8>,
+    pub msg: Vec<u8>,
     pub pk: Vec<u8>,
     pub sk: Vec<u8>,
-    pub ct: Vec<u8>,
-    pub ss: Vec<u8>,
+    /// Signature followed by the message
+    pub sm: Vec<u8>,
 }
 
-// Converts string octuples from tvec files into Kat structs
-impl From<&[String]> for KAT {
-    fn from(kat: &[String]) -> Self {
-        // Extract values from key:value lines
-        let values: Vec<String> = kat
-            .iter()
-            .map(|katline| {
-                let val: Vec<&str> = katline.split(": ").collect();
-                if val.len() > 1 {
-                    val[1].into()
-                } else {
-                    val[0].into()
-                }
+impl SignKAT {
+    /// The tests of `PQCgenKAT_sign` with `count`, `seed` and `msg` filled in
+    pub fn seeds() -> Vec<SignKAT> {
+        kat_seeds(KAT_COUNT, true)
+            .into_iter()
+            .enumerate()
+            .map(|(count, (seed, msg))| SignKAT {
+                count,
+                seed,
+                msg,
+                ..Default::default()
             })
-            .collect();
-        // Build KAT from values
-        KAT {
-            keygen_rand0: decode_hex(&values[0]),
-            keygen_rand1: decode_hex(&values[1]),
-            pk: decode_hex(&values[2]),
-            sk: decode_hex(&values[3]),
-            encap_rand: decode_hex(&values[4]),
-            ct: decode_hex(&values[5]),
-            ss: decode_hex(&values[6]),
+            .collect()
+    }
+
+    pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        writeln!(w, "count = {}", self.count)?;
+        writeln!(w, "seed = {}", encode_hex(&self.seed))?;
+        writeln!(w, "mlen = {}", self.msg.len())?;
+        writeln!(w, "msg = {}", encode_hex(&self.msg))?;
+        writeln!(w, "pk = {}", encode_hex(&self.pk))?;
+        writeln!(w, "sk = {}", encode_hex(&self.sk))?;
+        writeln!(w, "smlen = {}", self.sm.len())?;
+        writeln!(w, "sm = {}", encode_hex(&self.sm))?;
+        writeln!(w)
+    }
+}
+
+pub struct SignKatFile {
+    pub lines: Lines<BufReader<File>>,
+}
+
+impl Iterator for SignKatFile {
+    type Item = SignKAT;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut kat = SignKAT::default();
+        for (key, value) in next_entry(&mut self.lines)? {
+            match key.as_str() {
+                "count" => kat.count = value.parse().expect("KAT count"),
+                "seed" => kat.seed = decode_hex(&value),
+                "msg" => kat.msg = decode_hex(&value),
+                "pk" => kat.pk = decode_hex(&value),
+                "sk" => kat.sk = decode_hex(&value),
+                "sm" => kat.sm = decode_hex(&value),
+                _ => {} // mlen, smlen
+            }
         }
+        Some(kat)
     }
 }
 
-pub fn kyber_k_to_security(kyber_k: usize) -> usize {
-    match kyber_k {
-        2 => 512,
-        3 => 768,
-        4 => 1024,
-        _ => panic!("Unknown Kyber K"),
+impl SignKatFile {
+    pub fn new(path: &Path) -> io::Result<SignKatFile> {
+        File::open(path).map(|f| SignKatFile {
+            lines: BufReader::new(f).lines(),
+        })
+    }
+}
+
+/// Known answer test of a KEM, as in the `.rsp` files of the NIST `PQCgenKAT_kem` tool
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct KemKAT {
+    pub count: usize,
+    pub seed: Vec<u8>,
+    pub pk: Vec<u8>,
+    pub sk: Vec<u8>,
+    pub ct: Vec<u8>,
+    pub ss: Vec<u8>,
+}
+
+impl KemKAT {
+    /// The tests of `PQCgenKAT_kem` with `count` and `seed` filled in
+    pub fn seeds() -> Vec<KemKAT> {
+        kat_seeds(KAT_COUNT, false)
+            .into_iter()
+            .enumerate()
+            .map(|(count, (seed, _))| KemKAT {
+                count,
+                seed,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        writeln!(w, "count = {}", self.count)?;
+        writeln!(w, "seed = {}", encode_hex(&self.seed))?;
+        writeln!(w, "pk = {}", encode_hex(&self.pk))?;
+        writeln!(w, "sk = {}", encode_hex(&self.sk))?;
+        writeln!(w, "ct = {}", encode_hex(&self.ct))?;
+        writeln!(w, "ss = {}", encode_hex(&self.ss))?;
+        writeln!(w)
     }
 }
 
-pub struct KatFile {
+pub struct KemKatFile {
     pub lines: Lines<BufReader<File>>,
 }
 
-impl Iterator for KatFile {
-    type Item = KAT;
+impl Iterator for KemKatFile {
+    type Item = KemKAT;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut v = vec![String::default(); 8];
-        for i in 0..8 {
-            match self.lines.next() {
-                Some(Ok(line)) => v[i] = line,
-                _ => return None,
+        let mut kat = KemKAT::default();
+        for (key, value) in next_entry(&mut self.lines)? {
+            match key.as_str() {
+                "count" => kat.count = value.parse().expect("KAT count"),
+                "seed" => kat.seed = decode_hex(&value),
+                "pk" => kat.pk = decode_hex(&value),
+                "sk" => kat.sk = decode_hex(&value),
+                "ct" => kat.ct = decode_hex(&value),
+                "ss" => kat.ss = decode_hex(&value),
+                _ => {}
             }
         }
-        Some(KAT::from(v.as_slice()))
+        Some(kat)
     }
 }
 
-impl KatFile {
-    pub fn new(path: &Path) -> io::Result<KatFile> {
-        File::open(path).and_then(|f| {
-            Ok(KatFile {
-                lines: BufReader::new(f).lines(),
-            })
+impl KemKatFile {
+    pub fn new(path: &Path) -> io::Result<KemKatFile> {
+        File::open(path).map(|f| KemKatFile {
+            lines: BufReader::new(f).lines(),
         })
     }
 }
+
+/// Write a `.rsp` file with header `# algorithm`, as the NIST KAT generators do
+pub fn write_rsp<W: Write, T>(
+    w: &mut W,
+    algorithm: &str,
+    kats: &[T],
+    write: fn(&T, &mut W) -> io::Result<()>,
+) -> io::Result<()> {
+    writeln!(w, "# {}\n", algorithm)?;
+    for kat in kats {
+        write(kat, w)?;
+    }
+    Ok(())
+}
+
+/// Expected SHA-256 digest of the KAT file `name`, from `sums` in the format of `sha256sum`
+pub fn expected_sha256<'a>(sums: &'a str, name: &str) -> &'a str {
+    sums.lines()
+        .filter_map(|line| line.split_once("  "))
+        .find(|(_, file)| *file == name)
+        .map(|(digest, _)| digest)
+        .unwrap_or_else(|| panic!("No digest of {}", name))
+}
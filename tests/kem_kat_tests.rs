@@ -1,64 +1,88 @@
 #![cfg(test)]
 #![allow(dead_code)]
 
-mod buffer_rng;
 mod kat;
 
-use buffer_rng::BufferRng;
 use kat::*;
+use krystals::kyber::*;
+use rand::RngCore;
 use std::path::Path;
 
-fn get_kats_iter<const KYBER_K: usize>() -> KatFile {
+fn get_rsp_iter(name: &str) -> KemKatFile {
     let kat_path = Path::new(env!("CARGO_MANIFEST_DIR"))
         .join("tests")
         .join("KATs")
-        .join(format!("kyber_{}.kat", kyber_k_to_security(KYBER_K)));
+        .join(format!("{}.rsp", name));
     println!("kat path: {:?}", kat_path);
-    KatFile::new(&kat_path).expect("Unable to load KAT file")
+    KemKatFile::new(&kat_path).expect("Unable to load KAT file")
 }
 
-fn test_kyber_kem<const KYBER_K: usize>() {
-    let kats = get_kats_iter::<KYBER_K>();
-    let mut rng = BufferRng::default();
-    for known in kats {
-        rng.reset();
-        rng.load_bytes(known.keygen_rand0.as_slice());
-        rng.load_bytes(known.keygen_rand1.as_slice());
-        rng.load_bytes(known.encap_rand.as_slice());
-        // let (pk, sk) = generate_keys::<_, KYBER_K>(&mut rng).expect("msg");
-        // let mut pk_bytes = pk.bytes.flatten().to_vec();
-        // pk_bytes.extend(pk.seed);
-        // assert_eq!(pk_bytes, &known.pk[..], "Public key mismatch");
-        // let mut sk_bytes = sk.cpa_sk.bytes.flatten().to_vec();
-        // let mut spkpk_bytes = sk.pk.bytes.flatten().to_vec();
-        // spkpk_bytes.extend(sk.pk.seed);
-        // sk_bytes.extend(spkpk_bytes);
-        // sk_bytes.extend(sk.h_pk);
-        // sk_bytes.extend(sk.z);
-        // assert_eq!(sk_bytes, &known.sk[..], "Secret key mismatch");
+/// Key pairs of the tests of `PQCgenKAT_kem`: the CTR_DRBG seeded with the test seed yields the entropy of
+/// the IND-CPA key pair, followed by the implicit rejection value `z`
+///
+/// `ct` and `ss` are left empty, as the KEM encapsulation is not implemented yet.
+fn generate_kats<const K: usize>() -> Vec<KemKAT> {
+    let mut kats = KemKAT::seeds();
+    for kat in kats.iter_mut() {
+        let mut rng = kat_rng(&kat.seed);
+        let mut entropy = [0u8; KYBER_SYMBYTES];
+        rng.fill_bytes(&mut entropy);
+        let mut cpa_sk = CPASecretKey::<K>::default();
+        let mut pk = PublicKey::<K>::default();
+        keypair(&entropy, &mut cpa_sk, &mut pk);
+        let mut z = [0u8; KYBER_SSBYTES];
+        rng.fill_bytes(&mut z);
 
-        // // FIXME
-        // const CT_BYTES: usize = 320 + 128;
+        kat.pk = pk.bytes.concat();
+        kat.pk.extend_from_slice(&pk.seed);
+        let sk = SecretKey::new(cpa_sk, pk, z);
+        kat.sk = sk.cpa_sk.bytes().concat();
+        kat.sk.extend_from_slice(&kat.pk);
+        kat.sk.extend_from_slice(&sk.h_pk);
+        kat.sk.extend_from_slice(&sk.z);
+    }
+    kats
+}
 
-        // let (ct, ss) = encapsulate::<_, KYBER_K, CT_BYTES>(&pk, &mut rng).unwrap();
-        // assert_eq!(&ss[..], &known.ss, "Shared secret mismatch");
-        // assert_eq!(&ct[..], &known.ct, "Ciphertext mismatch");
-        //     let decap_result = decapsulate(&ct, &sk);
-        //     assert!(decap_result.is_ok(), "KEM decapsulation failure");
-        //     assert_eq!(&decap_result.unwrap()[..], &known_ss[..], "Shared secret KAT doesn't match")
+/// Compare the generated key pairs with the `.rsp` file `name` of the reference implementation
+fn test_kyber_kat<const K: usize>(name: &str) {
+    let kats = generate_kats::<K>();
+    let mut num_kats = 0;
+    for (known, kat) in get_rsp_iter(name).zip(kats.iter()) {
+        assert_eq!(
+            known.seed, kat.seed,
+            "Seed mismatch (count = {})",
+            known.count
+        );
+        assert_eq!(
+            known.pk, kat.pk,
+            "Public key mismatch (count = {})",
+            known.count
+        );
+        assert_eq!(
+            known.sk, kat.sk,
+            "Secret key mismatch (count = {})",
+            known.count
+        );
+        num_kats += 1;
     }
+    assert_eq!(num_kats, KAT_COUNT, "Incomplete KAT file");
+}
+
+#[test]
+#[ignore = "requires tests/KATs/kyber512.rsp, generated with `make -C tests/KATs`"]
+fn kyber512_kat() {
+    test_kyber_kat::<2>("kyber512");
 }
 
-// all
-// #[test]
-// fn kyber_keypairs_512() {
-//     test_kyber_kem::<2>();
-// }
-// #[test]
-// fn kyber_keypairs_768() {
-//     test_kyber_kem::<3>();
-// }
-// #[test]
-// fn kyber_keypairs_1024() {
-//     test_kyber_kem::<4>();
-// }
+#[test]
+#[ignore = "requires tests/KATs/kyber768.rsp, generated with `make -C tests/KATs`"]
+fn kyber768_kat() {
+    test_kyber_kat::<3>("kyber768");
+}
+
+#[test]
+#[ignore = "requires tests/KATs/kyber1024.rsp, generated with `make -C tests/KATs`"]
+fn kyber1024_kat() {
+    test_kyber_kat::<4>("kyber1024");
+}